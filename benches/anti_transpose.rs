@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lophat::{
+    columns::VecColumn,
+    utils::{anti_transpose, anti_transpose_parallel},
+};
+
+// A pseudo-random upper-triangular matrix, with up to five entries in each column
+fn build_matrix(n_cols: usize) -> Vec<VecColumn> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..n_cols)
+        .map(|j| {
+            let mut entries: Vec<usize> = (0..j.min(5))
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % j as u64) as usize
+                })
+                .collect();
+            entries.sort_unstable();
+            entries.dedup();
+            VecColumn::from((0, entries))
+        })
+        .collect()
+}
+
+fn anti_transposes(c: &mut Criterion) {
+    let matrix = build_matrix(500_000);
+    let mut group = c.benchmark_group("anti_transpose");
+    group.sample_size(10);
+    group.bench_function("serial", |b| b.iter(|| anti_transpose(&matrix)));
+    group.bench_function("parallel", |b| b.iter(|| anti_transpose_parallel(&matrix)));
+    group.finish();
+}
+
+criterion_group!(benches, anti_transposes);
+criterion_main!(benches);
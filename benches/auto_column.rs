@@ -0,0 +1,63 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lophat::{
+    algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm},
+    columns::{AutoColumn, BitSetColumn, Column, VecColumn},
+    options::LoPhatOptions,
+};
+
+// An upper-triangular matrix in which every 10th column is dense and the rest have a couple of entries
+fn build_mixed_matrix(n_cols: usize) -> Vec<Vec<usize>> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..n_cols)
+        .map(|j| {
+            let mut entries: Vec<usize> = if j % 10 == 9 {
+                (0..j).filter(|_| next() & 1 == 0).collect()
+            } else if j > 0 {
+                vec![(next() % j as u64) as usize, j - 1]
+            } else {
+                vec![]
+            };
+            entries.dedup();
+            entries
+        })
+        .collect()
+}
+
+fn decompose<C: Column + 'static>(matrix: &[Vec<usize>]) {
+    let options = LoPhatOptions {
+        clearing: false,
+        ..Default::default()
+    };
+    let cols = matrix.iter().map(|entries| {
+        let mut col = C::new_with_dimension(0);
+        col.add_entries(entries.iter().copied());
+        col
+    });
+    LockFreeAlgorithm::<C>::init(Some(options))
+        .add_cols(cols)
+        .decompose()
+        .diagram();
+}
+
+fn mixed_densities(c: &mut Criterion) {
+    let matrix = build_mixed_matrix(3_000);
+    let mut group = c.benchmark_group("mixed_densities");
+    group.sample_size(10);
+    group.bench_function("VecColumn", |b| b.iter(|| decompose::<VecColumn>(&matrix)));
+    group.bench_function("BitSetColumn", |b| {
+        b.iter(|| decompose::<BitSetColumn>(&matrix))
+    });
+    group.bench_function("AutoColumn", |b| {
+        b.iter(|| decompose::<AutoColumn>(&matrix))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, mixed_densities);
+criterion_main!(benches);
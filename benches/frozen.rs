@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lophat::{
+    algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm, LockFreeDecomposition},
+    columns::VecColumn,
+    utils::CubicalComplexBuilder,
+};
+
+// The cubical complex of a square image with pseudo-random pixel values
+fn build_matrix(side: usize) -> Vec<VecColumn> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let values = (0..side * side)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 1_000) as f64
+        })
+        .collect();
+    CubicalComplexBuilder::new(values, &[side, side]).build().0
+}
+
+fn decompose(matrix: &[VecColumn]) -> LockFreeDecomposition<VecColumn> {
+    LockFreeAlgorithm::init(None)
+        .add_cols(matrix.iter().cloned())
+        .decompose()
+}
+
+// Only reading off the diagram is timed, the decompositions are computed up front
+fn diagram_readoff(c: &mut Criterion) {
+    let matrix = build_matrix(300);
+    let lock_free = decompose(&matrix);
+    let frozen = &decompose(&matrix).freeze();
+    let mut group = c.benchmark_group("diagram_readoff");
+    group.sample_size(10);
+    group.bench_function("LockFreeDecomposition", |b| b.iter(|| lock_free.diagram()));
+    group.bench_function("FrozenDecomposition", |b| b.iter(|| frozen.diagram()));
+    group.finish();
+}
+
+criterion_group!(benches, diagram_readoff);
+criterion_main!(benches);
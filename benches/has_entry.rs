@@ -0,0 +1,30 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lophat::columns::{Column, VecColumn};
+
+// Probes a column with 100,000 entries for present and absent indices, spread across the column
+fn has_entry(c: &mut Criterion) {
+    let col = VecColumn::from((1, (0..100_000).map(|i| 3 * i).collect()));
+    let probes: Vec<usize> = (0..1_000).map(|i| 299 * i).collect();
+    let mut group = c.benchmark_group("has_entry");
+    group.bench_function("binary_search", |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter(|probe| black_box(&col).has_entry(probe))
+                .count()
+        })
+    });
+    // The linear scan previously used by has_entry, for comparison
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter(|&&probe| black_box(&col).entries().any(|entry| entry == probe))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, has_entry);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use lophat::{
+    algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm},
+    columns::{Column, VecColumn},
+    options::{LoPhatOptions, SchedulePolicy},
+};
+
+// An upper-triangular matrix in which every 100th column is dense and the rest are cheap to reduce.
+// Under static chunking, the few threads handed the dense columns do most of the work.
+fn build_skewed_matrix(n_cols: usize) -> Vec<VecColumn> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..n_cols)
+        .map(|j| {
+            let mut col = VecColumn::new_with_dimension(0);
+            if j % 100 == 99 {
+                for i in 0..j {
+                    // xorshift, so that the dense columns differ from each other
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    if state & 1 == 0 {
+                        col.add_entry(i);
+                    }
+                }
+            } else if j > 0 {
+                col.add_entries([j / 2, j - 1].into_iter());
+            }
+            col
+        })
+        .collect()
+}
+
+fn schedule_policies(c: &mut Criterion) {
+    let matrix = build_skewed_matrix(5_000);
+    let mut group = c.benchmark_group("schedule_policy");
+    group.sample_size(10);
+    for schedule in [
+        SchedulePolicy::Static,
+        SchedulePolicy::Guided,
+        SchedulePolicy::Dynamic,
+    ] {
+        let options = LoPhatOptions {
+            schedule,
+            clearing: false,
+            ..Default::default()
+        };
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{schedule:?}")),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    LockFreeAlgorithm::init(Some(options.clone()))
+                        .add_cols(matrix.iter().cloned())
+                        .decompose()
+                        .diagram()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, schedule_policies);
+criterion_main!(benches);
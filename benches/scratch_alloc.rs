@@ -0,0 +1,193 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+use criterion::{
+    criterion_group, criterion_main,
+    measurement::{Measurement, ValueFormatter},
+    Criterion, Throughput,
+};
+use lophat::{
+    algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm},
+    columns::{Column, ColumnMode, VecColumn},
+    options::LoPhatOptions,
+};
+
+// Counts every allocation made by the process, across all threads
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Measures the number of allocations made during each iteration, rather than wall-clock time
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> usize {
+        ALLOCATIONS.load(Relaxed)
+    }
+
+    fn end(&self, start: usize) -> usize {
+        ALLOCATIONS.load(Relaxed) - start
+    }
+
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationsFormatter
+    }
+}
+
+struct AllocationsFormatter;
+
+impl ValueFormatter for AllocationsFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        _throughput: &Throughput,
+        _values: &mut [f64],
+    ) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
+
+// A VecColumn which allocates a fresh copy whenever it is copied into scratch space, as before scratch columns were re-used
+#[derive(Clone)]
+struct FreshCloneColumn(VecColumn);
+
+impl From<(usize, Vec<usize>)> for FreshCloneColumn {
+    fn from(value: (usize, Vec<usize>)) -> Self {
+        Self(VecColumn::from(value))
+    }
+}
+
+impl Column for FreshCloneColumn {
+    fn pivot(&self) -> Option<usize> {
+        self.0.pivot()
+    }
+
+    fn add_col(&mut self, other: &Self) {
+        self.0.add_col(&other.0)
+    }
+
+    fn add_entry(&mut self, entry: usize) {
+        self.0.add_entry(entry)
+    }
+
+    fn has_entry(&self, entry: &usize) -> bool {
+        self.0.has_entry(entry)
+    }
+
+    type EntriesIter<'a> = <VecColumn as Column>::EntriesIter<'a>;
+
+    fn entries<'a>(&'a self) -> Self::EntriesIter<'a> {
+        self.0.entries()
+    }
+
+    type EntriesRepr = Vec<usize>;
+
+    fn set_entries(&mut self, entries: Self::EntriesRepr) {
+        self.0.set_entries(entries)
+    }
+
+    fn dimension(&self) -> usize {
+        self.0.dimension()
+    }
+
+    fn set_dimension(&mut self, dimension: usize) {
+        self.0.set_dimension(dimension)
+    }
+
+    fn set_mode(&mut self, mode: ColumnMode) {
+        self.0.set_mode(mode)
+    }
+}
+
+// A pseudo-random upper-triangular matrix, with a few entries in each column, which requires many column additions to reduce
+fn build_matrix(n_cols: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..n_cols)
+        .map(|j| {
+            let mut entries: Vec<usize> = (0..j.min(5))
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % j as u64) as usize
+                })
+                .collect();
+            entries.sort_unstable();
+            entries.dedup();
+            (0, entries)
+        })
+        .collect()
+}
+
+fn decompose<C: Column<EntriesRepr = Vec<usize>> + 'static>(matrix: &[(usize, Vec<usize>)]) {
+    let options = LoPhatOptions {
+        clearing: false,
+        ..Default::default()
+    };
+    LockFreeAlgorithm::<C>::init(Some(options))
+        .add_cols(matrix.iter().cloned().map(C::from))
+        .decompose()
+        .diagram();
+}
+
+fn scratch_allocations(c: &mut Criterion<Allocations>) {
+    let matrix = build_matrix(2_000);
+    let mut group = c.benchmark_group("scratch_allocations");
+    group.sample_size(10);
+    group.bench_function("reused_scratch", |b| {
+        b.iter(|| decompose::<VecColumn>(&matrix))
+    });
+    group.bench_function("fresh_clone", |b| {
+        b.iter(|| decompose::<FreshCloneColumn>(&matrix))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().with_measurement(Allocations);
+    targets = scratch_allocations
+);
+criterion_main!(benches);
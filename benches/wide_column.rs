@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lophat::columns::{BitSetColumn, Column, WideColumn};
+
+const N_ENTRIES: usize = 1_000_000;
+// An entry far beyond the others, so that cancelling it leaves a long run of empty rows above the pivot
+const TOP_ENTRY: usize = 4 * N_ENTRIES;
+
+// Returns a column with a million entries, and another column which only shares the top entry
+fn build_columns<C: Column>() -> (C, C) {
+    let mut wide = C::new_with_dimension(1);
+    wide.add_entries((0..N_ENTRIES).map(|i| 2 * i).chain([TOP_ENTRY]));
+    let mut top = C::new_with_dimension(1);
+    top.add_entry(TOP_ENTRY);
+    (wide, top)
+}
+
+fn bench_add_col<C: Column>(c: &mut Criterion, name: &str) {
+    let (mut wide, _) = build_columns::<C>();
+    let (other, _) = build_columns::<C>();
+    // Adding the column twice restores the original, so the same column can be re-used for every iteration
+    c.bench_function(&format!("add_col/{name}"), |b| {
+        b.iter(|| wide.add_col(black_box(&other)))
+    });
+}
+
+fn bench_pivot<C: Column>(c: &mut Criterion, name: &str) {
+    let (mut wide, top) = build_columns::<C>();
+    wide.add_col(&top);
+    c.bench_function(&format!("pivot/{name}"), |b| {
+        b.iter(|| black_box(&wide).pivot())
+    });
+}
+
+// VecColumn is omitted, since it cancels entries in place and so takes minutes to add two columns of this size
+fn wide_columns(c: &mut Criterion) {
+    bench_add_col::<BitSetColumn>(c, "BitSetColumn");
+    bench_add_col::<WideColumn>(c, "WideColumn");
+    bench_pivot::<BitSetColumn>(c, "BitSetColumn");
+    bench_pivot::<WideColumn>(c, "WideColumn");
+}
+
+criterion_group!(benches, wide_columns);
+criterion_main!(benches);
@@ -1,4 +1,5 @@
-from typing import Iterator, List, Set, Tuple
+from enum import Enum
+from typing import Dict, Iterator, List, Set, Tuple
 
 
 def compute_pairings(
@@ -19,6 +20,7 @@ def compute_pairings(
 def compute_pairings_with_reps(
     matrix: List[Tuple[int, List[int]]] | Iterator[Tuple[int, List[int]]],
     options: LoPhatOptions | None = None,
+    parallel_reps: bool = False,
 ) -> PersistenceDiagramWithReps:
     """
     Decomposes the input matrix, using the lockfree algorithm.
@@ -27,10 +29,25 @@ def compute_pairings_with_reps(
 
     :param matrix: The boundary matrix, provided in sparse column format. Each column is a tuple of (dimension, boundary) where boundary is the list of non-zero indices.
     :param options: Options to control the R=DV decomposition algorithm.
+    :param parallel_reps: Whether to read off the representatives in parallel, after decomposition.
     :returns: The persistence pairings read off from the R=DV decomposition.
     """
 
 
+def betti_numbers(
+    matrix: List[Tuple[int, List[int]]] | Iterator[Tuple[int, List[int]]],
+    options: LoPhatOptions | None = None,
+) -> Dict[int, int]:
+    """
+    Decomposes the input matrix, using the lockfree algorithm, and returns the Betti number in each dimension.
+    Assumes that the input is the boundary matrix of a complex, with each column labelled by its dimension.
+
+    :param matrix: The boundary matrix, provided in sparse column format. Each column is a tuple of (dimension, boundary) where boundary is the list of non-zero indices.
+    :param options: Options to control the R=DV decomposition algorithm.
+    :returns: A dictionary mapping each dimension in which there is some column to its Betti number.
+    """
+
+
 class LoPhatOptions:
     """
     A class representing the persistence diagram computed by LoPHAT.
@@ -38,9 +55,18 @@ class LoPhatOptions:
 
     :param maintain_v: Whether to maintain_v during decompositon, usually best left False.
     :param num_threads: Max number of threads to use. Set at 0 to use all threads.
-    :param column_height: Optional hint to height of columns. If None, assumed that matrix is square.
+    :param column_height: Optional hint to height of columns. If None, inferred from the largest row index.
     :param min_chunk_len: When splitting work, don't reduce chunks to smaller than this size.
     :param clearing: Whether to employ the clearing optimisation. Cannot use if input non-square.
+    :param schedule: How to split columns into chunks of work when reducing in parallel.
+    :param lazy_clearing: Whether to mark cleared columns rather than overwriting them. Saves writes but adds a lookup to every read of R.
+    :param exhaustive: Whether to exhaustively reduce R into its canonical form. Only used by the serial algorithm.
+    :param thread_name_prefix: If provided, worker threads are named with this prefix followed by their index.
+    :param stack_size: If provided, the stack size (in bytes) of each worker thread.
+    :param assume_reduced: Whether to assume the input is already reduced and skip reduction, reading the diagram off directly.
+    :param max_homology_dim: If provided, only reduce the dimensions needed for homology up to this dimension. Features of higher dimension are not correctly reported.
+    :param v_on_clear: Whether to write the V column of each cleared column when maintaining V. If False, these columns of V are left empty.
+    :param max_col_len: If provided, truncate each working column to its largest max_col_len entries. The diagram is then only approximate; disable clearing when using this.
     """
 
     def __init__(
@@ -50,10 +76,33 @@ class LoPhatOptions:
         column_height: int | None = None,
         min_chunk_len: int = 1,
         clearing: bool = True,
+        schedule: SchedulePolicy = SchedulePolicy.Static,
+        lazy_clearing: bool = False,
+        exhaustive: bool = False,
+        thread_name_prefix: str | None = None,
+        stack_size: int | None = None,
+        assume_reduced: bool = False,
+        max_homology_dim: int | None = None,
+        v_on_clear: bool = True,
+        max_col_len: int | None = None,
     ) -> None:
         ...
 
 
+class SchedulePolicy(Enum):
+    """
+    The policy used to split the columns of a given dimension into chunks of work, to be reduced in parallel.
+
+    Static: Columns are split into chunks of at least min_chunk_len.
+    Guided: Chunks are split adaptively, becoming smaller as threads steal work from each other.
+    Dynamic: Every column is a separate piece of work. Best suited to matrices in which a few columns dominate the work.
+    """
+
+    Static = ...
+    Guided = ...
+    Dynamic = ...
+
+
 class PersistenceDiagram:
     """
     A class representing the persistence diagram computed by LoPHAT.
@@ -0,0 +1,304 @@
+#[cfg(feature = "serde")]
+use crate::impl_rvd_serialize;
+
+use crate::{
+    algorithms::Decomposition,
+    columns::{Column, VecColumn},
+    options::LoPhatOptions,
+};
+
+use hashbrown::HashMap;
+
+use super::{collect_dimensions, infer_column_height, DecompositionAlgo, NoVMatrixError};
+
+/// Computes persistent cohomology via the annotation algorithm of [Dey, Fan and Wang](https://doi.org/10.1145/2582112.2582165),
+/// with the compressed annotation matrix of [Boissonnat, Dey and Maria](https://doi.org/10.1007/s00453-015-9999-8).
+///
+/// Rather than reducing the anti-transposed boundary matrix, each simplex is assigned an annotation vector,
+/// recording its coordinates with respect to a basis of the cohomology classes alive so far.
+/// Annotations are stored as [`VecColumn`]s, whose entries are the births of the classes, i.e. the indices of the columns which created them.
+/// Annotations remain short (at most the number of live classes), so are stored sparsely regardless of `C`.
+/// Columns are processed in order: a column whose faces have zero total annotation creates a new class,
+/// otherwise it kills the youngest class in the total annotation of its faces.
+/// Simplices sharing the same annotation share storage, so each death only updates the distinct annotations of the dimension below.
+///
+/// Requires that the input is the boundary matrix of a filtered complex, in which every face appears before its cofaces.
+/// The options are ignored; in particular, V is never maintained.
+#[derive(Debug)]
+pub struct CohomologyAnnotationAlgorithm<C: Column> {
+    matrix: Vec<C>,
+}
+
+// The distinct annotations of all simplices, with simplices sharing equal annotations
+#[derive(Debug)]
+struct AnnotationTable {
+    vectors: Vec<VecColumn>,
+    // Union-find over the ids of vectors, merged when two vectors become equal
+    parent: Vec<usize>,
+    // The id of each distinct live vector, keyed by its entries
+    ids: HashMap<Vec<usize>, usize>,
+    // The ids of the live non-zero vectors annotating simplices of each dimension
+    live: HashMap<usize, Vec<usize>>,
+}
+
+// The id of the zero annotation, shared by every dimension
+const ZERO: usize = 0;
+
+impl AnnotationTable {
+    fn new() -> Self {
+        Self {
+            vectors: vec![VecColumn::default()],
+            parent: vec![ZERO],
+            ids: HashMap::from_iter([(vec![], ZERO)]),
+            live: HashMap::new(),
+        }
+    }
+
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            self.parent[id] = self.parent[self.parent[id]];
+            id = self.parent[id];
+        }
+        id
+    }
+
+    // Adds the annotation with a single new coordinate, for a simplex which creates a class
+    fn insert_class(&mut self, dimension: usize, birth: usize) -> usize {
+        let id = self.vectors.len();
+        self.vectors.push(VecColumn::from((dimension, vec![birth])));
+        self.parent.push(id);
+        self.ids.insert(vec![birth], id);
+        self.live.entry(dimension).or_default().push(id);
+        id
+    }
+
+    // Eliminates coordinate `birth` from every annotation of the given dimension, by adding `sum` to those containing it
+    fn kill(&mut self, dimension: usize, birth: usize, sum: &VecColumn) {
+        let Some(live) = self.live.get_mut(&dimension) else {
+            return;
+        };
+        let (updated, unchanged): (Vec<usize>, Vec<usize>) = live
+            .iter()
+            .partition(|&&id| self.vectors[id].has_entry(&birth));
+        *live = unchanged;
+        for &id in updated.iter() {
+            self.ids
+                .remove(&self.vectors[id].entries().collect::<Vec<_>>());
+            self.vectors[id].add_col(sum);
+        }
+        // Updated vectors may now coincide with others, in which case they are merged
+        for id in updated {
+            let key: Vec<usize> = self.vectors[id].entries().collect();
+            match self.ids.get(&key) {
+                Some(&existing) => {
+                    self.parent[id] = existing;
+                    self.vectors[id] = VecColumn::default();
+                }
+                None => {
+                    self.ids.insert(key, id);
+                    self.live.entry(dimension).or_default().push(id);
+                }
+            }
+        }
+    }
+}
+
+impl<C: Column> DecompositionAlgo<C> for CohomologyAnnotationAlgorithm<C> {
+    type Options = LoPhatOptions;
+
+    fn init(_options: Option<Self::Options>) -> Self {
+        Self { matrix: vec![] }
+    }
+
+    fn add_cols(mut self, cols: impl Iterator<Item = C>) -> Self {
+        self.matrix.extend(cols);
+        self
+    }
+
+    fn add_entries(mut self, entries: impl Iterator<Item = (usize, usize)>) -> Self {
+        for (row, col) in entries {
+            let col = self
+                .matrix
+                .get_mut(col)
+                .expect("Column index should correspond to a pre-existing column");
+            col.add_entry(row);
+        }
+        self
+    }
+
+    fn set_dimensions(mut self, dims: impl Iterator<Item = usize>) -> Self {
+        let dims = collect_dimensions(dims, self.matrix.len());
+        for (col, dim) in self.matrix.iter_mut().zip(dims) {
+            col.set_dimension(dim);
+        }
+        self
+    }
+
+    fn n_cols_so_far(&self) -> usize {
+        self.matrix.len()
+    }
+
+    // There is no pivots array, so column_height is never used
+    fn inferred_column_height(&self) -> usize {
+        infer_column_height(self.matrix.iter().map(|col| col.pivot()))
+    }
+
+    type Decomposition = CohomologyAnnotationDecomposition<C>;
+
+    fn decompose(self) -> Self::Decomposition {
+        let mut table = AnnotationTable::new();
+        let mut annotation_of: Vec<usize> = Vec::with_capacity(self.matrix.len());
+        let mut pairing: Vec<C> = Vec::with_capacity(self.matrix.len());
+        for (idx, col) in self.matrix.iter().enumerate() {
+            let dimension = col.dimension();
+            // The annotation of the boundary of this simplex
+            let mut sum = VecColumn::new_with_dimension(dimension.saturating_sub(1));
+            for face in col.entries() {
+                let id = *annotation_of
+                    .get(face)
+                    .expect("Faces should appear before their cofaces");
+                let id = table.find(id);
+                sum.add_col(&table.vectors[id]);
+            }
+            let mut pairing_col = C::new_with_dimension(dimension);
+            match sum.pivot() {
+                // The boundary is zero in cohomology, so a new class is born
+                None => annotation_of.push(table.insert_class(dimension, idx)),
+                // Otherwise the youngest class in the boundary dies
+                Some(birth) => {
+                    let birth_dimension = self.matrix[birth].dimension();
+                    table.kill(birth_dimension, birth, &sum);
+                    pairing_col.add_entry(birth);
+                    annotation_of.push(ZERO);
+                }
+            }
+            pairing.push(pairing_col);
+        }
+        let annotation_of = annotation_of.into_iter().map(|id| table.find(id)).collect();
+        CohomologyAnnotationDecomposition {
+            pairing,
+            annotation_of,
+            vectors: table.vectors,
+        }
+    }
+}
+
+/// Return type of [`CohomologyAnnotationAlgorithm`].
+///
+/// There is no R matrix, so [`get_r_col`](Decomposition::get_r_col) instead returns the pairing matrix:
+/// the column of each death has a single entry, its birth, and every other column is empty.
+/// Hence the provided [`diagram`](Decomposition::diagram) and related methods are correct, but representatives read from R are not.
+/// V is never maintained.
+///
+/// The final annotation of each simplex is available via [`annotation`](Self::annotation).
+pub struct CohomologyAnnotationDecomposition<C: Column> {
+    pairing: Vec<C>,
+    // The id of the annotation of each simplex, into vectors
+    annotation_of: Vec<usize>,
+    vectors: Vec<VecColumn>,
+}
+
+impl<C: Column> CohomologyAnnotationDecomposition<C> {
+    /// Returns the annotation of the simplex in column `idx`, once every column has been added.
+    /// The entries are the births of the essential classes which the simplex's cochain pairs with non-trivially.
+    pub fn annotation(&self, idx: usize) -> &VecColumn {
+        &self.vectors[self.annotation_of[idx]]
+    }
+}
+
+impl<C: Column> Decomposition<C> for CohomologyAnnotationDecomposition<C> {
+    type RColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
+    fn get_r_col(&self, index: usize) -> &C {
+        &self.pairing[index]
+    }
+
+    type VColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
+    fn get_v_col(&self, _index: usize) -> Result<&C, NoVMatrixError> {
+        Err(NoVMatrixError)
+    }
+
+    fn n_cols(&self) -> usize {
+        self.pairing.len()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_rvd_serialize!(CohomologyAnnotationDecomposition);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{LockFreeAlgorithm, SerialAlgorithm};
+    use crate::columns::VecColumn;
+    use crate::utils::fixtures::build_sphere_triangulation;
+    use crate::utils::{anti_transpose, order_filtration};
+
+    #[test]
+    fn annotation_agrees_with_anti_transpose_on_sphere() {
+        let matrix = build_sphere_triangulation();
+        let decomp = CohomologyAnnotationAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        let anti_transpose_diagram = LockFreeAlgorithm::init(None)
+            .add_cols(anti_transpose(&matrix).into_iter())
+            .decompose()
+            .diagram()
+            .anti_transpose(matrix.len());
+        assert_eq!(decomp.diagram(), anti_transpose_diagram);
+        let homology_diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        assert_eq!(decomp.diagram(), homology_diagram);
+        assert!(!decomp.has_v());
+        // Every vertex represents the single class in H^0, and the last triangle the class in H^2
+        for idx in 0..4 {
+            assert_eq!(decomp.annotation(idx), &VecColumn::from((0, vec![0])));
+        }
+        assert!((4..13).all(|idx| decomp.annotation(idx).is_empty()));
+        assert_eq!(decomp.annotation(13), &VecColumn::from((2, vec![13])));
+    }
+
+    #[test]
+    fn annotation_agrees_with_homology_on_simplex() {
+        // Every face of a 5-simplex, with a monotone filtration value
+        let simplices: Vec<(f64, Vec<usize>)> = (1..64usize)
+            .map(|mask| {
+                let vertices: Vec<usize> = (0..6).filter(|v| mask & (1 << v) != 0).collect();
+                let value = vertices.iter().map(|v| (v * 7 % 5) as f64).sum();
+                (value, vertices)
+            })
+            .collect();
+        let (matrix, _) = order_filtration(simplices);
+        let annotation_diagram = CohomologyAnnotationAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose()
+            .diagram();
+        let homology_diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        assert_eq!(annotation_diagram, homology_diagram);
+        assert_eq!(annotation_diagram.unpaired.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn faces_must_appear_first() {
+        CohomologyAnnotationAlgorithm::init(None)
+            .add_cols(
+                vec![
+                    VecColumn::from((1, vec![1])),
+                    VecColumn::new_with_dimension(0),
+                ]
+                .into_iter(),
+            )
+            .decompose();
+    }
+}
@@ -0,0 +1,296 @@
+use std::{borrow::Cow, cmp::Ordering, iter::Copied, slice::Iter};
+
+use hashbrown::HashSet;
+
+use crate::{
+    algorithms::Decomposition,
+    columns::{Column, ColumnMode},
+    utils::PersistenceDiagram,
+};
+
+use super::NoVMatrixError;
+
+// Columns stored contiguously, so that the entries of column `j` are `entries[offsets[j]..offsets[j + 1]]`, in increasing order
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnArena {
+    entries: Vec<usize>,
+    offsets: Vec<usize>,
+    dimensions: Vec<usize>,
+}
+
+impl ColumnArena {
+    fn from_columns(
+        n_cols: usize,
+        col: impl Fn(usize) -> Option<(Vec<usize>, usize)>,
+    ) -> Option<Self> {
+        let mut arena = Self {
+            entries: vec![],
+            offsets: Vec::with_capacity(n_cols + 1),
+            dimensions: Vec::with_capacity(n_cols),
+        };
+        arena.offsets.push(0);
+        for idx in 0..n_cols {
+            let (mut entries, dimension) = col(idx)?;
+            entries.sort_unstable();
+            arena.entries.extend(entries);
+            arena.offsets.push(arena.entries.len());
+            arena.dimensions.push(dimension);
+        }
+        Some(arena)
+    }
+
+    fn entries(&self, idx: usize) -> &[usize] {
+        &self.entries[self.offsets[idx]..self.offsets[idx + 1]]
+    }
+
+    fn column(&self, idx: usize) -> FrozenColumn<'_> {
+        FrozenColumn {
+            dimension: self.dimensions[idx],
+            entries: Cow::Borrowed(self.entries(idx)),
+        }
+    }
+}
+
+/// A column represented by an increasing slice of the non-zero indices, as returned by the [`Decomposition`] implementation of [`FrozenDecomposition`].
+///
+/// Columns read from a [`FrozenDecomposition`] borrow their entries from its arena.
+/// Modifying a column first copies its entries into a new `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrozenColumn<'a> {
+    dimension: usize,
+    entries: Cow<'a, [usize]>,
+}
+
+impl From<(usize, Vec<usize>)> for FrozenColumn<'_> {
+    /// The entries should be provided in increasing order.
+    fn from((dimension, entries): (usize, Vec<usize>)) -> Self {
+        Self {
+            dimension,
+            entries: Cow::Owned(entries),
+        }
+    }
+}
+
+impl Column for FrozenColumn<'_> {
+    fn pivot(&self) -> Option<usize> {
+        self.entries.last().copied()
+    }
+
+    // Merge the two sorted slices, keeping the entries appearing in exactly one
+    fn add_col(&mut self, other: &Self) {
+        let (ours, theirs) = (&self.entries, &other.entries);
+        let mut sum = Vec::with_capacity(ours.len() + theirs.len());
+        let (mut i, mut j) = (0, 0);
+        while i < ours.len() && j < theirs.len() {
+            match ours[i].cmp(&theirs[j]) {
+                Ordering::Less => {
+                    sum.push(ours[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    sum.push(theirs[j]);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum.extend_from_slice(&ours[i..]);
+        sum.extend_from_slice(&theirs[j..]);
+        self.entries = Cow::Owned(sum);
+    }
+
+    fn add_entry(&mut self, entry: usize) {
+        match self.entries.binary_search(&entry) {
+            Ok(idx) => {
+                self.entries.to_mut().remove(idx);
+            }
+            Err(idx) => self.entries.to_mut().insert(idx, entry),
+        }
+    }
+
+    fn has_entry(&self, entry: &usize) -> bool {
+        self.entries.binary_search(entry).is_ok()
+    }
+
+    type EntriesIter<'b>
+        = Copied<Iter<'b, usize>>
+    where
+        Self: 'b;
+
+    fn entries<'b>(&'b self) -> Self::EntriesIter<'b> {
+        self.entries.iter().copied()
+    }
+
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        self.entries.iter().rev().copied()
+    }
+
+    type EntriesRepr = Vec<usize>;
+
+    fn set_entries(&mut self, entries: Self::EntriesRepr) {
+        self.entries = Cow::Owned(entries);
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimension: usize) {
+        self.dimension = dimension;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // No difference for this representation
+    fn set_mode(&mut self, _mode: ColumnMode) {}
+}
+
+/// A read-only decomposition in which the columns of R (and V, if maintained) are each stored in a single contiguous arena,
+/// typically produced by [`LockFreeDecomposition::freeze`](crate::algorithms::LockFreeDecomposition::freeze).
+///
+/// Reading the entries of consecutive columns via [`r_entries`](Self::r_entries) is then sequential in memory,
+/// so scans over the whole decomposition, such as [`diagram`](Decomposition::diagram), are cache-friendly.
+///
+/// [`Decomposition`] is implemented for `&FrozenDecomposition`, so that the columns returned by
+/// [`get_r_col`](Decomposition::get_r_col) and [`get_v_col`](Decomposition::get_v_col) are [`FrozenColumn`]s borrowing from the arena, without allocating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrozenDecomposition {
+    r: ColumnArena,
+    v: Option<ColumnArena>,
+}
+
+impl FrozenDecomposition {
+    /// Copies the columns of R and V (if maintained) from `decomposition` into arenas.
+    pub fn from_decomposition<C: Column, D: Decomposition<C>>(decomposition: &D) -> Self {
+        let n_cols = decomposition.n_cols();
+        let r = ColumnArena::from_columns(n_cols, |idx| {
+            let col = decomposition.get_r_col(idx);
+            Some((col.entries().collect(), col.dimension()))
+        })
+        .expect("Every column of R should be present");
+        let v = ColumnArena::from_columns(n_cols, |idx| {
+            let col = decomposition.get_v_col(idx).ok()?;
+            Some((col.entries().collect(), col.dimension()))
+        });
+        Self { r, v }
+    }
+
+    /// Returns the entries of column `idx` of R, in increasing order, without allocating.
+    pub fn r_entries(&self, idx: usize) -> &[usize] {
+        self.r.entries(idx)
+    }
+
+    /// Returns the entries of column `idx` of V, in increasing order, without allocating.
+    /// Returns `NoVMatrixError` if V was not maintained.
+    pub fn v_entries(&self, idx: usize) -> Result<&[usize], NoVMatrixError> {
+        Ok(self.v.as_ref().ok_or(NoVMatrixError)?.entries(idx))
+    }
+}
+
+impl<'a> Decomposition<FrozenColumn<'a>> for &'a FrozenDecomposition {
+    // Always owned, but the owned column only borrows its entries from the arena
+    type RColRef<'b>
+        = Cow<'b, FrozenColumn<'a>>
+    where
+        Self: 'b;
+    fn get_r_col<'b>(&'b self, index: usize) -> Self::RColRef<'b> {
+        Cow::Owned(self.r.column(index))
+    }
+
+    type VColRef<'b>
+        = Cow<'b, FrozenColumn<'a>>
+    where
+        Self: 'b;
+    fn get_v_col<'b>(&'b self, index: usize) -> Result<Self::VColRef<'b>, NoVMatrixError> {
+        Ok(Cow::Owned(
+            self.v.as_ref().ok_or(NoVMatrixError)?.column(index),
+        ))
+    }
+
+    fn n_cols(&self) -> usize {
+        self.r.dimensions.len()
+    }
+
+    // The pivot of each column is the last of its entries, so no columns are constructed
+    fn diagram(&self) -> PersistenceDiagram {
+        let paired: HashSet<(usize, usize)> = (0..self.n_cols())
+            .filter_map(|idx| Some((*self.r_entries(idx).last()?, idx)))
+            .collect();
+        let mut unpaired: HashSet<usize> = (0..self.n_cols()).collect();
+        for (birth, death) in paired.iter() {
+            unpaired.remove(birth);
+            unpaired.remove(death);
+        }
+        PersistenceDiagram { unpaired, paired }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{DecompositionAlgo, LockFreeAlgorithm};
+    use crate::options::LoPhatOptions;
+    use crate::utils::fixtures::build_sphere_triangulation;
+
+    #[test]
+    fn frozen_agrees_with_lockfree() {
+        for maintain_v in [false, true] {
+            let options = LoPhatOptions {
+                maintain_v,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            let frozen = FrozenDecomposition::from_decomposition(&decomp);
+            let frozen = &frozen;
+            assert_eq!(frozen.n_cols(), decomp.n_cols());
+            assert_eq!(frozen.diagram(), decomp.diagram());
+            assert_eq!(frozen.has_v(), maintain_v);
+            for idx in 0..decomp.n_cols() {
+                // VecColumn entries are sorted, as are those in the arena
+                let entries: Vec<usize> = decomp.get_r_col(idx).entries().collect();
+                assert_eq!(frozen.r_entries(idx), entries);
+                let r_col = frozen.get_r_col(idx);
+                assert!(r_col.entries().eq(entries));
+                assert_eq!(r_col.dimension(), decomp.get_r_col(idx).dimension());
+                // The column borrows its entries from the arena
+                assert!(matches!(r_col.entries, Cow::Borrowed(_)));
+                if maintain_v {
+                    let v_col = frozen.get_v_col(idx).unwrap();
+                    assert!(v_col.entries().eq(decomp.get_v_col(idx).unwrap().entries()));
+                    assert_eq!(
+                        v_col.dimension(),
+                        decomp.get_v_col(idx).unwrap().dimension()
+                    );
+                }
+            }
+            assert_eq!(*frozen, decomp.freeze());
+        }
+    }
+
+    #[test]
+    fn frozen_column_agrees_with_vec_column() {
+        let spec = [(1, vec![0, 2, 5]), (1, vec![1, 2, 7]), (1, vec![])];
+        let frozen: Vec<FrozenColumn> = crate::columns::build_matrix(&spec);
+        let vec: Vec<crate::columns::VecColumn> = crate::columns::build_matrix(&spec);
+        for (i, j) in [(0, 1), (1, 0), (0, 2), (2, 0), (0, 0)] {
+            let mut sum = frozen[i].clone();
+            sum.add_col(&frozen[j]);
+            let mut expected = vec[i].clone();
+            expected.add_col(&vec[j]);
+            assert!(sum.entries().eq(expected.entries()));
+            assert_eq!(sum.pivot(), expected.pivot());
+        }
+        let mut col = frozen[0].clone();
+        col.add_entry(3);
+        col.add_entry(0);
+        assert!(col.entries().eq([2, 3, 5]));
+        assert!(col.has_entry(&3) && !col.has_entry(&0));
+    }
+}
@@ -1,41 +1,70 @@
 use std::ops::Deref;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::{Relaxed, Release};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+#[cfg(feature = "local_thread_pool")]
+use std::sync::OnceLock;
+#[cfg(feature = "spill")]
+use std::{io, path::Path};
 
 #[cfg(feature = "serde")]
 use crate::impl_rvd_serialize;
 
-use crate::columns::Column;
 use crate::columns::ColumnMode::{Storage, Working};
-use crate::options::LoPhatOptions;
-use crate::utils::set_mode_of_pair;
+use crate::columns::{Column, ColumnAccessor, VecColumn};
+use crate::error::LophatError;
+use crate::options::{LoPhatOptions, SchedulePolicy};
+use crate::utils::{clone_pair_from, set_dimension_of_pair, set_mode_of_pair, PersistenceDiagram};
 
+#[cfg(feature = "local_thread_pool")]
+use super::build_thread_pool;
+#[cfg(not(feature = "local_thread_pool"))]
+use super::check_global_thread_pool;
+use bit_set::BitSet;
+use hashbrown::HashSet;
 use pinboard::GuardedRef;
 use pinboard::NonEmptyPinboard;
 use rayon::prelude::*;
-#[cfg(feature = "local_thread_pool")]
-use rayon::ThreadPoolBuilder;
 
-use super::{Decomposition, DecompositionAlgo, NoVMatrixError};
+#[cfg(feature = "spill")]
+use super::spill::SpillFile;
+use super::strategy::{reduce_column_against, PairRef, ReducedColumn};
+use super::{
+    check_entry_row, collect_cleared, collect_dimensions, infer_column_height, reject_pair_filter,
+    validate_clearing, validate_entries, validate_no_pair_filter, Decomposition, DecompositionAlgo,
+    FrozenDecomposition, NoVMatrixError,
+};
+
+impl<C> PairRef<C> for GuardedRef<(C, Option<C>)> {
+    fn r_col(&self) -> &C {
+        &self.0
+    }
+
+    fn v_col(&self) -> Option<&C> {
+        self.1.as_ref()
+    }
+}
 
 enum LoPhatThreadPool {
-    #[cfg(not(feature = "local_thread_pool"))]
+    // Runs on the current rayon pool, i.e. the global pool unless nested inside another pool
     Global(),
+    // Built from the options on first use, so that no pool is built if the reduction is moved onto the current pool
     #[cfg(feature = "local_thread_pool")]
-    Local(rayon::ThreadPool),
+    Local(OnceLock<rayon::ThreadPool>),
 }
 
 impl LoPhatThreadPool {
-    fn install<OP, R>(&self, op: OP) -> R
+    #[cfg_attr(not(feature = "local_thread_pool"), allow(unused_variables))]
+    fn install<OP, R>(&self, options: &LoPhatOptions, op: OP) -> R
     where
         OP: FnOnce() -> R + Send,
         R: Send,
     {
         match self {
-            #[cfg(not(feature = "local_thread_pool"))]
             LoPhatThreadPool::Global() => op(),
             #[cfg(feature = "local_thread_pool")]
-            LoPhatThreadPool::Local(pool) => pool.install(op),
+            LoPhatThreadPool::Local(pool) => {
+                pool.get_or_init(|| build_thread_pool(options)).install(op)
+            }
         }
     }
 }
@@ -46,9 +75,21 @@ pub struct LockFreeAlgorithm<C: Column + 'static> {
     matrix: Vec<NonEmptyPinboard<(C, Option<C>)>>,
     // NOTE: We use `usize::MAX` as a sentinel value, meaning no pivot.
     pivots: Vec<AtomicUsize>,
+    // Number of columns in each dimension which are neither cycles nor cleared.
+    // Once this hits zero there is nothing left to reduce in that dimension.
+    remaining_boundaries: Vec<AtomicUsize>,
+    // Whether each column has been cleared, set during clear_with_column
+    cleared: Vec<AtomicBool>,
     options: LoPhatOptions,
     thread_pool: LoPhatThreadPool,
     max_dim: usize,
+    // Whether any working column was truncated to max_col_len
+    truncated: AtomicBool,
+    // Number of calls to reduce_dimension, so that skipped dimensions can be observed
+    reduced_dimensions: AtomicUsize,
+    // Where finished dimensions are written, see with_spill_dir
+    #[cfg(feature = "spill")]
+    spill: Option<SpillFile>,
 }
 
 impl<C: Column + 'static> LockFreeAlgorithm<C> {
@@ -76,6 +117,7 @@ impl<C: Column + 'static> LockFreeAlgorithm<C> {
     /// Return a column with index `l`, if one exists.
     /// If found, returns `(col_idx, col)`, where col is a tuple consisting of the corresponding column in R and V.
     /// If not maintaining V, second entry of tuple is `None`.
+    #[allow(clippy::type_complexity)]
     pub fn get_col_with_pivot(&self, l: usize) -> Option<(usize, GuardedRef<(C, Option<C>)>)> {
         loop {
             let piv = self.get_pivot(l);
@@ -99,49 +141,71 @@ impl<C: Column + 'static> LockFreeAlgorithm<C> {
     /// then will switch to reducing that column.
     /// It is safe to reduce all columns in parallel.
     pub fn reduce_column(&self, j: usize) {
+        let mut scratch = self.new_scratch();
+        self.reduce_column_with_scratch(j, &mut scratch);
+    }
+
+    // Returns an empty pair of columns, suitable for use as scratch space in reduce_column_with_scratch
+    fn new_scratch(&self) -> (C, Option<C>) {
+        let v_col = self.options.maintain_v.then(|| C::new_with_dimension(0));
+        (C::new_with_dimension(0), v_col)
+    }
+
+    // As for reduce_column, but the working copy of the column is made in `curr_column`, re-using its allocation.
+    // The contents of `curr_column` on entry are irrelevant.
+    fn reduce_column_with_scratch(&self, j: usize, curr_column: &mut (C, Option<C>)) {
         let mut working_j = j;
         'outer: loop {
             // We make a copy of the column because we want to mutate our local copy
-            let mut curr_column = self.matrix[working_j].read();
-            set_mode_of_pair(&mut curr_column, Working);
-            while let Some(l) = (&curr_column).0.pivot() {
-                let piv_with_column_opt = self.get_col_with_pivot(l);
-                if let Some((piv, piv_column)) = piv_with_column_opt {
-                    // Lines 17-24
-                    if piv < working_j {
-                        curr_column.0.add_col(&piv_column.0);
-                        // Only add V columns if we need to
-                        if self.options.maintain_v {
-                            let curr_v_col = curr_column.1.as_mut().unwrap();
-                            curr_v_col.add_col(piv_column.1.as_ref().unwrap());
-                        }
-                    } else if piv > working_j {
-                        self.write_to_matrix(working_j, curr_column);
-                        if self.cew_pivot_succeeds(l, Some(piv), Some(working_j)) {
-                            working_j = piv;
-                        }
-                        continue 'outer;
-                    } else {
-                        panic!()
+            // The copy is made into the scratch space, whereas writes store a (tightly allocated) clone
+            clone_pair_from(curr_column, &self.matrix[working_j].get_ref());
+            set_mode_of_pair(curr_column, Working);
+            // Lines 17-24
+            let truncate = |r_col: &mut C| {
+                if let Some(max_col_len) = self.options.max_col_len {
+                    self.truncate_column(r_col, max_col_len);
+                }
+            };
+            let reduced = reduce_column_against(
+                working_j,
+                &mut curr_column.0,
+                curr_column.1.as_mut(),
+                |l| self.get_col_with_pivot(l),
+                truncate,
+            );
+            self.write_to_matrix(working_j, curr_column.clone());
+            match reduced {
+                ReducedColumn::ClaimedLater { pivot, owner } => {
+                    if self.cew_pivot_succeeds(pivot, Some(owner), Some(working_j)) {
+                        working_j = owner;
                     }
-                } else {
-                    // piv = -1 case
-                    self.write_to_matrix(working_j, curr_column);
-                    if self.cew_pivot_succeeds(l, None, Some(working_j)) {
+                    continue 'outer;
+                }
+                // piv = -1 case
+                ReducedColumn::Unclaimed(pivot) => {
+                    if self.cew_pivot_succeeds(pivot, None, Some(working_j)) {
                         return;
-                    } else {
-                        continue 'outer;
                     }
+                    continue 'outer;
                 }
-            }
-            // Lines 25-27 (curr_column = 0 clause)
-            if (&curr_column.0).is_cycle() {
-                self.write_to_matrix(working_j, curr_column);
-                return;
+                // Lines 25-27 (curr_column = 0 clause)
+                ReducedColumn::Cycle => return,
             }
         }
     }
 
+    // Drops all but the largest `max_col_len` entries of the column, recording that the decomposition is approximate
+    fn truncate_column(&self, column: &mut C, max_col_len: usize) {
+        let mut entries: Vec<usize> = column.entries().collect();
+        if entries.len() <= max_col_len {
+            return;
+        }
+        entries.sort_unstable();
+        // Adding an existing entry removes it
+        column.add_entries(entries.drain(..entries.len() - max_col_len));
+        self.truncated.store(true, Relaxed);
+    }
+
     fn write_to_matrix(&self, index: usize, mut to_write: (C, Option<C>)) {
         set_mode_of_pair(&mut to_write, Storage);
         self.matrix[index].set(to_write);
@@ -154,38 +218,282 @@ impl<C: Column + 'static> LockFreeAlgorithm<C> {
         let clearing_idx = boundary_r
             .pivot()
             .expect("Attempted to clear using cycle column");
-        let clearing_dimension = self.matrix[clearing_idx].get_ref().0.dimension();
-        // The cleared R column is empty
-        let r_col = C::new_with_dimension(clearing_dimension);
-        // The corresponding V column should be the R column of the boundary
-        let v_col = self.options.maintain_v.then(|| {
-            let mut br = boundary_r.clone();
-            br.set_dimension(clearing_dimension);
-            br
-        });
-        self.write_to_matrix(clearing_idx, (r_col, v_col));
+        let (clearing_dimension, was_empty) = {
+            let clearing_col = self.matrix[clearing_idx].get_ref();
+            (clearing_col.0.dimension(), clearing_col.0.is_empty())
+        };
+        // With lazy clearing the column is only marked, unless we need to store its V column
+        if !self.options.lazy_clearing || self.options.maintain_v {
+            // The cleared R column is empty
+            let r_col = C::new_with_dimension(clearing_dimension);
+            // The corresponding V column should be the R column of the boundary, unless not wanted
+            let v_col = self.options.maintain_v.then(|| {
+                if !self.options.v_on_clear {
+                    return C::new_with_dimension(clearing_dimension);
+                }
+                let mut br = boundary_r.clone();
+                br.set_dimension(clearing_dimension);
+                br
+            });
+            self.write_to_matrix(clearing_idx, (r_col, v_col));
+        }
+        if let Some(cleared) = self.cleared.get(clearing_idx) {
+            cleared.store(true, Relaxed);
+        }
+        if !was_empty {
+            if let Some(remaining) = self.remaining_boundaries.get(clearing_dimension) {
+                remaining.fetch_sub(1, Relaxed);
+            }
+        }
     }
 
     /// Reduce all columns of given dimension in parallel, according to `options`.
     pub fn reduce_dimension(&self, dimension: usize) {
+        self.reduced_dimensions.fetch_add(1, Relaxed);
+        // Cleared columns need no reduction, and may not have been overwritten if lazy_clearing
+        let has_dimension =
+            |&j: &usize| !self.is_cleared(j) && self.matrix[j].get_ref().0.dimension() == dimension;
+        // Each rayon job re-uses the same scratch space across the columns it reduces
+        let new_scratch = || self.new_scratch();
+        let reduce = |scratch: &mut (C, Option<C>), j| self.reduce_column_with_scratch(j, scratch);
         // Reduce matrix for columns of that dimension
-        self.thread_pool.install(|| {
-            (0..self.matrix.len())
-                .into_par_iter()
-                .with_min_len(self.options.min_chunk_len)
-                .filter(|&j| self.matrix[j].get_ref().0.dimension() == dimension)
-                .for_each(|j| self.reduce_column(j));
+        self.thread_pool.install(&self.options, || {
+            let indices = (0..self.matrix.len()).into_par_iter();
+            match self.options.schedule {
+                SchedulePolicy::Static => indices
+                    .with_min_len(self.options.min_chunk_len)
+                    .filter(has_dimension)
+                    .for_each_init(new_scratch, reduce),
+                SchedulePolicy::Guided => indices
+                    .filter(has_dimension)
+                    .for_each_init(new_scratch, reduce),
+                SchedulePolicy::Dynamic => indices
+                    .with_max_len(1)
+                    .filter(has_dimension)
+                    .for_each_init(new_scratch, reduce),
+            }
+        });
+    }
+
+    /// Returns the height of the columns, used to size the pivots array.
+    /// This is [`column_height`](LoPhatOptions::column_height) if provided, otherwise one more than the largest row index in any column.
+    /// In the latter case, this requires a pass over the matrix so is computed once, at the start of [`decompose`](DecompositionAlgo::decompose).
+    pub fn effective_column_height(&self) -> usize {
+        self.options.column_height.unwrap_or_else(|| {
+            infer_column_height(self.matrix.iter().map(|col| col.get_ref().0.pivot()))
+        })
+    }
+
+    // Sets up the pivots vector, ready for reduction
+    fn setup_pivots(&mut self) {
+        reject_pair_filter(&self.options);
+        let column_height = self.effective_column_height();
+        self.pivots = (0..column_height)
+            .map(|_| AtomicUsize::new(usize::MAX))
+            .collect();
+    }
+
+    // Marks every column as not yet cleared
+    fn setup_cleared(&mut self) {
+        self.cleared = (0..self.matrix.len())
+            .map(|_| AtomicBool::new(false))
+            .collect();
+    }
+
+    // Returns whether column j has been cleared
+    fn is_cleared(&self, j: usize) -> bool {
+        self.cleared
+            .get(j)
+            .is_some_and(|cleared| cleared.load(Relaxed))
+    }
+
+    // Counts the non-empty columns in each dimension, ready for reduction
+    fn setup_remaining_boundaries(&mut self) {
+        let mut remaining = vec![0; self.max_dim + 1];
+        for col in self.matrix.iter() {
+            let col = col.get_ref();
+            if !col.0.is_empty() {
+                remaining[col.0.dimension()] += 1;
+            }
+        }
+        self.remaining_boundaries = remaining.into_iter().map(AtomicUsize::new).collect();
+    }
+
+    // Returns true if every column of the given dimension is a cycle or has been cleared, so there is nothing to reduce
+    fn dimension_is_reduced(&self, dimension: usize) -> bool {
+        self.remaining_boundaries
+            .get(dimension)
+            .is_some_and(|remaining| remaining.load(Relaxed) == 0)
+    }
+
+    /// As for [`decompose`](DecompositionAlgo::decompose) but safe to call from within an existing rayon pool, e.g. inside an outer `par_iter` over many matrices.
+    ///
+    /// If called from a rayon worker thread, the reduction is run on that worker's pool, alongside the outer work, rather than on the pool configured by `options`.
+    /// With the `local_thread_pool` feature, this avoids every outer task blocking on its own pool of `num_threads` threads, which would oversubscribe the machine.
+    /// In this case `num_threads` is ignored and no local pool is ever built, since the local pool is only built when first used.
+    /// If not called from a rayon worker thread, this is identical to [`decompose`](DecompositionAlgo::decompose).
+    pub fn decompose_nested(mut self) -> LockFreeDecomposition<C> {
+        self.use_current_pool_if_nested();
+        self.decompose()
+    }
+
+    // Switches to the current rayon pool if called from a rayon worker thread, before the local pool is built
+    fn use_current_pool_if_nested(&mut self) {
+        if rayon::current_thread_index().is_some() {
+            self.thread_pool = LoPhatThreadPool::Global();
+        }
+    }
+
+    /// Takes a snapshot of the current state of the matrix and pivots.
+    /// To take a snapshot part way through a reduction, call this from the callback passed to [`decompose_with_checkpoint`](Self::decompose_with_checkpoint).
+    /// The snapshot can be written to disk (with the `serde` feature) and later resumed via [`restore`](Self::restore).
+    pub fn snapshot(&self) -> ReductionSnapshot {
+        let to_vec_column = |col: &C| {
+            let mut entries: Vec<usize> = col.entries().collect();
+            entries.sort_unstable();
+            VecColumn::from((col.dimension(), entries))
+        };
+        let cols: Vec<_> = (0..self.matrix.len())
+            .map(|j| self.current_column(j))
+            .collect();
+        let r = cols.iter().map(|col| to_vec_column(&col.0)).collect();
+        let v = self.options.maintain_v.then(|| {
+            cols.iter()
+                .map(|col| to_vec_column(col.1.as_ref().unwrap()))
+                .collect()
         });
+        let pivots = (0..self.pivots.len())
+            .map(|idx| self.get_pivot(idx))
+            .collect();
+        ReductionSnapshot { r, v, pivots }
+    }
+
+    // Returns the current R and V columns at index j, taking into account lazy clearing and spilling
+    fn current_column(&self, j: usize) -> (C, Option<C>) {
+        #[cfg(feature = "spill")]
+        if let Some(spill) = self.spill.as_ref() {
+            if let Some(pair) = spill.read(j, self.options.maintain_v) {
+                return pair;
+            }
+        }
+        let mut pair = self.matrix[j].read();
+        // Lazily cleared columns may still hold their original entries
+        if self.is_cleared(j) {
+            pair.0.clear_entries();
+        }
+        pair
+    }
+
+    /// Restores the algorithm from a snapshot, taken via [`snapshot`](Self::snapshot), ready to [`decompose`](DecompositionAlgo::decompose).
+    ///
+    /// Since each column in the snapshot is a valid partial reduction, resuming simply re-runs the reduction on the snapshot's matrix.
+    /// Columns which were already reduced are quickly re-reduced, so the result is the same as an uninterrupted reduction.
+    /// The pivots array is rebuilt during the reduction, rather than being restored from the snapshot.
+    ///
+    /// Panics if `options.maintain_v` but the snapshot did not maintain V.
+    pub fn restore(snapshot: ReductionSnapshot, options: Option<LoPhatOptions>) -> Self {
+        let mut algo = Self::init(options);
+        let from_vec_column = |col: VecColumn| {
+            let mut output = C::new_with_dimension(col.dimension());
+            output.add_entries(col.entries());
+            output
+        };
+        let mut v_cols = match (algo.options.maintain_v, snapshot.v) {
+            (true, Some(v)) => Some(v.into_iter()),
+            (true, None) => panic!("Snapshot should contain V in order to maintain V"),
+            (false, _) => None,
+        };
+        for r_col in snapshot.r {
+            let r_col = from_vec_column(r_col);
+            let v_col = v_cols.as_mut().map(|v| {
+                from_vec_column(
+                    v.next()
+                        .expect("Snapshot should have as many V columns as R"),
+                )
+            });
+            algo.max_dim = algo.max_dim.max(r_col.dimension());
+            algo.matrix.push(NonEmptyPinboard::new((r_col, v_col)));
+        }
+        algo
+    }
+
+    // Returns the highest dimension which needs to be reduced, taking into account max_homology_dim
+    fn top_reduced_dimension(&self) -> usize {
+        match self.options.max_homology_dim {
+            Some(max_homology_dim) => self.max_dim.min(max_homology_dim + 1),
+            None => self.max_dim,
+        }
+    }
+
+    // Reduces the given dimension, unless there is nothing to reduce, and then clears with it if requested
+    fn reduce_and_clear_dimension(&self, dimension: usize) {
+        // Skip dimensions which are entirely cycles, e.g. after clearing
+        if !self.dimension_is_reduced(dimension) {
+            self.reduce_dimension(dimension);
+        }
+        if self.options.clearing && dimension > 0 {
+            self.clear_dimension(dimension)
+        }
+    }
+
+    /// Decomposes the matrix, as in [`decompose`](DecompositionAlgo::decompose), but lazily yields the persistence diagram one dimension at a time.
+    ///
+    /// Each item is `(dimension, diagram)`, where `diagram` contains the pairings whose birth has the given dimension and the unpaired columns of that dimension.
+    /// Dimensions are yielded in increasing order, so H_0 arrives first.
+    /// The union of the yielded diagrams is the full persistence diagram.
+    ///
+    /// The diagram of a dimension is final once that dimension and the one above have been reduced, so dimensions are reduced bottom-up, each only when the corresponding item is requested.
+    /// Clearing only helps when reducing top-down, so the [`clearing`](LoPhatOptions::clearing) option is ignored and this may be slower than [`decompose`](DecompositionAlgo::decompose).
+    /// The [`max_homology_dim`](LoPhatOptions::max_homology_dim) option is also ignored, so every dimension is reduced.
+    pub fn decompose_streaming_diagram(
+        mut self,
+    ) -> impl Iterator<Item = (usize, PersistenceDiagram)> {
+        self.setup_pivots();
+        self.setup_remaining_boundaries();
+        self.setup_cleared();
+        (0..=self.max_dim).map(move |dimension| {
+            if dimension == 0 {
+                self.reduce_dimension(0);
+            }
+            if dimension < self.max_dim {
+                self.reduce_dimension(dimension + 1);
+            }
+            (dimension, self.diagram_of_dimension(dimension))
+        })
+    }
+
+    // Reads off the diagram for the given dimension, assuming that it and the dimension above have been reduced
+    fn diagram_of_dimension(&self, dimension: usize) -> PersistenceDiagram {
+        // Lazily cleared columns may still hold their original entries
+        let pivot_of = |j: usize| {
+            if self.is_cleared(j) {
+                None
+            } else {
+                self.matrix[j].get_ref().0.pivot()
+            }
+        };
+        let dimension_of = |j: usize| self.matrix[j].get_ref().0.dimension();
+        let paired: HashSet<(usize, usize)> = (0..self.matrix.len())
+            .filter(|&j| dimension_of(j) == dimension + 1)
+            .filter_map(|j| Some((pivot_of(j)?, j)))
+            .collect();
+        let births: HashSet<usize> = paired.iter().map(|&(birth, _)| birth).collect();
+        let unpaired = (0..self.matrix.len())
+            .filter(|&j| dimension_of(j) == dimension)
+            .filter(|&j| pivot_of(j).is_none() && !births.contains(&j))
+            .collect();
+        PersistenceDiagram { unpaired, paired }
     }
 
     /// Clear all columns of given dimension in parallel
     pub fn clear_dimension(&self, dimension: usize) {
         // Reduce matrix for columns of that dimension
-        self.thread_pool.install(|| {
+        self.thread_pool.install(&self.options, || {
             (0..self.matrix.len())
                 .into_par_iter()
                 .with_min_len(self.options.min_chunk_len)
                 .filter(|&j| self.matrix[j].get_ref().0.dimension() == dimension)
+                .filter(|&j| !self.is_cleared(j))
                 .filter(|&j| self.matrix[j].get_ref().0.is_boundary())
                 .for_each(|j| self.clear_with_column(j));
         });
@@ -199,34 +507,35 @@ impl<C: Column> DecompositionAlgo<C> for LockFreeAlgorithm<C> {
         let options = options.unwrap_or_default();
         // Setup thread pool
         #[cfg(feature = "local_thread_pool")]
-        let thread_pool = LoPhatThreadPool::Local(
-            ThreadPoolBuilder::new()
-                .num_threads(options.num_threads)
-                .build()
-                .expect("Failed to build thread pool"),
-        );
+        let thread_pool = LoPhatThreadPool::Local(OnceLock::new());
         #[cfg(not(feature = "local_thread_pool"))]
         let thread_pool = {
-            if options.num_threads != 0 {
-                panic!(
-                    "To specify a number of threads, please enable the local_thread_pool feature"
-                );
-            }
+            check_global_thread_pool(&options);
             LoPhatThreadPool::Global()
         };
         Self {
             matrix: vec![],
             pivots: vec![],
+            remaining_boundaries: vec![],
+            cleared: vec![],
             options,
             thread_pool,
             max_dim: 0,
+            truncated: AtomicBool::new(false),
+            reduced_dimensions: AtomicUsize::new(0),
+            #[cfg(feature = "spill")]
+            spill: None,
         }
     }
 
     fn add_cols(mut self, cols: impl Iterator<Item = C>) -> Self {
         let first_idx = self.matrix.len();
-        let new_cols = cols.enumerate().map(|(idx, r_col)| {
+        let column_height = self.options.column_height;
+        let new_cols = cols.enumerate().map(|(idx, mut r_col)| {
             self.max_dim = self.max_dim.max(r_col.dimension());
+            if let Some(column_height) = column_height {
+                r_col.reserve(column_height);
+            }
             if self.options.maintain_v {
                 let mut v_col = C::new_with_dimension(r_col.dimension());
                 v_col.add_entry(first_idx + idx);
@@ -241,6 +550,7 @@ impl<C: Column> DecompositionAlgo<C> for LockFreeAlgorithm<C> {
 
     fn add_entries(self, entries: impl Iterator<Item = (usize, usize)>) -> Self {
         for (row, col) in entries {
+            check_entry_row(row, col, self.options.column_height);
             let col = self
                 .matrix
                 .get(col)
@@ -252,67 +562,358 @@ impl<C: Column> DecompositionAlgo<C> for LockFreeAlgorithm<C> {
         self
     }
 
+    /// Additionally checks that each row lies within `column_height`, if provided.
+    fn try_add_entries(
+        self,
+        entries: impl Iterator<Item = (usize, usize)>,
+    ) -> Result<Self, LophatError> {
+        let entries: Vec<_> = entries.collect();
+        validate_entries(&entries, self.matrix.len(), self.options.column_height)?;
+        Ok(self.add_entries(entries.into_iter()))
+    }
+
+    /// Returns an error if clearing is requested but `column_height` does not match the number of columns,
+    /// or if [`accept_pair`](LoPhatOptions::accept_pair) is set.
+    fn try_decompose(self) -> Result<Self::Decomposition, LophatError> {
+        validate_clearing(&self.options, self.matrix.len())?;
+        validate_no_pair_filter(&self.options)?;
+        Ok(self.decompose())
+    }
+
+    fn set_dimensions(mut self, dims: impl Iterator<Item = usize>) -> Self {
+        let dims = collect_dimensions(dims, self.matrix.len());
+        self.max_dim = dims.iter().copied().max().unwrap_or(0);
+        for (col, dim) in self.matrix.iter().zip(dims) {
+            let mut col_clone = col.get_ref().clone();
+            set_dimension_of_pair(&mut col_clone, dim);
+            col.set(col_clone);
+        }
+        self
+    }
+
+    fn n_cols_so_far(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn lophat_options(&self) -> Option<&LoPhatOptions> {
+        Some(&self.options)
+    }
+
+    fn inferred_column_height(&self) -> usize {
+        self.effective_column_height()
+    }
+
     type Decomposition = LockFreeDecomposition<C>;
 
-    fn decompose(mut self) -> Self::Decomposition {
-        // Setup pivots vector
-        let column_height = self.options.column_height.unwrap_or(self.matrix.len());
-        self.pivots = (0..column_height)
-            .map(|_| AtomicUsize::new(usize::MAX))
-            .collect();
+    fn decompose(self) -> Self::Decomposition {
+        self.decompose_with_checkpoint(|_, _| ())
+    }
+}
+
+impl<C: Column + 'static> LockFreeAlgorithm<C> {
+    /// As for [`decompose`](DecompositionAlgo::decompose), but calls `checkpoint(dimension, &self)` once each dimension has been reduced (and used for clearing).
+    ///
+    /// Dimensions are reduced in decreasing order, so this is the point at which a [`snapshot`](Self::snapshot) captures a partial reduction.
+    /// The checkpoint is not called for dimensions which are skipped due to [`max_homology_dim`](LoPhatOptions::max_homology_dim),
+    /// nor at all if [`assume_reduced`](LoPhatOptions::assume_reduced).
+    pub fn decompose_with_checkpoint(
+        mut self,
+        mut checkpoint: impl FnMut(usize, &Self),
+    ) -> LockFreeDecomposition<C> {
+        reject_pair_filter(&self.options);
+        if self.options.assume_reduced {
+            let decomposition = self.into_decomposition();
+            debug_assert!(
+                decomposition.is_reduced(),
+                "Input should be reduced when assume_reduced is set"
+            );
+            return decomposition;
+        }
+        self.setup_pivots();
+        self.setup_remaining_boundaries();
+        self.setup_cleared();
         // Decompose
-        for dimension in (0..=self.max_dim).rev() {
-            self.reduce_dimension(dimension);
-            if self.options.clearing && dimension > 0 {
-                self.clear_dimension(dimension)
+        for dimension in (0..=self.top_reduced_dimension()).rev() {
+            self.reduce_and_clear_dimension(dimension);
+            checkpoint(dimension, &self);
+            #[cfg(feature = "spill")]
+            self.spill_dimension(dimension);
+        }
+        self.into_decomposition()
+    }
+
+    // Pushes columns of R alongside their existing columns of V, so that column operations are applied on top of V rather than the identity
+    pub(crate) fn add_cols_with_v(mut self, cols: impl Iterator<Item = (C, Option<C>)>) -> Self {
+        let column_height = self.options.column_height;
+        let new_cols = cols.map(|(mut r_col, v_col)| {
+            self.max_dim = self.max_dim.max(r_col.dimension());
+            if let Some(column_height) = column_height {
+                r_col.reserve(column_height);
             }
+            NonEmptyPinboard::new((r_col, v_col))
+        });
+        self.matrix.extend(new_cols);
+        self
+    }
+
+    /// Decomposes the matrix presented by `accessor`, without first collecting its columns.
+    ///
+    /// Each column is only fetched from `accessor` immediately before its dimension is reduced, and columns which have already been cleared are never fetched.
+    /// This is intended for accessors which compute columns on demand, such as [`AntiTransposeView`](crate::utils::AntiTransposeView),
+    /// so that cleared columns are never built.
+    /// Since the row indices are not known up front, if `column_height` is not provided then the matrix is assumed to be square.
+    pub fn decompose_accessor(
+        accessor: &(impl ColumnAccessor<C> + ?Sized),
+        options: Option<LoPhatOptions>,
+    ) -> LockFreeDecomposition<C> {
+        let mut options = options.unwrap_or_default();
+        let n_cols = accessor.n_cols();
+        options.column_height = Some(options.column_height.unwrap_or(n_cols));
+        // Columns are stored as empty placeholders, with the correct dimension, until they are fetched
+        let placeholders = (0..n_cols).map(|j| C::new_with_dimension(accessor.dimension(j)));
+        let mut algo = Self::init(Some(options)).add_cols(placeholders);
+        algo.setup_pivots();
+        algo.setup_cleared();
+        // The number of boundaries is not known up front, so remaining_boundaries is left empty and no dimension is skipped
+        for dimension in (0..=algo.top_reduced_dimension()).rev() {
+            algo.fetch_dimension(accessor, dimension);
+            algo.reduce_and_clear_dimension(dimension);
+            #[cfg(feature = "spill")]
+            algo.spill_dimension(dimension);
+        }
+        algo.into_decomposition()
+    }
+
+    // Replaces the placeholder of each column in the given dimension, which has not been cleared, with the column from `accessor`
+    fn fetch_dimension(&self, accessor: &(impl ColumnAccessor<C> + ?Sized), dimension: usize) {
+        let column_height = self.options.column_height;
+        self.thread_pool.install(&self.options, || {
+            (0..self.matrix.len())
+                .into_par_iter()
+                .with_min_len(self.options.min_chunk_len)
+                .filter(|&j| accessor.dimension(j) == dimension && !self.is_cleared(j))
+                .for_each(|j| {
+                    let mut r_col = accessor.column(j);
+                    if let Some(column_height) = column_height {
+                        r_col.reserve(column_height);
+                    }
+                    let v_col = self.matrix[j].get_ref().1.clone();
+                    self.matrix[j].set((r_col, v_col));
+                });
+        });
+    }
+
+    /// Writes columns to a file in `dir` once they are no longer needed for reduction, dropping them from memory.
+    /// They are then lazily read back whenever the decomposition is queried, e.g. via [`get_r_col`](Decomposition::get_r_col).
+    /// Requires the `spill` feature.
+    ///
+    /// Since dimensions are reduced from the top down, each dimension is spilled (with both R and V) as soon as it has been reduced and used for clearing.
+    /// This trades peak memory for IO, with some heavy caveats:
+    /// * only columns of dimensions which are reduced are spilled, and the dimension currently being reduced is always held in memory in full;
+    /// * an empty placeholder column, and the offset of each column in the file, are still held in memory;
+    /// * every read of a spilled column seeks within the file and allocates a new column, and reads are serialised behind a lock,
+    ///   so methods which scan every column, such as [`diagram`](Decomposition::diagram), become IO-bound;
+    /// * IO errors during reduction or reading panic, since [`decompose`](DecompositionAlgo::decompose) is infallible;
+    /// * the file is deleted when the decomposition is dropped, but is left behind if the process aborts;
+    /// * [`decompose_streaming_diagram`](Self::decompose_streaming_diagram) does not spill.
+    ///
+    /// Returns an error if the spill file cannot be created in `dir`.
+    #[cfg(feature = "spill")]
+    pub fn with_spill_dir(mut self, dir: impl AsRef<Path>) -> io::Result<Self> {
+        self.spill = Some(SpillFile::create(dir.as_ref())?);
+        Ok(self)
+    }
+
+    // Writes every column of the given dimension to the spill file, if any, and replaces them with empty placeholders
+    #[cfg(feature = "spill")]
+    fn spill_dimension(&mut self, dimension: usize) {
+        let Some(spill) = self.spill.as_mut() else {
+            return;
+        };
+        let indices: Vec<usize> = (0..self.matrix.len())
+            .filter(|&j| self.matrix[j].get_ref().0.dimension() == dimension)
+            .collect();
+        let cols = indices.iter().map(|&j| (j, self.matrix[j].get_ref()));
+        spill
+            .spill(cols)
+            .expect("Should be able to write columns to the spill file");
+        for j in indices {
+            let v_col = self.matrix[j]
+                .get_ref()
+                .1
+                .as_ref()
+                .map(|_| C::new_with_dimension(dimension));
+            self.matrix[j].set((C::new_with_dimension(dimension), v_col));
+        }
+    }
+
+    fn into_decomposition(self) -> LockFreeDecomposition<C> {
+        let cleared = collect_cleared(&self.cleared);
+        let pivots = self
+            .pivots
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect();
+        LockFreeDecomposition {
+            matrix: self.matrix,
+            cleared,
+            lazy_clearing: self.options.clearing && self.options.lazy_clearing,
+            pivots,
+            approximate: self.truncated.into_inner(),
+            reduced_dimensions: self.reduced_dimensions.into_inner(),
+            #[cfg(feature = "spill")]
+            spill: self.spill,
         }
-        LockFreeDecomposition(self.matrix)
     }
 }
 
+/// A snapshot of an in-progress reduction, produced by [`LockFreeAlgorithm::snapshot`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReductionSnapshot {
+    /// The current state of the columns of R.
+    pub r: Vec<VecColumn>,
+    /// The current state of the columns of V, if maintained.
+    pub v: Option<Vec<VecColumn>>,
+    /// The current state of the pivots array; `pivots[l]` is the column believed to have pivot `l`.
+    pub pivots: Vec<Option<usize>>,
+}
+
 /// Return type of [`LockFreeAlgorithm`].
-pub struct LockFreeDecomposition<C: Column + 'static>(Vec<NonEmptyPinboard<(C, Option<C>)>>);
+/// Alongside the columns, stores the indices of the columns which were cleared.
+///
+/// If decomposed with [`lazy_clearing`](LoPhatOptions::lazy_clearing), cleared columns were never overwritten.
+/// Instead, every call to [`get_r_col`](Decomposition::get_r_col) first checks whether the column was cleared and, if so, returns a freshly allocated empty column.
+/// Hence every read of R pays for an extra bit lookup, and every read of a cleared column constructs a new (empty) column.
+pub struct LockFreeDecomposition<C: Column + 'static> {
+    matrix: Vec<NonEmptyPinboard<(C, Option<C>)>>,
+    cleared: BitSet,
+    // Whether cleared columns may still hold their original contents
+    lazy_clearing: bool,
+    // The column with each pivot, or usize::MAX if none; empty if reduction was skipped
+    pivots: Vec<usize>,
+    // Whether any column was truncated during reduction
+    approximate: bool,
+    reduced_dimensions: usize,
+    // Holds the columns which were dropped from memory, if decomposed with a spill directory
+    #[cfg(feature = "spill")]
+    spill: Option<SpillFile>,
+}
+
+enum LockFreeColRef<C> {
+    Stored(GuardedRef<(C, Option<C>)>),
+    // A column constructed on read, e.g. empty for a lazily cleared column or read back from disk for a spilled column
+    Owned(C),
+}
 
-pub struct LockFreeRRef<C>(GuardedRef<(C, Option<C>)>);
+pub struct LockFreeRRef<C>(LockFreeColRef<C>);
 
 impl<C> Deref for LockFreeRRef<C> {
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &self.0.deref().0
+        match &self.0 {
+            LockFreeColRef::Stored(col_ref) => &col_ref.deref().0,
+            LockFreeColRef::Owned(col) => col,
+        }
     }
 }
 
-pub struct LockFreeVRef<C>(GuardedRef<(C, Option<C>)>);
+pub struct LockFreeVRef<C>(LockFreeColRef<C>);
 
 impl<C> Deref for LockFreeVRef<C> {
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &self.0.deref().1.as_ref().unwrap()
+        match &self.0 {
+            LockFreeColRef::Stored(col_ref) => col_ref.deref().1.as_ref().unwrap(),
+            LockFreeColRef::Owned(col) => col,
+        }
     }
 }
 
 impl<C: Column + 'static> Decomposition<C> for LockFreeDecomposition<C> {
     type RColRef<'a> = LockFreeRRef<C>;
     fn get_r_col<'a>(&'a self, index: usize) -> Self::RColRef<'a> {
-        LockFreeRRef(self.0[index].get_ref())
+        let col_ref = self.matrix[index].get_ref();
+        if self.lazy_clearing && self.cleared.contains(index) {
+            let empty = C::new_with_dimension(col_ref.0.dimension());
+            return LockFreeRRef(LockFreeColRef::Owned(empty));
+        }
+        #[cfg(feature = "spill")]
+        if let Some((r_col, _)) = self.read_spilled(index, col_ref.1.is_some()) {
+            return LockFreeRRef(LockFreeColRef::Owned(r_col));
+        }
+        LockFreeRRef(LockFreeColRef::Stored(col_ref))
     }
 
     type VColRef<'a> = LockFreeVRef<C>;
     fn get_v_col<'a>(&'a self, index: usize) -> Result<Self::VColRef<'a>, NoVMatrixError> {
-        let col_ref = self.0[index].get_ref();
+        let col_ref = self.matrix[index].get_ref();
         let has_v = col_ref.1.is_some();
-        if has_v {
-            Ok(LockFreeVRef(col_ref))
-        } else {
-            Err(NoVMatrixError)
+        if !has_v {
+            return Err(NoVMatrixError);
+        }
+        #[cfg(feature = "spill")]
+        if let Some((_, v_col)) = self.read_spilled(index, has_v) {
+            return Ok(LockFreeVRef(LockFreeColRef::Owned(v_col.unwrap())));
         }
+        Ok(LockFreeVRef(LockFreeColRef::Stored(col_ref)))
     }
 
     fn n_cols(&self) -> usize {
-        self.0.len()
+        self.matrix.len()
+    }
+
+    fn cleared_columns(&self) -> HashSet<usize> {
+        self.cleared.iter().collect()
+    }
+
+    // Reads the final pivots array, unless it was never set up (e.g. with assume_reduced)
+    fn column_with_pivot(&self, l: usize) -> Option<usize> {
+        if self.pivots.is_empty() {
+            return (0..self.n_cols()).find(|&j| self.get_r_col(j).pivot() == Some(l));
+        }
+        self.pivots.get(l).copied().filter(|&j| j != usize::MAX)
+    }
+
+    fn is_approximate(&self) -> bool {
+        self.approximate
+    }
+}
+
+impl<C: Column + 'static> LockFreeDecomposition<C> {
+    // Returns each pivot alongside the column which claimed it, from the final pivots array unless it was never set up
+    pub(crate) fn claimed_pivots(&self) -> Vec<(usize, usize)> {
+        if self.pivots.is_empty() {
+            return (0..self.n_cols())
+                .filter_map(|j| Some((self.get_r_col(j).pivot()?, j)))
+                .collect();
+        }
+        self.pivots
+            .iter()
+            .enumerate()
+            .filter(|(_, &j)| j != usize::MAX)
+            .map(|(l, &j)| (l, j))
+            .collect()
+    }
+
+    /// Returns the number of dimensions which were reduced.
+    /// Dimensions in which every column is empty or has been cleared are skipped, so are not counted.
+    pub fn n_reduced_dimensions(&self) -> usize {
+        self.reduced_dimensions
+    }
+
+    // Reads column `idx` back from disk, if it was spilled
+    #[cfg(feature = "spill")]
+    fn read_spilled(&self, idx: usize, has_v: bool) -> Option<(C, Option<C>)> {
+        self.spill.as_ref()?.read(idx, has_v)
+    }
+
+    /// Copies the columns of R (and V, if maintained) into a read-only [`FrozenDecomposition`], in which they are stored contiguously.
+    /// Prefer this for repeated scans over the decomposition, since reads no longer go through a pinboard.
+    pub fn freeze(self) -> FrozenDecomposition {
+        FrozenDecomposition::from_decomposition(&self)
     }
 }
 
@@ -322,16 +923,23 @@ mod tests {
     use super::*;
     use crate::algorithms::Decomposition;
     use crate::algorithms::SerialAlgorithm;
-    use crate::columns::{BitSetColumn, BitSetVecHybridColumn, VecColumn};
+    use crate::columns::{
+        build_matrix, BitSetColumn, BitSetVecHybridColumn, Index, VecColumn, VecColumnT,
+    };
+    use crate::utils::fixtures::build_sphere_triangulation;
+    use crate::utils::PersistenceDiagram;
+    use hashbrown::HashSet;
     use proptest::collection::hash_set;
     use proptest::prelude::*;
 
     proptest! {
         #[test]
         fn lockfree_agrees_with_serial( matrix in sut_matrix(100) ) {
-            let mut options = LoPhatOptions::default();
-            options.clearing = false;
-            let serial_dgm = SerialAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned()).decompose().diagram();
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let serial_dgm = SerialAlgorithm::init(Some(options.clone())).add_cols(matrix.iter().cloned()).decompose().diagram();
             let parallel_dgm = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
             assert_eq!(serial_dgm, parallel_dgm);
         }
@@ -340,14 +948,12 @@ mod tests {
     proptest! {
         #[test]
         fn hybrid_cols_work( matrix in sut_matrix(100) ) {
-            let hybrid_matrix = matrix.iter().map(|col| {
-                let mut hybrid_col = BitSetVecHybridColumn::new_with_dimension(col.dimension());
-                hybrid_col.add_entries(col.entries());
-                hybrid_col
-            });
-            let mut options = LoPhatOptions::default();
-            options.clearing = false;
-            let hybrid_dgm = LockFreeAlgorithm::init( Some(options)).add_cols(hybrid_matrix).decompose().diagram();
+            let hybrid_matrix = build_matrix::<BitSetVecHybridColumn>(&spec_of(&matrix)).into_iter();
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let hybrid_dgm = LockFreeAlgorithm::init( Some(options.clone())).add_cols(hybrid_matrix).decompose().diagram();
             let vec_dgm = LockFreeAlgorithm::init( Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
             assert_eq!(vec_dgm, hybrid_dgm);
         }
@@ -356,19 +962,991 @@ mod tests {
     proptest! {
         #[test]
         fn bit_set_cols_work( matrix in sut_matrix(100) ) {
-            let bit_set_matrix = matrix.iter().map(|col| {
-                let mut bit_set_col = BitSetColumn::new_with_dimension(col.dimension());
-                bit_set_col.add_entries(col.entries());
-                bit_set_col
-            });
-            let mut options = LoPhatOptions::default();
-            options.clearing = false;
-            let bit_set_dgm = LockFreeAlgorithm::init(Some(options)).add_cols(bit_set_matrix).decompose().diagram();
+            let bit_set_matrix = build_matrix::<BitSetColumn>(&spec_of(&matrix)).into_iter();
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let bit_set_dgm = LockFreeAlgorithm::init(Some(options.clone())).add_cols(bit_set_matrix).decompose().diagram();
             let vec_dgm = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
             assert_eq!(vec_dgm, bit_set_dgm);
         }
     }
 
+    proptest! {
+        #[test]
+        fn schedule_policies_agree( matrix in sut_matrix(100) ) {
+            let diagrams: Vec<_> = [SchedulePolicy::Static, SchedulePolicy::Guided, SchedulePolicy::Dynamic]
+                .into_iter()
+                .map(|schedule| {
+                    let options = LoPhatOptions {
+                        schedule,
+                        min_chunk_len: 4,
+                        ..Default::default()
+                    };
+                    LockFreeAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned()).decompose().diagram()
+                })
+                .collect();
+            assert_eq!(&diagrams[0], &diagrams[1]);
+            assert_eq!(&diagrams[0], &diagrams[2]);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn counts_match_diagram( matrix in sut_matrix(100) ) {
+            let decomp = LockFreeAlgorithm::init(None).add_cols(matrix.into_iter()).decompose();
+            let diagram = decomp.diagram();
+            assert_eq!(decomp.pair_count(), diagram.paired.len());
+            let essential: usize = decomp.essential_count_by_dim().values().sum();
+            assert_eq!(essential, diagram.unpaired.len());
+        }
+
+        #[test]
+        fn bar_lengths_match_diagram( matrix in sut_matrix(100) ) {
+            let decomp = LockFreeAlgorithm::init(None).add_cols(matrix.into_iter()).decompose();
+            let diagram = decomp.diagram();
+            let mut lengths: Vec<usize> = diagram.paired.iter().map(|(birth, death)| death - birth).collect();
+            lengths.sort_unstable();
+            lengths.reverse();
+            assert_eq!(decomp.bar_lengths(), lengths);
+            assert_eq!(decomp.essential_count(), diagram.unpaired.len());
+        }
+
+        #[test]
+        fn r_equals_dv( matrix in sut_matrix(100) ) {
+            let options = LoPhatOptions {
+                maintain_v: true,
+                clearing: false,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned()).decompose();
+            for j in 0..decomp.n_cols() {
+                let mut dv_col = VecColumn::new_with_dimension(0);
+                for i in decomp.get_v_col(j).unwrap().entries() {
+                    dv_col.add_col(&matrix[i]);
+                }
+                assert!(dv_col.entries().eq(decomp.get_r_col(j).entries()));
+            }
+        }
+
+        #[test]
+        fn scratch_agrees_with_fresh_allocation( matrix in sut_matrix(100) ) {
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let with_scratch = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(matrix.iter().cloned())
+                .decompose();
+            // reduce_column allocates a fresh scratch column on every call
+            let mut algo = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned());
+            algo.setup_pivots();
+            algo.setup_remaining_boundaries();
+            algo.setup_cleared();
+            (0..algo.matrix.len())
+                .into_par_iter()
+                .for_each(|j| algo.reduce_column(j));
+            let fresh = algo.into_decomposition();
+            assert_eq!(with_scratch.diagram(), fresh.diagram());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn parallel_reps_agree( matrix in sut_matrix(100) ) {
+            let options = LoPhatOptions {
+                maintain_v: true,
+                clearing: false,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose();
+            assert_eq!(decomp.all_representatives(), decomp.all_representatives_parallel());
+        }
+    }
+
+    #[cfg(feature = "bitvec")]
+    proptest! {
+        #[test]
+        fn bit_vec_cols_work( matrix in sut_matrix(100) ) {
+            let bit_vec_matrix = build_matrix::<crate::columns::BitVecColumn>(&spec_of(&matrix)).into_iter();
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let bit_vec_dgm = LockFreeAlgorithm::init(Some(options.clone())).add_cols(bit_vec_matrix).decompose().diagram();
+            let vec_dgm = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
+            assert_eq!(vec_dgm, bit_vec_dgm);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn u32_vec_cols_work( matrix in sut_matrix(100) ) {
+            let u32_matrix = matrix.iter().map(|col| {
+                let entries = col.entries().map(u32::from_usize).collect();
+                VecColumnT::<u32>::from((col.dimension(), entries))
+            });
+            let options = LoPhatOptions {
+                clearing: false,
+                ..Default::default()
+            };
+            let u32_dgm = LockFreeAlgorithm::init(Some(options.clone())).add_cols(u32_matrix).decompose().diagram();
+            let vec_dgm = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
+            assert_eq!(vec_dgm, u32_dgm);
+        }
+    }
+
+    #[test]
+    fn reduced_dimensions_are_skipped() {
+        let options = LoPhatOptions {
+            clearing: true,
+            ..Default::default()
+        };
+        let mut algo = LockFreeAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter());
+        algo.setup_pivots();
+        algo.setup_remaining_boundaries();
+        // Vertices are all cycles, so need no reduction
+        assert!(algo.dimension_is_reduced(0));
+        assert!(!algo.dimension_is_reduced(1));
+        assert!(!algo.dimension_is_reduced(2));
+        algo.reduce_dimension(2);
+        algo.clear_dimension(2);
+        // Three of the edges are cleared by triangles
+        assert_eq!(algo.remaining_boundaries[1].load(Relaxed), 3);
+        assert!(!algo.dimension_is_reduced(1));
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        assert_eq!(decomp.diagram(), correct_diagram);
+        // Dimension 0 is skipped
+        assert_eq!(decomp.n_reduced_dimensions(), 2);
+        // The only edge is cleared by the column above, leaving nothing to reduce in dimension 1.
+        // In a boundary matrix the columns paired with lower dimensions are never cleared, so this requires a matrix which is not one.
+        let matrix: Vec<VecColumn> = vec![(0, vec![]), (0, vec![]), (1, vec![0, 1]), (2, vec![2])]
+            .into_iter()
+            .map(VecColumn::from)
+            .collect();
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        assert_eq!(decomp.n_reduced_dimensions(), 1);
+        assert_eq!(
+            decomp.diagram(),
+            PersistenceDiagram {
+                unpaired: HashSet::from_iter(vec![0, 1]),
+                paired: HashSet::from_iter(vec![(2, 3)]),
+            }
+        );
+        // Without clearing, dimension 1 is reduced
+        let options = LoPhatOptions {
+            clearing: false,
+            ..Default::default()
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(matrix.into_iter())
+            .decompose();
+        assert_eq!(decomp.n_reduced_dimensions(), 2);
+    }
+
+    #[test]
+    fn owned_columns_equal_references() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let owned: Vec<(VecColumn, VecColumn)> = (0..decomp.n_cols())
+            .map(|idx| {
+                let r_col = decomp.get_r_col_owned(idx);
+                let v_col = decomp.get_v_col_owned(idx).unwrap();
+                assert_eq!(r_col, *decomp.get_r_col(idx));
+                assert_eq!(v_col, *decomp.get_v_col(idx).unwrap());
+                (r_col, v_col)
+            })
+            .collect();
+        // Owned columns outlive the decomposition and can be sent to another thread
+        drop(decomp);
+        let n_cycles =
+            std::thread::spawn(move || owned.iter().filter(|(r, _)| r.is_cycle()).count())
+                .join()
+                .unwrap();
+        assert_eq!(n_cycles, 8);
+        let decomp = LockFreeAlgorithm::<VecColumn>::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        assert!(decomp.get_v_col_owned(0).is_err());
+    }
+
+    #[test]
+    fn max_homology_dim_skips_higher_dimensions() {
+        // The boundary of the 4-simplex, i.e. a 3-sphere, with every simplex at the same filtration value
+        let simplices: Vec<(f64, Vec<usize>)> = (1..(1 << 5) - 1)
+            .map(|mask: usize| (0.0, (0..5).filter(|v| mask & (1 << v) != 0).collect()))
+            .collect();
+        let (matrix, _) = crate::utils::order_filtration(simplices);
+        let low_dim_features = |diagram: PersistenceDiagram| {
+            let mut paired: Vec<_> = diagram
+                .paired
+                .into_iter()
+                .filter(|&(birth, _)| matrix[birth].dimension() <= 1)
+                .collect();
+            let mut unpaired: Vec<_> = diagram
+                .unpaired
+                .into_iter()
+                .filter(|&idx| matrix[idx].dimension() <= 1)
+                .collect();
+            paired.sort_unstable();
+            unpaired.sort_unstable();
+            (paired, unpaired)
+        };
+        let full = LockFreeAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose()
+            .diagram();
+        let options = LoPhatOptions {
+            max_homology_dim: Some(1),
+            ..Default::default()
+        };
+        let algo = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned());
+        assert_eq!(algo.top_reduced_dimension(), 2);
+        let decomp = algo.decompose();
+        // The tetrahedra are never reduced
+        assert!((0..matrix.len())
+            .filter(|&idx| matrix[idx].dimension() == 3)
+            .all(|idx| *decomp.get_r_col(idx) == matrix[idx]));
+        let (paired, unpaired) = low_dim_features(decomp.diagram());
+        assert_eq!((paired.clone(), unpaired.clone()), low_dim_features(full));
+        // H_0 is a single point and H_1 is trivial, so 4 vertices and 6 edges are paired
+        assert_eq!(paired.len(), 10);
+        assert_eq!(unpaired, vec![0]);
+    }
+
+    #[test]
+    fn pair_events_match_diagram() {
+        use crate::algorithms::PairEvent;
+
+        let decomp = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let mut events = vec![];
+        decomp.for_each_pair(|event| events.push(event));
+        let mut from_events = PersistenceDiagram::default();
+        for event in events.iter() {
+            match *event {
+                PairEvent::Finite { birth, death } => {
+                    assert!(from_events.paired.insert((birth, death)))
+                }
+                PairEvent::Essential { birth } => assert!(from_events.unpaired.insert(birth)),
+            }
+        }
+        assert_eq!(from_events, decomp.diagram());
+        // Finite pairs come first, by increasing death
+        assert_eq!(events[0], PairEvent::Finite { birth: 1, death: 4 });
+        assert_eq!(events[6], PairEvent::Essential { birth: 0 });
+        assert_eq!(events[7], PairEvent::Essential { birth: 13 });
+    }
+
+    #[test]
+    fn written_diagram_parses_back() {
+        let decomp = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let mut buffer: Vec<u8> = vec![];
+        decomp.write_diagram(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let mut parsed = PersistenceDiagram::default();
+        for line in text.lines() {
+            let (birth, death) = line.split_once(' ').unwrap();
+            let birth: usize = birth.parse().unwrap();
+            if death == "inf" {
+                assert!(parsed.unpaired.insert(birth));
+            } else {
+                assert!(parsed.paired.insert((birth, death.parse().unwrap())));
+            }
+        }
+        assert_eq!(parsed, decomp.diagram());
+        // Same order as for_each_pair
+        assert_eq!(text.lines().next(), Some("1 4"));
+        assert_eq!(text.lines().last(), Some("13 inf"));
+    }
+
+    #[test]
+    fn cleared_columns_are_births() {
+        let decomp = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        // Edges cleared by triangles, then vertices cleared by edges
+        let cleared = decomp.cleared_columns();
+        assert_eq!(cleared, HashSet::from_iter(vec![1, 2, 3, 6, 8, 9]));
+        let births: HashSet<usize> = decomp.diagram().paired.iter().map(|p| p.0).collect();
+        assert_eq!(cleared, births);
+        let locking = crate::algorithms::LockingAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        assert_eq!(locking.cleared_columns(), cleared);
+        // Nothing is cleared without the optimisation
+        let options = LoPhatOptions {
+            clearing: false,
+            ..Default::default()
+        };
+        let no_clearing = LockFreeAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        assert!(no_clearing.cleared_columns().is_empty());
+    }
+
+    #[test]
+    fn non_square_height_is_inferred() {
+        // Rows extend beyond the number of columns, e.g. a coboundary restricted to some columns
+        let matrix: Vec<VecColumn> = vec![(0, vec![]), (1, vec![3, 5]), (1, vec![2, 5])]
+            .into_iter()
+            .map(VecColumn::from)
+            .collect();
+        let options = LoPhatOptions {
+            clearing: false,
+            ..Default::default()
+        };
+        let algo = LockFreeAlgorithm::init(Some(options.clone())).add_cols(matrix.iter().cloned());
+        assert_eq!(algo.effective_column_height(), 6);
+        let diagram = algo.decompose().diagram();
+        assert_eq!(diagram.paired, HashSet::from_iter(vec![(5, 1), (3, 2)]));
+        let locking = crate::algorithms::LockingAlgorithm::init(Some(options.clone()))
+            .add_cols(matrix.iter().cloned());
+        assert_eq!(locking.effective_column_height(), 6);
+        assert_eq!(locking.decompose().diagram(), diagram);
+        // An explicit column_height is used as given
+        let options = LoPhatOptions {
+            column_height: Some(10),
+            ..options
+        };
+        let algo = LockFreeAlgorithm::init(Some(options)).add_cols(matrix.into_iter());
+        assert_eq!(algo.effective_column_height(), 10);
+    }
+
+    fn check_inferred_height<Algo: DecompositionAlgo<VecColumn, Options = LoPhatOptions>>() {
+        // A tall matrix, with more rows than columns
+        let algo = Algo::init(None).add_cols_with_entries(
+            vec![(1, vec![0, 7]), (1, vec![2, 9]), (2, vec![1])].into_iter(),
+        );
+        assert_eq!(algo.n_cols_so_far(), 3);
+        assert_eq!(algo.inferred_column_height(), 10);
+        assert_eq!(Algo::init(None).inferred_column_height(), 0);
+    }
+
+    #[cfg(feature = "spill")]
+    #[test]
+    fn spilled_decomposition_agrees_with_in_memory() {
+        let dir = std::env::temp_dir().join(format!("lophat-spill-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for clearing in [false, true] {
+            let options = LoPhatOptions {
+                maintain_v: true,
+                clearing,
+                ..Default::default()
+            };
+            let in_memory = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            let spilled = LockFreeAlgorithm::init(Some(options))
+                .with_spill_dir(&dir)
+                .unwrap()
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+            // Every dimension was reduced, so only placeholders remain in memory
+            assert!(spilled.matrix.iter().all(|col| col.get_ref().0.is_empty()));
+            assert_eq!(spilled.diagram(), in_memory.diagram());
+            for idx in 0..in_memory.n_cols() {
+                assert_eq!(*spilled.get_r_col(idx), *in_memory.get_r_col(idx));
+                assert_eq!(
+                    *spilled.get_v_col(idx).unwrap(),
+                    *in_memory.get_v_col(idx).unwrap()
+                );
+            }
+            drop(spilled);
+            assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        }
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn sphere_bar_lengths() {
+        let decomp = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        // Pairs (1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)
+        assert_eq!(decomp.bar_lengths(), vec![6, 4, 3, 3, 2, 2]);
+        assert_eq!(decomp.essential_count(), 2);
+    }
+
+    // The Rips complex, up to triangles, of points evenly spaced around a circle with a small deterministic perturbation
+    fn noisy_circle_rips(n_points: usize) -> (Vec<VecColumn>, Vec<f64>) {
+        let points: Vec<(f64, f64)> = (0..n_points)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * i as f64 / n_points as f64;
+                let radius = 1.0 + 0.05 * ((i * 7 % 5) as f64 - 2.0);
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        let distance = |a: usize, b: usize| {
+            let (dx, dy) = (points[a].0 - points[b].0, points[a].1 - points[b].1);
+            (dx * dx + dy * dy).sqrt()
+        };
+        let mut simplices: Vec<(f64, Vec<usize>)> = (0..n_points).map(|i| (0.0, vec![i])).collect();
+        for a in 0..n_points {
+            for b in a + 1..n_points {
+                simplices.push((distance(a, b), vec![a, b]));
+                for c in b + 1..n_points {
+                    let value = distance(a, b).max(distance(a, c)).max(distance(b, c));
+                    simplices.push((value, vec![a, b, c]));
+                }
+            }
+        }
+        crate::utils::order_filtration(simplices)
+    }
+
+    #[test]
+    fn truncated_diagram_keeps_persistent_features() {
+        let (matrix, values) = noisy_circle_rips(16);
+        let dims: Vec<usize> = matrix.iter().map(|col| col.dimension()).collect();
+        // The bars of the given dimension, sorted
+        let bars_with = |max_col_len: Option<usize>, dimension: usize| {
+            // Truncated columns are no longer boundaries, so cannot be used for clearing
+            let options = LoPhatOptions {
+                max_col_len,
+                clearing: false,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options))
+                .add_cols(matrix.iter().cloned())
+                .decompose();
+            let mut barcode =
+                crate::utils::Barcode::from_diagram(&decomp.diagram(), &dims, &values);
+            barcode.sort();
+            let bars: Vec<(f64, f64)> = barcode
+                .bars
+                .into_iter()
+                .filter(|bar| bar.dimension == dimension)
+                .map(|bar| (bar.birth, bar.death))
+                .collect();
+            (decomp.is_approximate(), bars)
+        };
+        // A cap which is never reached changes nothing
+        assert_eq!(
+            bars_with(None, 1),
+            (false, bars_with(Some(matrix.len()), 1).1)
+        );
+        let (exact_is_approximate, exact) = bars_with(None, 1);
+        assert!(!exact_is_approximate);
+        let (is_approximate, truncated) = bars_with(Some(4), 1);
+        assert!(is_approximate);
+        // The circle is still found, along with every other bar of the exact diagram
+        let circle = exact
+            .iter()
+            .copied()
+            .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+            .unwrap();
+        assert!(circle.1 - circle.0 > 1.0);
+        assert!(exact.iter().all(|bar| truncated.contains(bar)));
+        // However, truncation also introduces spurious features
+        assert!(truncated.len() > exact.len());
+        // Components only depend on edges, whose columns are short, so are unaffected
+        assert_eq!(bars_with(None, 0).1, bars_with(Some(4), 0).1);
+    }
+
+    #[test]
+    fn inferred_column_height_of_tall_matrix() {
+        check_inferred_height::<LockFreeAlgorithm<VecColumn>>();
+        check_inferred_height::<crate::algorithms::LockingAlgorithm<VecColumn>>();
+        check_inferred_height::<crate::algorithms::SerialAlgorithm<VecColumn>>();
+    }
+
+    #[test]
+    fn streamed_diagrams_union_to_full_diagram() {
+        for clearing in [false, true] {
+            let options = LoPhatOptions {
+                clearing,
+                ..Default::default()
+            };
+            let full = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose()
+                .diagram();
+            let streamed: Vec<_> = LockFreeAlgorithm::init(Some(options))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose_streaming_diagram()
+                .collect();
+            let dimensions: Vec<usize> = streamed.iter().map(|(dim, _)| *dim).collect();
+            assert_eq!(dimensions, vec![0, 1, 2]);
+            let mut union = PersistenceDiagram::default();
+            for (_, diagram) in streamed.iter() {
+                union.paired.extend(diagram.paired.iter().copied());
+                union.unpaired.extend(diagram.unpaired.iter().copied());
+            }
+            assert_eq!(union, full);
+            // H_0 has three finite features and one essential class
+            let h0 = &streamed[0].1;
+            assert_eq!(h0.paired, HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7)]));
+            assert_eq!(h0.unpaired, HashSet::from_iter(vec![0]));
+        }
+    }
+
+    #[test]
+    fn view_based_cohomology_matches_materialised() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation();
+        for (clearing, maintain_v) in [(false, false), (true, false), (true, true)] {
+            let options = LoPhatOptions {
+                clearing,
+                maintain_v,
+                ..Default::default()
+            };
+            let materialised = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(crate::utils::anti_transpose(&matrix).into_iter())
+                .decompose();
+            let view = crate::utils::AntiTransposeView::new(&matrix);
+            let viewed = LockFreeAlgorithm::decompose_accessor(&view, Some(options));
+            assert_eq!(viewed.diagram(), materialised.diagram());
+            assert_eq!(viewed.cleared_columns(), materialised.cleared_columns());
+            for j in 0..matrix.len() {
+                assert_eq!(*viewed.get_r_col(j), *materialised.get_r_col(j));
+                if maintain_v {
+                    assert_eq!(
+                        *viewed.get_v_col(j).unwrap(),
+                        *materialised.get_v_col(j).unwrap()
+                    );
+                }
+            }
+        }
+        // Slices present themselves, so decomposing one agrees with decompose
+        let direct = LockFreeAlgorithm::decompose_accessor(matrix.as_slice(), None);
+        let owned = LockFreeAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        assert_eq!(direct.diagram(), owned.diagram());
+    }
+
+    #[test]
+    fn lazy_clearing_agrees_with_eager() {
+        for maintain_v in [false, true] {
+            let eager_options = LoPhatOptions {
+                maintain_v,
+                ..Default::default()
+            };
+            let lazy_options = LoPhatOptions {
+                lazy_clearing: true,
+                ..eager_options.clone()
+            };
+            let eager = LockFreeAlgorithm::init(Some(eager_options))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            let lazy = LockFreeAlgorithm::init(Some(lazy_options))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            assert_eq!(eager.diagram(), lazy.diagram());
+            assert_eq!(eager.cleared_columns(), lazy.cleared_columns());
+            for j in 0..eager.n_cols() {
+                assert_eq!(*eager.get_r_col(j), *lazy.get_r_col(j));
+                if maintain_v {
+                    assert_eq!(*eager.get_v_col(j).unwrap(), *lazy.get_v_col(j).unwrap());
+                }
+            }
+            // Without V, the cleared edges are never overwritten
+            let stored_boundary = lazy.matrix[6].get_ref().0.is_boundary();
+            assert_eq!(stored_boundary, !maintain_v);
+        }
+    }
+
+    fn check_v_on_clear<Algo: DecompositionAlgo<VecColumn, Options = LoPhatOptions>>() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let full = Algo::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let skipped = Algo::init(Some(LoPhatOptions {
+            v_on_clear: false,
+            ..options
+        }))
+        .add_cols(build_sphere_triangulation().into_iter())
+        .decompose();
+        assert_eq!(full.diagram(), skipped.diagram());
+        assert_eq!(full.all_representatives(), skipped.all_representatives());
+        let cleared = skipped.cleared_columns();
+        assert!(!cleared.is_empty());
+        for j in cleared {
+            assert!(skipped.get_v_col(j).unwrap().is_empty());
+            assert!(!full.get_v_col(j).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn skipping_v_on_clear_keeps_diagram_and_representatives() {
+        check_v_on_clear::<LockFreeAlgorithm<VecColumn>>();
+        check_v_on_clear::<crate::algorithms::LockingAlgorithm<VecColumn>>();
+    }
+
+    #[test]
+    fn provenance_of_cleared_column() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        // Edge 6 is the pivot of triangle 12, so is cleared and V[6] is R[12]
+        assert!(decomp.cleared_columns().contains(&6));
+        let provenance = decomp.provenance(6).unwrap();
+        assert!(provenance.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(decomp
+            .get_r_col(12)
+            .pivots_descending()
+            .eq(provenance.iter().rev().copied()));
+        // The columns of D in the provenance sum to R[6], which is empty
+        let mut sum = VecColumn::new_with_dimension(1);
+        for &j in provenance.iter() {
+            sum.add_col(&matrix[j]);
+        }
+        assert!(sum.is_empty() && decomp.get_r_col(6).is_empty());
+        let without_v = LockFreeAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose();
+        assert!(without_v.provenance(6).is_err());
+    }
+
+    #[test]
+    fn column_with_pivot_on_sphere() {
+        for assume_reduced in [false, true] {
+            let options = LoPhatOptions {
+                maintain_v: true,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(build_sphere_triangulation().into_iter())
+                .decompose();
+            let decomp = if assume_reduced {
+                // Feed R back in, so that the pivots array is never set up
+                let r = (0..decomp.n_cols()).map(|j| decomp.get_r_col_owned(j));
+                LockFreeAlgorithm::init(Some(LoPhatOptions {
+                    assume_reduced,
+                    ..options
+                }))
+                .add_cols(r)
+                .decompose()
+            } else {
+                decomp
+            };
+            let diagram = decomp.diagram();
+            for &(birth, death) in diagram.paired.iter() {
+                assert_eq!(decomp.column_with_pivot(birth), Some(death));
+            }
+            for l in [0, 10, 11, 12, 13, 100] {
+                assert_eq!(decomp.column_with_pivot(l), None);
+            }
+        }
+    }
+
+    #[test]
+    fn set_dimensions_enables_clearing() {
+        let dims: Vec<usize> = build_sphere_triangulation()
+            .iter()
+            .map(|col| col.dimension())
+            .collect();
+        let dimensionless = build_sphere_triangulation().into_iter().map(|mut col| {
+            col.set_dimension(0);
+            col
+        });
+        let decomp = LockFreeAlgorithm::init(None)
+            .add_cols(dimensionless)
+            .set_dimensions(dims.into_iter())
+            .decompose();
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        assert_eq!(decomp.diagram(), correct_diagram);
+        assert_eq!(
+            decomp.cleared_columns(),
+            HashSet::from_iter(vec![1, 2, 3, 6, 8, 9])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "local_thread_pool")]
+    fn nested_decompositions_complete() {
+        use rayon::ThreadPoolBuilder;
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        let options = LoPhatOptions {
+            num_threads: 2,
+            ..Default::default()
+        };
+        let outer_pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let diagrams: Vec<PersistenceDiagram> = outer_pool.install(|| {
+            (0..32)
+                .into_par_iter()
+                .map(|_| {
+                    LockFreeAlgorithm::init(Some(options.clone()))
+                        .add_cols(build_sphere_triangulation().into_iter())
+                        .decompose_nested()
+                        .diagram()
+                })
+                .collect()
+        });
+        assert_eq!(diagrams.len(), 32);
+        assert!(diagrams.iter().all(|diagram| *diagram == correct_diagram));
+        // Outside of a pool, behaves as decompose
+        let diagram = LockFreeAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose_nested()
+            .diagram();
+        assert_eq!(diagram, correct_diagram);
+    }
+
+    #[test]
+    #[cfg(feature = "local_thread_pool")]
+    fn local_pool_is_built_lazily() {
+        let is_built = |algo: &LockFreeAlgorithm<VecColumn>| match &algo.thread_pool {
+            LoPhatThreadPool::Local(pool) => pool.get().is_some(),
+            LoPhatThreadPool::Global() => false,
+        };
+        let algo = LockFreeAlgorithm::init(None).add_cols(build_sphere_triangulation().into_iter());
+        assert!(!is_built(&algo));
+        algo.clear_dimension(0);
+        assert!(is_built(&algo));
+        // Inside a rayon worker, decompose_nested runs on the current pool, so never builds the local pool
+        let outer_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let built_in_worker = outer_pool.install(|| {
+            let mut algo =
+                LockFreeAlgorithm::init(None).add_cols(build_sphere_triangulation().into_iter());
+            algo.use_current_pool_if_nested();
+            algo.clear_dimension(0);
+            is_built(&algo)
+        });
+        assert!(!built_in_worker);
+    }
+
+    #[test]
+    #[cfg(feature = "local_thread_pool")]
+    fn worker_threads_are_named() {
+        let options = LoPhatOptions {
+            num_threads: 2,
+            thread_name_prefix: Some("lophat".to_string()),
+            stack_size: Some(8 * 1024 * 1024),
+            ..Default::default()
+        };
+        let pool = crate::algorithms::build_thread_pool(&options);
+        let names: Vec<Option<String>> =
+            pool.broadcast(|_| std::thread::current().name().map(|name| name.to_string()));
+        let mut names: Vec<String> = names.into_iter().map(Option::unwrap).collect();
+        names.sort();
+        assert_eq!(names, vec!["lophat-0", "lophat-1"]);
+        let diagram = LockFreeAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose()
+            .diagram();
+        let default_diagram = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose()
+            .diagram();
+        assert_eq!(diagram, default_diagram);
+    }
+
+    #[test]
+    fn add_cols_with_entries_agrees_with_two_steps() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation();
+        let with_entries = matrix.iter().map(|col| {
+            // Provide entries out of order
+            let mut entries: Vec<usize> = col.entries().collect();
+            entries.reverse();
+            (col.dimension(), entries)
+        });
+        let one_step = LockFreeAlgorithm::<VecColumn>::init(None)
+            .add_cols_with_entries(with_entries)
+            .decompose();
+        let empty_cols = matrix
+            .iter()
+            .map(|col| VecColumn::new_with_dimension(col.dimension()));
+        let entries = matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(j, col)| col.entries().map(move |i| (i, j)));
+        let two_step = LockFreeAlgorithm::init(None)
+            .add_cols(empty_cols)
+            .add_entries(entries)
+            .decompose();
+        assert_eq!(one_step.diagram(), two_step.diagram());
+        for idx in 0..matrix.len() {
+            assert_eq!(*one_step.get_r_col(idx), *two_step.get_r_col(idx));
+        }
+    }
+
+    #[test]
+    fn decompose_into_diagram_agrees() {
+        let diagram = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose_into_diagram();
+        let expected = LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose()
+            .diagram();
+        assert_eq!(diagram, expected);
+        let serial_diagram = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose_into_diagram();
+        assert_eq!(serial_diagram, expected);
+    }
+
+    #[test]
+    fn n_cols_so_far_tracks_add_cols() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation();
+        let algo = LockFreeAlgorithm::init(None);
+        assert_eq!(algo.n_cols_so_far(), 0);
+        let algo = algo.add_cols(matrix[..10].iter().cloned());
+        assert_eq!(algo.n_cols_so_far(), 10);
+        let algo = algo.add_cols(matrix[10..].iter().cloned());
+        assert_eq!(algo.n_cols_so_far(), 14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Entry (row 5, column 2) has row index beyond column_height (5)")]
+    fn add_entries_reports_row_beyond_column_height() {
+        let options = LoPhatOptions {
+            column_height: Some(5),
+            ..Default::default()
+        };
+        LockFreeAlgorithm::<VecColumn>::init(Some(options))
+            .add_cols((0..3).map(VecColumn::new_with_dimension))
+            .add_entries(vec![(4, 2), (5, 2)].into_iter());
+    }
+
+    fn options_with_pair_filter() -> LoPhatOptions {
+        LoPhatOptions {
+            accept_pair: Some(std::sync::Arc::new(|_, _| true)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pair_filter_is_unsupported() {
+        let unsupported = Some(LophatError::UnsupportedOption {
+            option: "accept_pair",
+        });
+        let lockfree = LockFreeAlgorithm::init(Some(options_with_pair_filter()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .try_decompose();
+        assert_eq!(lockfree.err(), unsupported);
+        let locking = crate::algorithms::LockingAlgorithm::init(Some(options_with_pair_filter()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .try_decompose();
+        assert_eq!(locking.err(), unsupported);
+    }
+
+    #[test]
+    #[should_panic(expected = "Should only set accept_pair for the serial algorithm")]
+    fn pair_filter_panics_on_decompose() {
+        LockFreeAlgorithm::init(Some(options_with_pair_filter()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+    }
+
+    #[test]
+    fn try_methods_return_errors() {
+        let options = LoPhatOptions {
+            column_height: Some(5),
+            ..Default::default()
+        };
+        let new_algo = || {
+            LockFreeAlgorithm::<VecColumn>::init(Some(options.clone()))
+                .add_cols((0..3).map(VecColumn::new_with_dimension))
+        };
+        assert_eq!(
+            new_algo().try_add_entries(vec![(0, 3)].into_iter()).err(),
+            Some(LophatError::ColumnOutOfRange { col: 3, n_cols: 3 })
+        );
+        assert_eq!(
+            new_algo().try_add_entries(vec![(5, 2)].into_iter()).err(),
+            Some(LophatError::RowOutOfRange {
+                row: 5,
+                col: 2,
+                column_height: 5
+            })
+        );
+        assert_eq!(
+            new_algo().try_decompose().err(),
+            Some(LophatError::NonSquareWithClearing {
+                column_height: 5,
+                n_cols: 3
+            })
+        );
+        let decomp = new_algo()
+            .try_add_entries(vec![(0, 2), (1, 2)].into_iter())
+            .unwrap()
+            .add_cols((0..2).map(VecColumn::new_with_dimension))
+            .try_decompose()
+            .unwrap();
+        assert_eq!(decomp.get_r_col(2).pivot(), Some(1));
+    }
+
+    #[test]
+    fn snapshot_restore_agrees() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            num_threads: 1, // So we can predict the output
+            ..Default::default()
+        };
+        let straight = LockFreeAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        // Snapshot once the top dimension has been reduced
+        let mut snapshot = None;
+        let mut checkpoints = vec![];
+        let checkpointed = LockFreeAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose_with_checkpoint(|dimension, algo| {
+                checkpoints.push(dimension);
+                if dimension == 2 {
+                    snapshot = Some(algo.snapshot());
+                }
+            });
+        assert_eq!(checkpoints, vec![2, 1, 0]);
+        assert_eq!(checkpointed.diagram(), straight.diagram());
+        let snapshot = snapshot.unwrap();
+        // The pivots array is sized by the largest row index (9), rather than the number of columns
+        assert_eq!(snapshot.pivots.len(), 10);
+        assert_eq!(snapshot.pivots[6], Some(12));
+        // Restore and finish
+        let restored: LockFreeDecomposition<VecColumn> =
+            LockFreeAlgorithm::restore(snapshot, Some(options)).decompose();
+        assert_eq!(restored.diagram(), straight.diagram());
+        for idx in 0..restored.n_cols() {
+            assert_eq!(*restored.get_r_col(idx), *straight.get_r_col(idx));
+        }
+    }
+
+    // Describes each column as (dimension, entries), for use with build_matrix
+    fn spec_of(matrix: &[VecColumn]) -> Vec<(usize, Vec<usize>)> {
+        matrix
+            .iter()
+            .map(|col| (col.dimension(), col.entries().collect()))
+            .collect()
+    }
+
     // Generates a strict upper triangular matrix of VecColumns with given size
     fn sut_matrix(size: usize) -> impl Strategy<Value = Vec<VecColumn>> {
         let mut matrix = vec![];
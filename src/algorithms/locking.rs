@@ -2,21 +2,41 @@
 use crate::impl_rvd_serialize;
 
 use std::ops::Deref;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::Relaxed;
 use std::sync::RwLock;
 use std::sync::RwLockReadGuard;
 
 use crate::algorithms::Decomposition;
 use crate::columns::Column;
 use crate::columns::ColumnMode::{Storage, Working};
+use crate::error::LophatError;
 use crate::options::LoPhatOptions;
-use crate::utils::set_mode_of_pair;
+use crate::utils::{set_dimension_of_pair, set_mode_of_pair};
 
-use rayon::prelude::*;
 #[cfg(feature = "local_thread_pool")]
-use rayon::ThreadPoolBuilder;
+use super::build_thread_pool;
+#[cfg(not(feature = "local_thread_pool"))]
+use super::check_global_thread_pool;
+use hashbrown::HashSet;
+use rayon::prelude::*;
 
-use super::DecompositionAlgo;
+use super::strategy::{reduce_column_against, PairRef, ReducedColumn};
 use super::NoVMatrixError;
+use super::{
+    check_entry_row, collect_cleared, collect_dimensions, infer_column_height, reject_pair_filter,
+    validate_clearing, validate_entries, validate_no_pair_filter, DecompositionAlgo,
+};
+
+impl<C> PairRef<C> for RwLockReadGuard<'_, (C, Option<C>)> {
+    fn r_col(&self) -> &C {
+        &self.0
+    }
+
+    fn v_col(&self) -> Option<&C> {
+        self.1.as_ref()
+    }
+}
 
 enum LoPhatThreadPool {
     #[cfg(not(feature = "local_thread_pool"))]
@@ -46,15 +66,32 @@ impl LoPhatThreadPool {
 pub struct LockingAlgorithm<C: Column + 'static> {
     matrix: Vec<RwLock<(C, Option<C>)>>,
     pivots: Vec<RwLock<Option<usize>>>,
+    // Whether each column has been cleared, set during clear_with_column
+    cleared: Vec<AtomicBool>,
     options: LoPhatOptions,
     thread_pool: LoPhatThreadPool,
     max_dim: usize,
 }
 
 impl<'a, C: Column> LockingAlgorithm<C> {
+    // Pushes columns of R alongside their existing columns of V, so that column operations are applied on top of V rather than the identity
+    pub(crate) fn add_cols_with_v(mut self, cols: impl Iterator<Item = (C, Option<C>)>) -> Self {
+        let column_height = self.options.column_height;
+        let new_cols = cols.map(|(mut r_col, v_col)| {
+            self.max_dim = self.max_dim.max(r_col.dimension());
+            if let Some(column_height) = column_height {
+                r_col.reserve(column_height);
+            }
+            RwLock::new((r_col, v_col))
+        });
+        self.matrix.extend(new_cols);
+        self
+    }
+
     /// Return a column with index `l`, if one exists.
     /// If found, returns `(col_idx, col)`, where col is a tuple consisting of the corresponding column in R and V.
     /// If not maintaining V, second entry of tuple is `None`.
+    #[allow(clippy::type_complexity)]
     pub fn get_col_with_pivot(
         &'a self,
         l: usize,
@@ -87,44 +124,35 @@ impl<'a, C: Column> LockingAlgorithm<C> {
             // without locking other threads from reading
             let mut curr_column = self.matrix[working_j].read().unwrap().clone();
             set_mode_of_pair(&mut curr_column, Working);
-            while let Some(l) = (&curr_column).0.pivot() {
-                let piv_with_column_opt = self.get_col_with_pivot(l);
-                if let Some((piv, piv_column)) = piv_with_column_opt {
-                    // Lines 17-24
-                    if piv < working_j {
-                        curr_column.0.add_col(&piv_column.0);
-                        // Only add V columns if we need to
-                        if self.options.maintain_v {
-                            let curr_v_col = curr_column.1.as_mut().unwrap();
-                            curr_v_col.add_col(&piv_column.1.as_ref().unwrap());
-                        }
-                    } else if piv > working_j {
-                        self.write_to_matrix(working_j, curr_column);
-                        let mut pivot_lock = self.pivots[l].write().unwrap();
-                        if *pivot_lock == Some(piv) {
-                            *pivot_lock = Some(working_j);
-                            working_j = piv
-                        }
-                        continue 'outer;
-                    } else {
-                        panic!()
+            // Lines 17-24
+            let reduced = reduce_column_against(
+                working_j,
+                &mut curr_column.0,
+                curr_column.1.as_mut(),
+                |l| self.get_col_with_pivot(l),
+                |_| {},
+            );
+            self.write_to_matrix(working_j, curr_column);
+            match reduced {
+                ReducedColumn::ClaimedLater { pivot, owner } => {
+                    let mut pivot_lock = self.pivots[pivot].write().unwrap();
+                    if *pivot_lock == Some(owner) {
+                        *pivot_lock = Some(working_j);
+                        working_j = owner
                     }
-                } else {
-                    // piv = -1 case
-                    self.write_to_matrix(working_j, curr_column);
-                    let mut pivot_lock = self.pivots[l].write().unwrap();
-                    if *pivot_lock == None {
+                    continue 'outer;
+                }
+                // piv = -1 case
+                ReducedColumn::Unclaimed(pivot) => {
+                    let mut pivot_lock = self.pivots[pivot].write().unwrap();
+                    if pivot_lock.is_none() {
                         *pivot_lock = Some(working_j);
                         return;
-                    } else {
-                        continue 'outer;
                     }
+                    continue 'outer;
                 }
-            }
-            // Lines 25-27 (curr_column = 0 clause)
-            if (&curr_column.0).is_cycle() {
-                self.write_to_matrix(working_j, curr_column);
-                return;
+                // Lines 25-27 (curr_column = 0 clause)
+                ReducedColumn::Cycle => return,
             }
         }
     }
@@ -147,13 +175,19 @@ impl<'a, C: Column> LockingAlgorithm<C> {
         let clearing_dimension = self.matrix[clearing_idx].read().unwrap().0.dimension();
         // The cleared R column is empty
         let r_col = C::new_with_dimension(clearing_dimension);
-        // The corresponding V column should be the R column of the boundary
+        // The corresponding V column should be the R column of the boundary, unless not wanted
         let v_col = self.options.maintain_v.then(|| {
+            if !self.options.v_on_clear {
+                return C::new_with_dimension(clearing_dimension);
+            }
             let mut br = boundary_r.clone();
             br.set_dimension(clearing_dimension);
             br
         });
         self.write_to_matrix(clearing_idx, (r_col, v_col));
+        if let Some(cleared) = self.cleared.get(clearing_idx) {
+            cleared.store(true, Relaxed);
+        }
     }
 
     /// Reduce all columns of given dimension in parallel, according to `options`.
@@ -181,6 +215,70 @@ impl<'a, C: Column> LockingAlgorithm<C> {
         });
     }
 
+    /// Returns the height of the columns, used to size the pivots array.
+    /// This is [`column_height`](LoPhatOptions::column_height) if provided, otherwise one more than the largest row index in any column.
+    /// In the latter case, this requires a pass over the matrix so is computed once, at the start of [`decompose`](DecompositionAlgo::decompose).
+    pub fn effective_column_height(&self) -> usize {
+        self.options.column_height.unwrap_or_else(|| {
+            infer_column_height(self.matrix.iter().map(|col| col.read().unwrap().0.pivot()))
+        })
+    }
+
+    // Sets up the pivots and cleared arrays and then reduces, unless the input is assumed to be reduced
+    fn setup_and_reduce(&mut self) {
+        reject_pair_filter(&self.options);
+        // Setup pivots vector
+        let column_height = self.effective_column_height();
+        self.pivots = (0..column_height).map(|_| RwLock::new(None)).collect();
+        self.cleared = (0..self.matrix.len())
+            .map(|_| AtomicBool::new(false))
+            .collect();
+        if self.options.assume_reduced {
+            return;
+        }
+        // Decompose
+        for dimension in (0..=self.max_dim).rev() {
+            self.reduce_dimension(dimension);
+            if self.options.clearing && dimension > 0 {
+                self.clear_dimension(dimension)
+            }
+        }
+    }
+
+    // As for decompose, but moves out the reduced columns alongside each pivot and the column which claimed it
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn decompose_into_parts(mut self) -> (Vec<(C, Option<C>)>, Vec<(usize, usize)>) {
+        self.setup_and_reduce();
+        let columns: Vec<(C, Option<C>)> = self
+            .matrix
+            .into_iter()
+            .map(|col| {
+                col.into_inner()
+                    .expect("Column lock should not be poisoned")
+            })
+            .collect();
+        // The pivots array is not filled if the input is assumed to be reduced
+        let claimed = if self.options.assume_reduced {
+            columns
+                .iter()
+                .enumerate()
+                .filter_map(|(j, (r_col, _))| Some((r_col.pivot()?, j)))
+                .collect()
+        } else {
+            self.pivots
+                .into_iter()
+                .enumerate()
+                .filter_map(|(l, j)| {
+                    Some((
+                        l,
+                        j.into_inner().expect("Pivot lock should not be poisoned")?,
+                    ))
+                })
+                .collect()
+        };
+        (columns, claimed)
+    }
+
     /// Reduce all columns in parallel, according to `options`.
     pub fn reduce(&self) {
         for dimension in (0..=self.max_dim).rev() {
@@ -199,24 +297,16 @@ impl<C: Column> DecompositionAlgo<C> for LockingAlgorithm<C> {
         let options = options.unwrap_or_default();
         // Setup thread pool
         #[cfg(feature = "local_thread_pool")]
-        let thread_pool = LoPhatThreadPool::Local(
-            ThreadPoolBuilder::new()
-                .num_threads(options.num_threads)
-                .build()
-                .expect("Failed to build thread pool"),
-        );
+        let thread_pool = LoPhatThreadPool::Local(build_thread_pool(&options));
         #[cfg(not(feature = "local_thread_pool"))]
         let thread_pool = {
-            if options.num_threads != 0 {
-                panic!(
-                    "To specify a number of threads, please enable the local_thread_pool feature"
-                );
-            }
+            check_global_thread_pool(&options);
             LoPhatThreadPool::Global()
         };
         Self {
             matrix: vec![],
             pivots: vec![],
+            cleared: vec![],
             options,
             thread_pool,
             max_dim: 0,
@@ -225,8 +315,12 @@ impl<C: Column> DecompositionAlgo<C> for LockingAlgorithm<C> {
 
     fn add_cols(mut self, cols: impl Iterator<Item = C>) -> Self {
         let first_idx = self.matrix.len();
-        let new_cols = cols.enumerate().map(|(idx, r_col)| {
+        let column_height = self.options.column_height;
+        let new_cols = cols.enumerate().map(|(idx, mut r_col)| {
             self.max_dim = self.max_dim.max(r_col.dimension());
+            if let Some(column_height) = column_height {
+                r_col.reserve(column_height);
+            }
             if self.options.maintain_v {
                 let mut v_col = C::new_with_dimension(r_col.dimension());
                 v_col.add_entry(first_idx + idx);
@@ -241,6 +335,7 @@ impl<C: Column> DecompositionAlgo<C> for LockingAlgorithm<C> {
 
     fn add_entries(self, entries: impl Iterator<Item = (usize, usize)>) -> Self {
         for (row, col) in entries {
+            check_entry_row(row, col, self.options.column_height);
             let mut col = self
                 .matrix
                 .get(col)
@@ -252,25 +347,66 @@ impl<C: Column> DecompositionAlgo<C> for LockingAlgorithm<C> {
         self
     }
 
+    /// Additionally checks that each row lies within `column_height`, if provided.
+    fn try_add_entries(
+        self,
+        entries: impl Iterator<Item = (usize, usize)>,
+    ) -> Result<Self, LophatError> {
+        let entries: Vec<_> = entries.collect();
+        validate_entries(&entries, self.matrix.len(), self.options.column_height)?;
+        Ok(self.add_entries(entries.into_iter()))
+    }
+
+    /// Returns an error if clearing is requested but `column_height` does not match the number of columns,
+    /// or if [`accept_pair`](LoPhatOptions::accept_pair) is set.
+    fn try_decompose(self) -> Result<Self::Decomposition, LophatError> {
+        validate_clearing(&self.options, self.matrix.len())?;
+        validate_no_pair_filter(&self.options)?;
+        Ok(self.decompose())
+    }
+
+    fn set_dimensions(mut self, dims: impl Iterator<Item = usize>) -> Self {
+        let dims = collect_dimensions(dims, self.matrix.len());
+        self.max_dim = dims.iter().copied().max().unwrap_or(0);
+        for (col, dim) in self.matrix.iter_mut().zip(dims) {
+            let col = col.get_mut().expect("Column lock should not be poisoned");
+            set_dimension_of_pair(col, dim);
+        }
+        self
+    }
+
+    fn n_cols_so_far(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn lophat_options(&self) -> Option<&LoPhatOptions> {
+        Some(&self.options)
+    }
+
+    fn inferred_column_height(&self) -> usize {
+        self.effective_column_height()
+    }
+
     type Decomposition = LockingDecomposition<C>;
 
     fn decompose(mut self) -> Self::Decomposition {
-        // Setup pivots vector
-        let column_height = self.options.column_height.unwrap_or(self.matrix.len());
-        self.pivots = (0..column_height).map(|_| RwLock::new(None)).collect();
-        // Decompose
-        for dimension in (0..=self.max_dim).rev() {
-            self.reduce_dimension(dimension);
-            if self.options.clearing && dimension > 0 {
-                self.clear_dimension(dimension)
-            }
+        self.setup_and_reduce();
+        if self.options.assume_reduced {
+            let decomposition = LockingDecomposition(self.matrix, HashSet::new());
+            debug_assert!(
+                decomposition.is_reduced(),
+                "Input should be reduced when assume_reduced is set"
+            );
+            return decomposition;
         }
-        LockingDecomposition(self.matrix)
+        let cleared = collect_cleared(&self.cleared);
+        LockingDecomposition(self.matrix, cleared)
     }
 }
 
 /// Return type of [`LockingAlgorithm`].
-pub struct LockingDecomposition<C: Column + 'static>(Vec<RwLock<(C, Option<C>)>>);
+/// Alongside the columns, stores the indices of the columns which were cleared.
+pub struct LockingDecomposition<C: Column + 'static>(Vec<RwLock<(C, Option<C>)>>, HashSet<usize>);
 
 pub struct LockingRRef<'a, C>(RwLockReadGuard<'a, (C, Option<C>)>);
 
@@ -288,17 +424,23 @@ impl<'a, C> Deref for LockingVRef<'a, C> {
     type Target = C;
 
     fn deref(&self) -> &Self::Target {
-        &self.0.deref().1.as_ref().unwrap()
+        self.0.deref().1.as_ref().unwrap()
     }
 }
 
 impl<C: Column + 'static> Decomposition<C> for LockingDecomposition<C> {
-    type RColRef<'a> = LockingRRef<'a, C> where Self : 'a;
+    type RColRef<'a>
+        = LockingRRef<'a, C>
+    where
+        Self: 'a;
     fn get_r_col<'a>(&'a self, index: usize) -> Self::RColRef<'a> {
         LockingRRef(self.0[index].read().unwrap())
     }
 
-    type VColRef<'a> = LockingVRef<'a, C> where Self : 'a;
+    type VColRef<'a>
+        = LockingVRef<'a, C>
+    where
+        Self: 'a;
     fn get_v_col<'a>(&'a self, index: usize) -> Result<Self::VColRef<'a>, NoVMatrixError> {
         let col_ref = self.0[index].read().unwrap();
         let has_v = col_ref.1.is_some();
@@ -312,6 +454,10 @@ impl<C: Column + 'static> Decomposition<C> for LockingDecomposition<C> {
     fn n_cols(&self) -> usize {
         self.0.len()
     }
+
+    fn cleared_columns(&self) -> HashSet<usize> {
+        self.1.clone()
+    }
 }
 
 #[cfg(test)]
@@ -327,7 +473,7 @@ mod tests {
         #[test]
         fn locking_agrees_with_serial( matrix in sut_matrix(100) ) {
             let options = LoPhatOptions::default();
-            let serial_dgm = SerialAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned()).decompose().diagram();
+            let serial_dgm = SerialAlgorithm::init(Some(options.clone())).add_cols(matrix.iter().cloned()).decompose().diagram();
             let parallel_dgm = LockingAlgorithm::init(Some(options)).add_cols(matrix.into_iter()).decompose().diagram();
             assert_eq!(serial_dgm, parallel_dgm);
         }
@@ -3,22 +3,68 @@
 //! Each algorithm is encapsulated in a struct and the main interface to these structs is the [`DecompositionAlgo`] trait.
 //! By providing appropriate options during construction, each algorithm can also maintain V in the R=DV decomposition.
 
-use crate::{columns::Column, utils::PersistenceDiagram};
-use hashbrown::HashSet;
+use crate::{
+    columns::Column,
+    error::LophatError,
+    options::LoPhatOptions,
+    utils::{transpose, ColumnReader, PersistenceDiagram, RepresentativeSet},
+};
+use bit_set::BitSet;
+use hashbrown::{HashMap, HashSet};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::time::{Duration, Instant};
 
+mod annotation;
+mod frozen;
 mod lock_free;
 mod locking;
+mod relative;
 mod serial;
+#[cfg(feature = "spill")]
+mod spill;
+mod strategy;
 
-pub use lock_free::{LockFreeAlgorithm, LockFreeDecomposition};
+pub use annotation::{CohomologyAnnotationAlgorithm, CohomologyAnnotationDecomposition};
+pub use frozen::{FrozenColumn, FrozenDecomposition};
+pub use lock_free::{LockFreeAlgorithm, LockFreeDecomposition, ReductionSnapshot};
 pub use locking::{LockingAlgorithm, LockingDecomposition};
-pub use serial::{SerialAlgorithm, SerialDecomposition};
+pub use relative::decompose_relative_cohomology;
+pub use serial::{
+    decompose_blocks, InPlaceSerialDecomposition, SerialAlgorithm, SerialDecomposition,
+};
+pub use strategy::{
+    LockFreeStrategy, LockingStrategy, ReductionStrategy, SerialStrategy, StrategyAlgorithm,
+    StrategyDecomposition,
+};
 
 /// Error type returned when attempting to query a column of V from a decomposition in which V was not maintained.
 #[derive(Debug)]
 pub struct NoVMatrixError;
 
+/// The role played by a column in the persistence pairing, as returned by [`Decomposition::classify`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnClass {
+    /// The column creates a feature, which is destroyed by the column with the given index.
+    Birth(usize),
+    /// The column destroys the feature created by the column with the given index.
+    Death(usize),
+    /// The column creates a feature which is never destroyed.
+    Essential,
+}
+
+/// A single feature of the persistence diagram, as passed to the callback of [`Decomposition::for_each_pair`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairEvent {
+    /// The feature created by column `birth` is destroyed by column `death`.
+    Finite { birth: usize, death: usize },
+    /// The feature created by column `birth` is never destroyed.
+    Essential { birth: usize },
+}
+
 /// A struct implementing this trait represents the output of an R=DV decomposition of a matrix D and is typically constructed by [`DecompositionAlgo::decompose`].
 ///
 /// The main required methods are [`get_r_col`](Decomposition::get_r_col) and [`get_v_col`](Decomposition::get_v_col), which return immutable references to columns of the R and V matrix respectively.
@@ -45,22 +91,333 @@ where
     /// Returns the number of column in R (equal to the number of columns in D).
     fn n_cols(&self) -> usize;
 
+    /// As for [`get_r_col`](Self::get_r_col) but returns an owned clone of the column, which does not borrow from the decomposition.
+    fn get_r_col_owned(&self, index: usize) -> C {
+        self.get_r_col(index).clone()
+    }
+
+    /// As for [`get_v_col`](Self::get_v_col) but returns an owned clone of the column, which does not borrow from the decomposition.
+    fn get_v_col_owned(&self, index: usize) -> Result<C, NoVMatrixError> {
+        self.get_v_col(index).map(|col| col.clone())
+    }
+
+    /// Returns the indices of the columns of D which were summed to form column `idx` of R, in increasing order.
+    /// These are the entries of column `idx` of V, so can be used to trace a feature back to the input simplices.
+    /// Returns `NoVMatrixError` if V was not maintained by the algorithm.
+    fn provenance(&self, idx: usize) -> Result<Vec<usize>, NoVMatrixError> {
+        Ok(sorted_entries(&*self.get_v_col(idx)?))
+    }
+
+    /// Computes the product `V * chain`, i.e. the sum of the columns of V indexed by the entries of `chain`.
+    ///
+    /// This is V itself, not its inverse: the columns of V form a new basis of chains, and this maps a chain expressed in coordinates with respect to that basis back to the original basis.
+    /// In particular, applying V to the basis vector with a single entry `j` returns column `j` of V.
+    /// The returned column has the same dimension as `chain`.
+    /// Returns `NoVMatrixError` if V was not maintained by the algorithm.
+    fn apply_v(&self, chain: &C) -> Result<C, NoVMatrixError> {
+        let mut product = C::new_with_dimension(chain.dimension());
+        for idx in chain.entries() {
+            product.add_col(&*self.get_v_col(idx)?);
+        }
+        Ok(product)
+    }
+
     /// Uses the methods implemented by this trait to read-off the column pairings which constiute the persistence diagram.
     fn diagram(&self) -> PersistenceDiagram {
         let r_col_iter = (0..self.n_cols()).map(|idx| self.get_r_col(idx));
-        let paired: HashSet<(usize, usize)> = r_col_iter
-            .enumerate()
-            .filter_map(|(idx, col)| {
-                let lowest_idx = col.pivot()?;
-                Some((lowest_idx, idx))
-            })
+        diagram_from_r_cols(r_col_iter, self.n_cols())
+    }
+
+    /// Calls `f` on each feature of the persistence diagram, without collecting the diagram.
+    /// This is the push-based complement to [`diagram`](Decomposition::diagram), e.g. for streaming pairs to disk.
+    ///
+    /// The finite pairs are visited first, in increasing order of death, followed by the essential features, in increasing order of birth.
+    /// The only allocation is a bit per column, recording which columns are paired.
+    fn for_each_pair(&self, mut f: impl FnMut(PairEvent)) {
+        let mut paired = BitSet::with_capacity(self.n_cols());
+        for death in 0..self.n_cols() {
+            if let Some(birth) = self.get_r_col(death).pivot() {
+                paired.insert(birth);
+                f(PairEvent::Finite { birth, death });
+            }
+        }
+        for birth in 0..self.n_cols() {
+            if !paired.contains(birth) && self.get_r_col(birth).is_cycle() {
+                f(PairEvent::Essential { birth });
+            }
+        }
+    }
+
+    /// Writes each feature of the persistence diagram to `writer`, one per line, without collecting the diagram.
+    /// This is the IO-bound complement to [`for_each_pair`](Decomposition::for_each_pair), and visits features in the same deterministic order.
+    ///
+    /// A finite pair is written as `birth death` and an essential feature as `birth inf`.
+    /// Stops at, and returns, the first error from `writer`.
+    fn write_diagram<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut result = Ok(());
+        self.for_each_pair(|event| {
+            if result.is_err() {
+                return;
+            }
+            result = match event {
+                PairEvent::Finite { birth, death } => writeln!(writer, "{birth} {death}"),
+                PairEvent::Essential { birth } => writeln!(writer, "{birth} inf"),
+            };
+        });
+        result
+    }
+
+    /// As for [`diagram`](Decomposition::diagram) but drops any pair `(birth, death)` with index persistence `death - birth` less than `min_persistence`.
+    /// The dropped indices are not considered to be unpaired.
+    ///
+    /// If the decomposed matrix was not upper-triangular then a pair may have `birth > death`, in which case its persistence is taken to be `birth - death`.
+    fn diagram_filtered(&self, min_persistence: usize) -> PersistenceDiagram {
+        let mut diagram = self.diagram();
+        diagram
+            .paired
+            .retain(|(birth, death)| death.abs_diff(*birth) >= min_persistence);
+        diagram
+    }
+
+    /// As for [`diagram`](Decomposition::diagram) but only records a pair `(birth, death)` if `death` is in `death_allowed`.
+    /// The birth of any other pair is recorded as unpaired, as though its death never occurs.
+    ///
+    /// For example, if D is the boundary matrix of a complex L, filtered so that a subcomplex K appears first, then passing the indices of K as `death_allowed` reads off the diagram of K (together with the essential classes of L that are born outside K).
+    /// This is not full image persistence, but is a useful building block for such computations.
+    fn diagram_restricted(&self, death_allowed: &HashSet<usize>) -> PersistenceDiagram {
+        let mut diagram = PersistenceDiagram::default();
+        self.for_each_pair(|event| match event {
+            PairEvent::Finite { birth, death } if death_allowed.contains(&death) => {
+                diagram.paired.insert((birth, death));
+            }
+            PairEvent::Finite { birth, .. } | PairEvent::Essential { birth } => {
+                diagram.unpaired.insert(birth);
+            }
+        });
+        diagram
+    }
+
+    /// Returns the role of column `idx` in the persistence pairing.
+    ///
+    /// The provided implementation scans the columns of R after `idx` for a pivot in row `idx`.
+    /// Implementors with access to a map from pivots to columns should override this.
+    fn classify(&self, idx: usize) -> ColumnClass {
+        if let Some(birth) = self.get_r_col(idx).pivot() {
+            return ColumnClass::Death(birth);
+        }
+        match (idx + 1..self.n_cols()).find(|&j| self.get_r_col(j).pivot() == Some(idx)) {
+            Some(death) => ColumnClass::Birth(death),
+            None => ColumnClass::Essential,
+        }
+    }
+
+    /// Returns the index of the column of R with pivot `l`, or `None` if there is no such column.
+    /// In a reduced matrix this column is unique.
+    ///
+    /// The provided implementation scans the columns of R.
+    /// Implementors with access to a map from pivots to columns should override this.
+    fn column_with_pivot(&self, l: usize) -> Option<usize> {
+        (0..self.n_cols()).find(|&j| self.get_r_col(j).pivot() == Some(l))
+    }
+
+    /// Returns the index paired with column `idx`, or `None` if `idx` is unpaired.
+    /// Uses [`classify`](Decomposition::classify).
+    fn pair_of(&self, idx: usize) -> Option<usize> {
+        match self.classify(idx) {
+            ColumnClass::Birth(partner) | ColumnClass::Death(partner) => Some(partner),
+            ColumnClass::Essential => None,
+        }
+    }
+
+    /// Returns the index-persistence `death - birth` of the feature created by column `birth`.
+    /// Returns `None` if `birth` is essential, or is not a birth at all (i.e. it is a death column).
+    /// Also returns `None` if `birth` dies at an earlier index, which can only happen if the decomposed matrix was not upper-triangular.
+    /// Uses [`classify`](Decomposition::classify), so is answered from the map from pivots to columns when the implementor has one.
+    fn lifetime(&self, birth: usize) -> Option<usize> {
+        match self.classify(birth) {
+            ColumnClass::Birth(death) => death.checked_sub(birth),
+            ColumnClass::Death(_) | ColumnClass::Essential => None,
+        }
+    }
+
+    /// Returns the pairing as a vector of length [`n_cols`](Decomposition::n_cols), in which entry `i` is the index paired with `i`, or `None` if `i` is unpaired.
+    /// Each pairing is recorded in both directions, so that `pair[birth] = Some(death)` and `pair[death] = Some(birth)`.
+    ///
+    /// Assumes that the decomposed matrix is square.
+    fn pairing_array(&self) -> Vec<Option<usize>> {
+        let mut pair = vec![None; self.n_cols()];
+        for death in 0..self.n_cols() {
+            if let Some(birth) = self.get_r_col(death).pivot() {
+                pair[birth] = Some(death);
+                pair[death] = Some(birth);
+            }
+        }
+        pair
+    }
+
+    /// Returns the rank of the boundary map in each dimension, i.e. the number of columns of R in dimension `d` which are not cycles.
+    /// Dimensions in which every column of R is a cycle are omitted.
+    ///
+    /// For a filtered simplicial complex, the Betti numbers can be recovered as
+    /// `β_d = (#dim-d cols) - rank(∂_d) - rank(∂_{d+1})`.
+    fn boundary_ranks(&self) -> HashMap<usize, usize> {
+        let mut ranks = HashMap::new();
+        for idx in 0..self.n_cols() {
+            let r_col = self.get_r_col(idx);
+            if r_col.is_boundary() {
+                *ranks.entry(r_col.dimension()).or_insert(0) += 1;
+            }
+        }
+        ranks
+    }
+
+    /// Returns the number of pairs in the [`diagram`](Decomposition::diagram), i.e. the number of columns of R with a pivot.
+    /// Does not allocate.
+    fn pair_count(&self) -> usize {
+        (0..self.n_cols())
+            .filter(|&idx| self.get_r_col(idx).pivot().is_some())
+            .count()
+    }
+
+    /// Returns the number of unpaired columns in each dimension, omitting dimensions with none.
+    /// Rather than constructing the full [`diagram`](Decomposition::diagram), only allocates one bit per row.
+    fn essential_count_by_dim(&self) -> HashMap<usize, usize> {
+        // Pivots may exceed n_cols if the matrix is tall
+        let mut births = BitSet::new();
+        for idx in 0..self.n_cols() {
+            if let Some(birth) = self.get_r_col(idx).pivot() {
+                births.insert(birth);
+            }
+        }
+        let mut counts = HashMap::new();
+        for idx in 0..self.n_cols() {
+            let r_col = self.get_r_col(idx);
+            if !births.contains(idx) && r_col.is_cycle() {
+                *counts.entry(r_col.dimension()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns the index persistence `death - birth` of every finite pair, sorted in decreasing order.
+    /// Rather than constructing the full [`diagram`](Decomposition::diagram), only reads the pivot of each column of R.
+    ///
+    /// If the decomposed matrix was not upper-triangular then a pair may have `birth > death`, in which case its persistence is taken to be `birth - death`.
+    fn bar_lengths(&self) -> Vec<usize> {
+        let mut lengths: Vec<usize> = (0..self.n_cols())
+            .filter_map(|death| Some(death.abs_diff(self.get_r_col(death).pivot()?)))
             .collect();
-        let mut unpaired: HashSet<usize> = (0..self.n_cols()).collect();
-        for (birth, death) in paired.iter() {
-            unpaired.remove(birth);
-            unpaired.remove(death);
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        lengths
+    }
+
+    /// Returns the number of unpaired columns in the [`diagram`](Decomposition::diagram), across all dimensions.
+    /// Uses [`essential_count_by_dim`](Decomposition::essential_count_by_dim).
+    fn essential_count(&self) -> usize {
+        self.essential_count_by_dim().values().sum()
+    }
+
+    /// Returns, for each dimension, the indices of the columns of that dimension in increasing order.
+    /// Dimensions with no columns are omitted.
+    fn columns_by_dimension(&self) -> BTreeMap<usize, Vec<usize>> {
+        let mut by_dimension: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for idx in 0..self.n_cols() {
+            let dimension = self.get_r_col(idx).dimension();
+            by_dimension.entry(dimension).or_default().push(idx);
+        }
+        by_dimension
+    }
+
+    /// Returns every pair of columns `(i, j)`, with `i < j`, for which the columns of R share a pivot.
+    /// Each such `j` is paired with the first column `i` having that pivot.
+    ///
+    /// In a correct reduction this is always empty, so this is intended as a self-check, e.g. when developing a new algorithm.
+    fn pivot_collisions(&self) -> Vec<(usize, usize)> {
+        let mut first_with_pivot = HashMap::new();
+        let mut collisions = vec![];
+        for idx in 0..self.n_cols() {
+            if let Some(pivot) = self.get_r_col(idx).pivot() {
+                if let Some(&first) = first_with_pivot.get(&pivot) {
+                    collisions.push((first, idx));
+                } else {
+                    first_with_pivot.insert(pivot, idx);
+                }
+            }
+        }
+        collisions
+    }
+
+    /// Returns true if R is reduced, i.e. no two columns of R share a pivot (see [`pivot_collisions`](Decomposition::pivot_collisions)).
+    fn is_reduced(&self) -> bool {
+        self.pivot_collisions().is_empty()
+    }
+
+    /// Returns the Betti number in each dimension in which there is some column, i.e. the number of unpaired columns of that dimension.
+    /// Unlike [`essential_count_by_dim`](Decomposition::essential_count_by_dim), dimensions with Betti number zero are included.
+    ///
+    /// Assumes that D is the boundary matrix of a complex, with the dimension of each column set correctly.
+    fn betti_numbers(&self) -> HashMap<usize, usize> {
+        let mut betti = self.essential_count_by_dim();
+        for dimension in self.columns_by_dimension().into_keys() {
+            betti.entry(dimension).or_insert(0);
         }
-        PersistenceDiagram { unpaired, paired }
+        betti
+    }
+
+    /// Returns the indices of the columns which were cleared by the clearing optimisation.
+    /// The columns of R (and V) at these indices differ from those of the standard reduction, though the diagram is unchanged.
+    ///
+    /// The provided implementation returns the empty set, which is correct for algorithms that do not employ clearing.
+    fn cleared_columns(&self) -> HashSet<usize> {
+        HashSet::new()
+    }
+
+    /// Collects a representative for every feature in the [`diagram`](Decomposition::diagram).
+    /// Paired features are represented by columns of R and unpaired features by columns of V.
+    ///
+    /// Panics if V was not maintained by the algorithm and there is some unpaired column.
+    fn all_representatives(&self) -> RepresentativeSet {
+        let diagram = self.diagram();
+        let paired = diagram
+            .paired
+            .iter()
+            .map(|&(birth, death)| ((birth, death), sorted_entries(&*self.get_r_col(death))))
+            .collect();
+        let unpaired = diagram
+            .unpaired
+            .iter()
+            .map(|&birth| {
+                let v_col = self
+                    .get_v_col(birth)
+                    .expect("V should be maintained to find representatives of unpaired columns");
+                (birth, sorted_entries(&*v_col))
+            })
+            .collect();
+        RepresentativeSet { paired, unpaired }
+    }
+
+    /// As for [`all_representatives`](Decomposition::all_representatives) but the columns are read and sorted in parallel, using rayon.
+    fn all_representatives_parallel(&self) -> RepresentativeSet
+    where
+        Self: Sync,
+    {
+        let diagram = self.diagram();
+        let paired = diagram
+            .paired
+            .par_iter()
+            .map(|&(birth, death)| ((birth, death), sorted_entries(&*self.get_r_col(death))))
+            .collect();
+        let unpaired = diagram
+            .unpaired
+            .par_iter()
+            .map(|&birth| {
+                let v_col = self
+                    .get_v_col(birth)
+                    .expect("V should be maintained to find representatives of unpaired columns");
+                (birth, sorted_entries(&*v_col))
+            })
+            .collect();
+        RepresentativeSet { paired, unpaired }
     }
 
     /// By checking whether `self.get_v_col(0)` returns an error, determines whether the V matrix was maintained for this decomposition.
@@ -69,6 +426,192 @@ where
         // Otherwise we just check whether we can get the first v column
         self.n_cols() == 0 || self.get_v_col(0).is_ok()
     }
+
+    /// Returns whether the decomposition is only approximate, e.g. because columns were truncated during reduction (see [`max_col_len`](LoPhatOptions::max_col_len)).
+    /// In this case the [`diagram`](Self::diagram) may differ from the true persistence diagram and R=DV need not hold.
+    fn is_approximate(&self) -> bool {
+        false
+    }
+
+    /// Renders R, and V if maintained, as a human-readable dump intended for debugging small matrices.
+    ///
+    /// Each matrix is headed by its name, followed by one line per column of the form `idx(dim): [entries]`, with entries sorted in increasing order.
+    /// In R, the pivot of each non-empty column is marked with an asterisk, e.g. `10(2): [4, 7, 8*]`.
+    fn pretty_print(&self) -> String {
+        let mut lines = vec!["R:".to_string()];
+        for idx in 0..self.n_cols() {
+            let r_col = self.get_r_col(idx);
+            lines.push(pretty_column(idx, &*r_col, r_col.pivot()));
+        }
+        if self.has_v() && self.n_cols() > 0 {
+            lines.push("V:".to_string());
+            for idx in 0..self.n_cols() {
+                let v_col = self
+                    .get_v_col(idx)
+                    .expect("V should be maintained for every column");
+                lines.push(pretty_column(idx, &*v_col, None));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+// A single line of Decomposition::pretty_print, marking the entry `marked` with an asterisk
+fn pretty_column<C: Column>(idx: usize, col: &C, marked: Option<usize>) -> String {
+    let entries: Vec<String> = sorted_entries(col)
+        .into_iter()
+        .map(|entry| {
+            if Some(entry) == marked {
+                format!("{entry}*")
+            } else {
+                entry.to_string()
+            }
+        })
+        .collect();
+    format!("{idx}({}): [{}]", col.dimension(), entries.join(", "))
+}
+
+// Returns the entries of the column, sorted in increasing order
+fn sorted_entries<C: Column>(col: &C) -> Vec<usize> {
+    let mut entries: Vec<usize> = col.entries().collect();
+    entries.sort_unstable();
+    entries
+}
+
+// Reads off the pairings from the columns of R, provided in order.
+pub(crate) fn diagram_from_r_cols<C: Column, R: Deref<Target = C>>(
+    r_col_iter: impl Iterator<Item = R>,
+    n_cols: usize,
+) -> PersistenceDiagram {
+    let paired: HashSet<(usize, usize)> = r_col_iter
+        .enumerate()
+        .filter_map(|(idx, col)| {
+            let lowest_idx = col.pivot()?;
+            Some((lowest_idx, idx))
+        })
+        .collect();
+    let mut unpaired: HashSet<usize> = (0..n_cols).collect();
+    for (birth, death) in paired.iter() {
+        unpaired.remove(birth);
+        unpaired.remove(death);
+    }
+    PersistenceDiagram { unpaired, paired }
+}
+
+// Builds the thread pool described by `options`
+#[cfg(feature = "local_thread_pool")]
+pub(crate) fn build_thread_pool(options: &LoPhatOptions) -> rayon::ThreadPool {
+    let mut builder = rayon::ThreadPoolBuilder::new().num_threads(options.num_threads);
+    if let Some(prefix) = options.thread_name_prefix.clone() {
+        builder = builder.thread_name(move |idx| format!("{prefix}-{idx}"));
+    }
+    if let Some(stack_size) = options.stack_size {
+        builder = builder.stack_size(stack_size);
+    }
+    builder.build().expect("Failed to build thread pool")
+}
+
+// Panics if `options` configures the worker threads, which requires the local_thread_pool feature
+#[cfg(not(feature = "local_thread_pool"))]
+pub(crate) fn check_global_thread_pool(options: &LoPhatOptions) {
+    if options.num_threads != 0 {
+        panic!("To specify a number of threads, please enable the local_thread_pool feature");
+    }
+    if options.thread_name_prefix.is_some() || options.stack_size.is_some() {
+        panic!("To configure the worker threads, please enable the local_thread_pool feature");
+    }
+}
+
+// Returns one more than the largest pivot, i.e. the smallest column_height which fits every column
+pub(crate) fn infer_column_height(pivots: impl Iterator<Item = Option<usize>>) -> usize {
+    pivots.flatten().max().map_or(0, |pivot| pivot + 1)
+}
+
+// Collects the indices of those columns which are flagged as cleared, into any set type
+pub(crate) fn collect_cleared<S: FromIterator<usize>>(cleared: &[AtomicBool]) -> S {
+    cleared
+        .iter()
+        .enumerate()
+        .filter(|(_, is_cleared)| is_cleared.load(Relaxed))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+// Collects the provided dimensions, panicking if there is not exactly one per column
+pub(crate) fn collect_dimensions(dims: impl Iterator<Item = usize>, n_cols: usize) -> Vec<usize> {
+    let dims: Vec<usize> = dims.collect();
+    assert_eq!(
+        dims.len(),
+        n_cols,
+        "Should provide exactly one dimension for each column"
+    );
+    dims
+}
+
+// Checks that the entry lies within the range specified by `column_height`
+pub(crate) fn validate_entry_row(
+    row: usize,
+    col: usize,
+    column_height: Option<usize>,
+) -> Result<(), LophatError> {
+    match column_height {
+        Some(column_height) if row >= column_height => Err(LophatError::RowOutOfRange {
+            row,
+            col,
+            column_height,
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Panics with an informative message if the entry lies outside of the range specified by `column_height`
+pub(crate) fn check_entry_row(row: usize, col: usize, column_height: Option<usize>) {
+    if let Err(e) = validate_entry_row(row, col, column_height) {
+        panic!("{e}");
+    }
+}
+
+// Checks that every entry references an existing column and lies within the range specified by `column_height`
+pub(crate) fn validate_entries(
+    entries: &[(usize, usize)],
+    n_cols: usize,
+    column_height: Option<usize>,
+) -> Result<(), LophatError> {
+    for &(row, col) in entries {
+        if col >= n_cols {
+            return Err(LophatError::ColumnOutOfRange { col, n_cols });
+        }
+        validate_entry_row(row, col, column_height)?;
+    }
+    Ok(())
+}
+
+// Checks that the clearing optimisation is only requested for square matrices
+pub(crate) fn validate_clearing(options: &LoPhatOptions, n_cols: usize) -> Result<(), LophatError> {
+    match options.column_height {
+        Some(column_height) if options.clearing && column_height != n_cols => {
+            Err(LophatError::NonSquareWithClearing {
+                column_height,
+                n_cols,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+// Checks that no pair filter is provided, for algorithms which do not support one
+pub(crate) fn validate_no_pair_filter(options: &LoPhatOptions) -> Result<(), LophatError> {
+    match options.accept_pair {
+        Some(_) => Err(LophatError::UnsupportedOption {
+            option: "accept_pair",
+        }),
+        None => Ok(()),
+    }
+}
+
+// As for validate_no_pair_filter, but panics
+pub(crate) fn reject_pair_filter(options: &LoPhatOptions) {
+    validate_no_pair_filter(options).expect("Should only set accept_pair for the serial algorithm");
 }
 
 /// A struct implementing this trait implements an algorithm for computing the R=DV decomposition of a matrix D.
@@ -81,19 +624,154 @@ where
     C: Column,
 {
     /// A struct of options that you wish to provide to the algorithm.
-    type Options: Default + Copy;
+    type Options: Default + Clone;
     /// Initialise the algorithm with the options provided and an empty input matrix
     fn init(options: Option<Self::Options>) -> Self;
 
     /// Push the provided columns onto the end of the matrix
     fn add_cols(self, cols: impl Iterator<Item = C>) -> Self;
 
+    /// Push columns onto the end of the matrix, each provided as a dimension and its entries (in any order).
+    /// Each column is constructed with the correct dimension and all of its entries at once,
+    /// which avoids both forgetting the dimension and the cost of patching entries in one at a time via [`add_entries`](DecompositionAlgo::add_entries).
+    fn add_cols_with_entries(self, cols: impl Iterator<Item = (usize, Vec<usize>)>) -> Self
+    where
+        Self: Sized,
+    {
+        self.add_cols(cols.map(|(dimension, entries)| {
+            let mut col = C::new_with_dimension(dimension);
+            col.add_entries(entries.into_iter());
+            col
+        }))
+    }
+
+    /// Incrementally parses columns from `reader`, in the format of [`ColumnReader`], and pushes them onto the end of the matrix.
+    /// The columns are not collected into an intermediate `Vec`.
+    /// Returns an error if `reader` fails or ends part way through a column.
+    fn add_cols_from_reader<R: Read>(self, reader: R) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut error = None;
+        let cols = ColumnReader::new(reader).map_while(|col| match col {
+            Ok(col) => Some(col),
+            Err(e) => {
+                error = Some(e);
+                None
+            }
+        });
+        let algo = self.add_cols(cols);
+        match error {
+            Some(e) => Err(e),
+            None => Ok(algo),
+        }
+    }
+
     /// Add the provided (row, column) entries to the matrix.
-    /// If the column has not already been pushed via [`add_cols`](DecompositionAlgo::add_cols) then `panic!()`
+    /// If the column has not already been pushed via [`add_cols`](DecompositionAlgo::add_cols) then `panic!()`.
+    /// Algorithms which pre-allocate a pivots array also `panic!()` if the row lies beyond the configured column height.
     fn add_entries(self, entries: impl Iterator<Item = (usize, usize)>) -> Self;
 
+    /// As for [`add_entries`](DecompositionAlgo::add_entries) but returns an error, rather than panicking, if any entry is invalid.
+    /// The entries are all validated before any are added.
+    ///
+    /// The provided implementation only checks that each column has already been pushed.
+    fn try_add_entries(
+        self,
+        entries: impl Iterator<Item = (usize, usize)>,
+    ) -> Result<Self, LophatError>
+    where
+        Self: Sized,
+    {
+        let entries: Vec<_> = entries.collect();
+        validate_entries(&entries, self.n_cols_so_far(), None)?;
+        Ok(self.add_entries(entries.into_iter()))
+    }
+
+    /// Overrides the dimension of each column pushed so far, in order (including the corresponding column of V, if maintained).
+    /// Useful if the columns were constructed without the correct dimension, which is required for clearing.
+    ///
+    /// Panics if the number of dimensions provided differs from [`n_cols_so_far`](DecompositionAlgo::n_cols_so_far).
+    fn set_dimensions(self, dims: impl Iterator<Item = usize>) -> Self;
+
+    /// Returns the number of columns pushed onto the matrix so far, via [`add_cols`](DecompositionAlgo::add_cols).
+    /// In particular, this is the index that the next pushed column will take.
+    fn n_cols_so_far(&self) -> usize;
+
+    /// Returns the height of the columns that will be used by [`decompose`](DecompositionAlgo::decompose), given the columns pushed so far.
+    /// This is [`column_height`](LoPhatOptions::column_height) if provided, otherwise one more than the largest row index in any column.
+    /// Algorithms which do not make use of `column_height` always return the inferred height.
+    ///
+    /// When inferred, this requires a pass over the matrix.
+    fn inferred_column_height(&self) -> usize;
+
     /// Return tupe of [`decompose`](DecompositionAlgo::decompose) -- should carry sufficient information to query columns of the resulting decomposition.
     type Decomposition: Decomposition<C>;
     /// Decomposes the built-up matrix (D) into an R=DV decomposition, following the relevant algorithm and provided options.
     fn decompose(self) -> Self::Decomposition;
+
+    /// Decomposes the built-up matrix, as in [`decompose`](DecompositionAlgo::decompose), and returns only the [`diagram`](Decomposition::diagram).
+    /// The decomposition (i.e. R and V) is dropped before returning, so only the diagram remains in memory.
+    fn decompose_into_diagram(self) -> PersistenceDiagram
+    where
+        Self: Sized,
+    {
+        let decomposition = self.decompose();
+        decomposition.diagram()
+    }
+
+    /// Decomposes the boundary matrix whose coboundary matrix is provided, by first [transposing](transpose) it and pushing the result onto the matrix.
+    /// * `coboundary` - column `j` contains the indices of the cofaces of simplex `j`, and has the dimension of simplex `j`.
+    ///
+    /// The output is the decomposition of the boundary matrix, so the diagram is that of persistent homology, with indices referring to the same simplices as `coboundary`.
+    /// This differs from computing cohomology, which decomposes the [anti-transpose](crate::utils::anti_transpose) of the boundary matrix, reversing the order of the columns.
+    /// To compute cohomology from a coboundary matrix, instead reverse the order of its rows and columns.
+    ///
+    /// Panics if any columns have already been pushed.
+    fn decompose_from_coboundary(self, coboundary: &[C]) -> Self::Decomposition
+    where
+        Self: Sized,
+    {
+        assert_eq!(
+            self.n_cols_so_far(),
+            0,
+            "Should call decompose_from_coboundary before pushing any columns"
+        );
+        self.add_cols(transpose(coboundary).into_iter()).decompose()
+    }
+
+    /// As for [`decompose`](DecompositionAlgo::decompose) but also returns the wall-clock time taken to decompose, measured with [`Instant`].
+    ///
+    /// Only the call to [`decompose`](DecompositionAlgo::decompose) is timed, so building the matrix beforehand and reading off the diagram afterwards are excluded.
+    /// This gives a consistent timing point when comparing algorithms.
+    fn decompose_timed(self) -> (Self::Decomposition, Duration)
+    where
+        Self: Sized,
+    {
+        let start = Instant::now();
+        let decomposition = self.decompose();
+        (decomposition, start.elapsed())
+    }
+
+    /// Returns the [`LoPhatOptions`] the algorithm was initialised with, if its options are of that type and are retained.
+    /// Used by provided methods, such as [`try_decompose`](DecompositionAlgo::try_decompose), to validate the options.
+    ///
+    /// The provided implementation returns `None`.
+    fn lophat_options(&self) -> Option<&LoPhatOptions> {
+        None
+    }
+
+    /// As for [`decompose`](DecompositionAlgo::decompose) but first checks that the provided options are compatible with the built-up matrix.
+    ///
+    /// The provided implementation returns an error if clearing is requested but `column_height` does not match the number of columns,
+    /// provided the options are available via [`lophat_options`](DecompositionAlgo::lophat_options).
+    fn try_decompose(self) -> Result<Self::Decomposition, LophatError>
+    where
+        Self: Sized,
+    {
+        if let Some(options) = self.lophat_options() {
+            validate_clearing(options, self.n_cols_so_far())?;
+        }
+        Ok(self.decompose())
+    }
 }
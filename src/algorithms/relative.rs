@@ -0,0 +1,97 @@
+use hashbrown::HashSet;
+
+use crate::algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm};
+use crate::columns::Column;
+use crate::options::LoPhatOptions;
+use crate::utils::{anti_transpose, PersistenceDiagram};
+
+/// Computes the persistent relative cohomology of a pair (X, A), using the [`LockFreeAlgorithm`].
+/// * `matrix` - the boundary matrix of X, which should be square.
+/// * `subcomplex_cols` - the indices of the columns corresponding to simplices in the subcomplex A.
+/// * `options` - options passed to the algorithm.
+///
+/// The rows and columns of the subcomplex are masked out (to obtain the relative boundary matrix),
+/// which is then anti-transposed and decomposed.
+/// The returned diagram is re-indexed to refer to the columns of `matrix`, as in [`PersistenceDiagram::anti_transpose`].
+/// Indices in `subcomplex_cols` are neither paired nor unpaired in the output.
+pub fn decompose_relative_cohomology<C: Column + 'static>(
+    matrix: &[C],
+    subcomplex_cols: &HashSet<usize>,
+    options: LoPhatOptions,
+) -> PersistenceDiagram {
+    let width = matrix.len();
+    let relative_matrix: Vec<C> = matrix
+        .iter()
+        .enumerate()
+        .map(|(j, col)| {
+            let mut relative_col = C::new_with_dimension(col.dimension());
+            if !subcomplex_cols.contains(&j) {
+                relative_col.add_entries(col.entries().filter(|i| !subcomplex_cols.contains(i)));
+            }
+            relative_col
+        })
+        .collect();
+    let at: Vec<C> = anti_transpose(&relative_matrix);
+    let mut diagram = LockFreeAlgorithm::init(Some(options))
+        .add_cols(at.into_iter())
+        .decompose()
+        .diagram()
+        .anti_transpose(width);
+    // Simplices in the subcomplex have empty rows and columns so are never paired
+    diagram
+        .unpaired
+        .retain(|idx| !subcomplex_cols.contains(idx));
+    diagram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::VecColumn;
+    use crate::utils::fixtures::build_sphere_triangulation;
+
+    #[test]
+    fn interval_relative_to_endpoints_is_circle() {
+        let matrix: Vec<VecColumn> = vec![(0, vec![]), (0, vec![]), (1, vec![0, 1])]
+            .into_iter()
+            .map(|col| col.into())
+            .collect();
+        let diagram = decompose_relative_cohomology(
+            &matrix,
+            &HashSet::from_iter(vec![0, 1]),
+            LoPhatOptions::default(),
+        );
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![2]),
+            paired: HashSet::new(),
+        };
+        assert_eq!(diagram, correct_diagram);
+    }
+
+    #[test]
+    fn sphere_relative_to_vertex_is_reduced() {
+        let matrix = build_sphere_triangulation();
+        let diagram = decompose_relative_cohomology(
+            &matrix,
+            &HashSet::from_iter(vec![0]),
+            LoPhatOptions::default(),
+        );
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        assert_eq!(diagram, correct_diagram);
+    }
+
+    #[test]
+    fn empty_subcomplex_is_absolute_cohomology() {
+        let matrix = build_sphere_triangulation();
+        let diagram =
+            decompose_relative_cohomology(&matrix, &HashSet::new(), LoPhatOptions::default());
+        let absolute = LockFreeAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        assert_eq!(diagram, absolute);
+    }
+}
@@ -4,12 +4,20 @@ use crate::impl_rvd_serialize;
 use crate::{
     algorithms::Decomposition,
     columns::{Column, ColumnMode},
-    options::LoPhatOptions,
+    options::{LoPhatOptions, PairFilter},
+    utils::PersistenceDiagram,
 };
 
+use hashbrown::HashSet;
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
 
-use super::{DecompositionAlgo, NoVMatrixError};
+use super::strategy::{reduce_column_against, ReducedColumn};
+use super::{
+    collect_dimensions, infer_column_height, ColumnClass, DecompositionAlgo, NoVMatrixError,
+};
 
 /// Implements the standard left-to-right column additional algorithm of [Edelsbrunner et al.](https://doi.org/10.1109/SFCS.2000.892133).
 /// No optimisations have been implemented.
@@ -18,11 +26,19 @@ pub struct SerialAlgorithm<C: Column> {
     r: Vec<C>,
     v: Option<Vec<C>>,
     low_inverse: HashMap<usize, usize>,
+    accept_pair: Option<AcceptPair>,
+    exhaustive: bool,
+    assume_reduced: bool,
 }
 
-fn col_idx_with_same_low<C: Column>(low_inverse: &HashMap<usize, usize>, col: &C) -> Option<usize> {
-    let pivot = col.pivot()?;
-    low_inverse.get(&pivot).copied()
+// Wraps the predicate from LoPhatOptions::accept_pair, so that SerialAlgorithm can derive Debug
+#[derive(Clone)]
+struct AcceptPair(PairFilter);
+
+impl std::fmt::Debug for AcceptPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AcceptPair")
+    }
 }
 
 impl<C: Column> SerialAlgorithm<C> {
@@ -83,17 +99,32 @@ impl<C: Column> SerialAlgorithm<C> {
             v_splits.as_mut().unwrap().1[0].set_mode(ColumnMode::Working)
         }
         // Reduce the column, keeping track of how we do this in V
-        while let Some(col_idx) = col_idx_with_same_low(&self.low_inverse, &post_r[0]) {
-            post_r[0].add_col(&(prior_r[col_idx]));
-            if maintain_v {
-                let (prior_v, post_v) = v_splits.as_mut().unwrap();
-                post_v[0].add_col(&prior_v[col_idx]);
+        let final_pivot = if self.accept_pair.is_some() {
+            self.reduce_with_pair_filter(idx)
+        } else {
+            let (prior_v, post_v) = match v_splits {
+                Some((prior_v, post_v)) => (Some(&*prior_v), Some(&mut post_v[0])),
+                None => (None, None),
+            };
+            let low_inverse = &self.low_inverse;
+            let claimed_by = |pivot| {
+                let col_idx = *low_inverse.get(&pivot)?;
+                Some((col_idx, (&prior_r[col_idx], prior_v.map(|v| &v[col_idx]))))
+            };
+            match reduce_column_against(idx, &mut post_r[0], post_v, claimed_by, |_| {}) {
+                ReducedColumn::Cycle => None,
+                ReducedColumn::Unclaimed(pivot) => Some(pivot),
+                ReducedColumn::ClaimedLater { .. } => {
+                    unreachable!("Columns should be reduced from left to right")
+                }
             }
-        }
+        };
         // Update low inverse
-        let final_pivot = self.r[idx].pivot();
         if let Some(final_pivot) = final_pivot {
-            // This column has a lowest 1 and is being inserted at the end of R
+            if self.exhaustive {
+                self.reduce_below_pivot(idx, final_pivot);
+            }
+            // This column has a lowest 1 and claims that pivot
             self.low_inverse.insert(final_pivot, idx);
         }
         // Push to decomposition
@@ -102,6 +133,253 @@ impl<C: Column> SerialAlgorithm<C> {
             self.v.as_mut().unwrap()[idx].set_mode(ColumnMode::Storage);
         }
     }
+
+    // Reduces column `idx`, consulting the pair filter before claiming a pivot, and returns the claimed pivot (if any).
+    // Earlier columns may have entries above the pivot that they claimed, so adding them can introduce entries above the current row.
+    // Hence we only ever work downwards: once a row has been eliminated or rejected, only the entries below it are considered.
+    fn reduce_with_pair_filter(&mut self, idx: usize) -> Option<usize> {
+        let mut bound = usize::MAX;
+        loop {
+            let row = self.r[idx].pivots_descending().find(|&row| row < bound)?;
+            match self.low_inverse.get(&row).copied() {
+                Some(owner) => self.add_earlier_column(idx, owner),
+                None if self.accepts_pair(row, idx) => return Some(row),
+                // Rejected, so continue as if this row were unavailable
+                None => {}
+            }
+            bound = row;
+        }
+    }
+
+    // Eliminates entries of the reduced column `idx` which are the pivot of an earlier column, working down from `pivot`.
+    // Adding an earlier column only affects entries at or below its pivot, so each entry need only be visited once.
+    fn reduce_below_pivot(&mut self, idx: usize, pivot: usize) {
+        let (prior_r, post_r) = self.r.split_at_mut(idx);
+        let mut v_splits = self.v.as_mut().map(|v| v.split_at_mut(idx));
+        let mut bound = pivot;
+        loop {
+            let next = post_r[0]
+                .pivots_descending()
+                .filter(|&row| row < bound)
+                .find_map(|row| {
+                    let col_idx = *self.low_inverse.get(&row)?;
+                    // Only earlier columns may be added, which matters if reduced out of order
+                    (col_idx < idx).then_some((row, col_idx))
+                });
+            let Some((row, col_idx)) = next else {
+                return;
+            };
+            post_r[0].add_col(&prior_r[col_idx]);
+            if let Some((prior_v, post_v)) = v_splits.as_mut() {
+                post_v[0].add_col(&prior_v[col_idx]);
+            }
+            bound = row;
+        }
+    }
+
+    // Adds the earlier column `source` to column `target`, in both R and V
+    fn add_earlier_column(&mut self, target: usize, source: usize) {
+        let (prior_r, post_r) = self.r.split_at_mut(target);
+        post_r[0].add_col(&prior_r[source]);
+        if let Some(v) = self.v.as_mut() {
+            let (prior_v, post_v) = v.split_at_mut(target);
+            post_v[0].add_col(&prior_v[source]);
+        }
+    }
+
+    fn set_mode_of_column(&mut self, idx: usize, mode: ColumnMode) {
+        self.r[idx].set_mode(mode);
+        if let Some(v) = self.v.as_mut() {
+            v[idx].set_mode(mode);
+        }
+    }
+
+    // Reduces column `j`, where columns may have been reduced in any order.
+    // Only earlier columns are ever added, so if `j` finds its pivot claimed by a later column,
+    // then `j` takes the pivot and we switch to reducing the later column instead.
+    fn reduce_column_in_any_order(&mut self, j: usize) {
+        let mut working_j = j;
+        self.set_mode_of_column(working_j, ColumnMode::Working);
+        while let Some(pivot) = self.r[working_j].pivot() {
+            match self.low_inverse.get(&pivot).copied() {
+                Some(owner) if owner < working_j => self.add_earlier_column(working_j, owner),
+                Some(owner) if owner > working_j => {
+                    self.low_inverse.insert(pivot, working_j);
+                    self.set_mode_of_column(working_j, ColumnMode::Storage);
+                    working_j = owner;
+                    self.set_mode_of_column(working_j, ColumnMode::Working);
+                }
+                Some(_) => break,
+                None => {
+                    self.low_inverse.insert(pivot, working_j);
+                    break;
+                }
+            }
+        }
+        self.set_mode_of_column(working_j, ColumnMode::Storage);
+    }
+
+    fn accepts_pair(&self, birth: usize, death: usize) -> bool {
+        self.accept_pair
+            .as_ref()
+            .is_none_or(|accept_pair| (accept_pair.0)(birth, death))
+    }
+
+    // Reduces all columns from `first_idx` onwards, assuming all prior columns are reduced and in low_inverse
+    fn decompose_from(mut self, first_idx: usize) -> SerialDecomposition<C> {
+        let n_cols = self.r.len();
+        if self.assume_reduced {
+            self.claim_pivots(first_idx..n_cols);
+        } else {
+            self.reduce_range(first_idx..n_cols);
+        }
+        SerialDecomposition {
+            r: self.r,
+            v: self.v,
+            low_inverse: self.low_inverse,
+            accept_pair: self.accept_pair,
+            exhaustive: self.exhaustive,
+        }
+    }
+
+    fn reduce_range(&mut self, range: Range<usize>) {
+        for idx in range {
+            self.reduce_column_at_index(idx);
+        }
+    }
+
+    // Records the pivot of each column in `range` without reducing, assuming these columns are already reduced.
+    // With a pair filter, rejected columns must still be reduced below their pivot.
+    fn claim_pivots(&mut self, range: Range<usize>) {
+        for idx in range {
+            if self.accept_pair.is_some() {
+                self.reduce_column_at_index(idx);
+            } else if let Some(pivot) = self.r[idx].pivot() {
+                let existing = self.low_inverse.insert(pivot, idx);
+                debug_assert!(
+                    existing.is_none(),
+                    "Input should be reduced when assume_reduced is set"
+                );
+            }
+        }
+    }
+
+    /// Decomposes the matrix, reducing the columns in the provided `order` rather than from left to right.
+    ///
+    /// Whenever a column is found to have the same pivot as a later column, which was reduced first, the earlier column takes that pivot and the later column is reduced further.
+    /// Hence only earlier columns are ever added to later columns, so that R = DV is a valid decomposition and the diagram is the same for every order.
+    /// If `exhaustive` is set, the exhaustive reduction takes place once all columns have been reduced.
+    ///
+    /// Panics if `order` is not a permutation of the column indices, or if [`accept_pair`](LoPhatOptions::accept_pair) is set.
+    pub fn decompose_in_order(mut self, order: &[usize]) -> SerialDecomposition<C> {
+        assert!(
+            self.accept_pair.is_none(),
+            "Should not set accept_pair when decomposing in a custom order"
+        );
+        let n_cols = self.r.len();
+        let mut seen = vec![false; n_cols];
+        for &idx in order {
+            assert!(
+                idx < n_cols && !std::mem::replace(&mut seen[idx], true),
+                "Order should be a permutation of the column indices"
+            );
+        }
+        assert_eq!(
+            order.len(),
+            n_cols,
+            "Order should be a permutation of the column indices"
+        );
+        for &idx in order {
+            self.reduce_column_in_any_order(idx);
+        }
+        if self.exhaustive {
+            for idx in 0..n_cols {
+                if let Some(pivot) = self.r[idx].pivot() {
+                    self.reduce_below_pivot(idx, pivot);
+                }
+            }
+        }
+        SerialDecomposition {
+            r: self.r,
+            v: self.v,
+            low_inverse: self.low_inverse,
+            accept_pair: self.accept_pair,
+            exhaustive: self.exhaustive,
+        }
+    }
+
+    /// Decomposes the provided matrix without taking ownership of it, avoiding a copy of the input.
+    ///
+    /// **Note:** the columns of `matrix` are reduced in place, so after calling this `matrix` holds R rather than D.
+    /// The returned decomposition borrows R from `matrix` and owns V (if maintained).
+    pub fn decompose_in_place(
+        matrix: &mut Vec<C>,
+        options: Option<LoPhatOptions>,
+    ) -> InPlaceSerialDecomposition<'_, C> {
+        let mut algo = Self::init(options);
+        if let Some(v) = algo.v.as_mut() {
+            *v = matrix
+                .iter()
+                .enumerate()
+                .map(|(idx, col)| {
+                    let mut v_col = C::new_with_dimension(col.dimension());
+                    v_col.add_entry(idx);
+                    v_col
+                })
+                .collect();
+        }
+        // Moves the columns into the algorithm, without copying them, and then back again
+        algo.r = std::mem::take(matrix);
+        let decomposition = algo.decompose();
+        *matrix = decomposition.r;
+        InPlaceSerialDecomposition {
+            r: matrix,
+            v: decomposition.v,
+            low_inverse: decomposition.low_inverse,
+        }
+    }
+
+    // Pushes columns of R alongside their existing columns of V, so that column operations are applied on top of V rather than the identity
+    pub(crate) fn add_cols_with_v(mut self, cols: impl Iterator<Item = (C, Option<C>)>) -> Self {
+        for (r_col, v_col) in cols {
+            self.r.push(r_col);
+            if let Some(v) = self.v.as_mut() {
+                v.push(v_col.expect("V should be provided for every column when maintaining V"));
+            }
+        }
+        self
+    }
+
+    /// Reduces only the columns in `range`, against the pivots of all columns before `range.start`.
+    ///
+    /// The columns before the window are treated as fixed and must already be reduced, i.e. have distinct pivots.
+    /// The columns after the window are left untouched and unreduced, so any pairings they would contribute are absent from the output.
+    /// In particular, decomposing the window `0..n`, where `n` is the number of columns, is equivalent to [`decompose`](DecompositionAlgo::decompose).
+    ///
+    /// Panics if `range` extends beyond the matrix, or if two columns before the window share a pivot.
+    pub fn decompose_window(mut self, range: Range<usize>) -> SerialDecomposition<C> {
+        assert!(
+            range.end <= self.r.len(),
+            "Window should lie within the columns of the matrix"
+        );
+        for (idx, col) in self.r[..range.start].iter().enumerate() {
+            if let Some(pivot) = col.pivot() {
+                let existing = self.low_inverse.insert(pivot, idx);
+                assert!(
+                    existing.is_none(),
+                    "Columns before the window should already be reduced"
+                );
+            }
+        }
+        self.reduce_range(range);
+        SerialDecomposition {
+            r: self.r,
+            v: self.v,
+            low_inverse: self.low_inverse,
+            accept_pair: self.accept_pair,
+            exhaustive: self.exhaustive,
+        }
+    }
 }
 
 impl<C: Column> DecompositionAlgo<C> for SerialAlgorithm<C> {
@@ -113,6 +391,9 @@ impl<C: Column> DecompositionAlgo<C> for SerialAlgorithm<C> {
             r: vec![],
             v: options.maintain_v.then_some(vec![]),
             low_inverse: HashMap::new(),
+            accept_pair: options.accept_pair.map(AcceptPair),
+            exhaustive: options.exhaustive,
+            assume_reduced: options.assume_reduced,
         }
     }
 
@@ -141,32 +422,209 @@ impl<C: Column> DecompositionAlgo<C> for SerialAlgorithm<C> {
         self
     }
 
+    fn set_dimensions(mut self, dims: impl Iterator<Item = usize>) -> Self {
+        let dims = collect_dimensions(dims, self.r.len());
+        for (idx, dim) in dims.into_iter().enumerate() {
+            self.r[idx].set_dimension(dim);
+            if let Some(v) = self.v.as_mut() {
+                v[idx].set_dimension(dim);
+            }
+        }
+        self
+    }
+
+    fn n_cols_so_far(&self) -> usize {
+        self.r.len()
+    }
+
+    // The serial algorithm has no pivots array, so never uses column_height
+    fn inferred_column_height(&self) -> usize {
+        infer_column_height(self.r.iter().map(|col| col.pivot()))
+    }
+
     type Decomposition = SerialDecomposition<C>;
 
-    fn decompose(mut self) -> Self::Decomposition {
-        for idx in 0..self.r.len() {
-            self.reduce_column_at_index(idx);
-        }
-        SerialDecomposition {
-            r: self.r,
-            v: self.v,
-        }
+    fn decompose(self) -> Self::Decomposition {
+        self.decompose_from(0)
     }
 }
 
 /// Return type of [`SerialAlgorithm`].
+///
+/// Retains the map from pivots to columns, so that further columns can be appended and reduced via [`append_and_reduce`](Self::append_and_reduce).
+/// This map is also used to answer [`diagram`](Decomposition::diagram), [`classify`](Decomposition::classify) and [`pair_of`](Decomposition::pair_of) without re-scanning the columns of R.
 pub struct SerialDecomposition<C: Column> {
     r: Vec<C>,
     v: Option<Vec<C>>,
+    low_inverse: HashMap<usize, usize>,
+    // Retained so that appended columns are reduced in the same way
+    accept_pair: Option<AcceptPair>,
+    exhaustive: bool,
+}
+
+impl<C: Column> SerialDecomposition<C> {
+    // Moves out R, V (if maintained) and the map from pivots to columns
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn into_parts(self) -> (Vec<C>, Option<Vec<C>>, HashMap<usize, usize>) {
+        (self.r, self.v, self.low_inverse)
+    }
+
+    /// Pushes the provided columns onto the end of the decomposed matrix and reduces only these new columns.
+    /// The existing columns of R and V are unchanged.
+    pub fn append_and_reduce(&mut self, cols: impl Iterator<Item = C>) {
+        let first_idx = self.r.len();
+        let algo = SerialAlgorithm {
+            r: std::mem::take(&mut self.r),
+            v: self.v.take(),
+            low_inverse: std::mem::take(&mut self.low_inverse),
+            accept_pair: self.accept_pair.take(),
+            exhaustive: self.exhaustive,
+            // Appended columns are never assumed to be reduced
+            assume_reduced: false,
+        };
+        *self = algo.add_cols(cols).decompose_from(first_idx);
+    }
+
+    /// Removes the last `k` columns from R and V, releasing any pivots that they held.
+    ///
+    /// Since no column is ever reduced by a later column, the remaining columns form a valid decomposition of the truncated matrix.
+    /// Combined with [`append_and_reduce`](Self::append_and_reduce), this supports a window of columns that grows and shrinks from the right.
+    ///
+    /// Panics if `k` exceeds the number of columns.
+    pub fn pop_columns(&mut self, k: usize) {
+        assert!(
+            k <= self.r.len(),
+            "Should not pop more columns than are in the decomposition"
+        );
+        let new_len = self.r.len() - k;
+        self.r.truncate(new_len);
+        if let Some(v) = self.v.as_mut() {
+            v.truncate(new_len);
+        }
+        self.low_inverse.retain(|_, col_idx| *col_idx < new_len);
+    }
+
+    /// Extends the decomposed complex to the cone over it and reduces only the new columns.
+    /// * `base_boundaries` - the columns of the original boundary matrix D, which was decomposed.
+    ///
+    /// If D has `n` columns then the apex of the cone is appended at index `n`,
+    /// followed by the cone over each column of D, in order.
+    /// The cone over column `i` has boundary given by column `i` plus the cone over the boundary of `i`
+    /// (the apex, in the case that `i` is a vertex).
+    pub fn extend_with_cone(&mut self, base_boundaries: impl Iterator<Item = C>) {
+        let n = self.n_cols();
+        let apex = C::new_with_dimension(0);
+        let cone_cols = base_boundaries.enumerate().map(|(idx, base_col)| {
+            let mut cone_col = C::new_with_dimension(base_col.dimension() + 1);
+            if base_col.dimension() == 0 {
+                cone_col.add_entry(n);
+            }
+            cone_col.add_entry(idx);
+            cone_col.add_entries(base_col.entries().map(|face| n + 1 + face));
+            cone_col
+        });
+        let cols: Vec<C> = std::iter::once(apex).chain(cone_cols).collect();
+        assert_eq!(
+            cols.len(),
+            n + 1,
+            "Should provide a boundary for every column in the decomposition"
+        );
+        self.append_and_reduce(cols.into_iter());
+    }
+}
+
+/// Decomposes a block-diagonal matrix, by decomposing each block independently with the [`SerialAlgorithm`], in parallel across blocks.
+/// * `blocks` - each block of the matrix, as the range of (global) column indices which it occupies, together with its columns.
+///   Within each block, row indices are local, i.e. relative to the start of its range.
+/// * `options` - options passed to the algorithm for every block.
+///
+/// Since no column of one block has an entry in the rows of another, no pivots are shared between blocks, so there is no contention.
+/// The decompositions of the blocks are then assembled into a decomposition of the whole matrix, with all indices offset to be global.
+///
+/// Panics if the ranges do not tile `0..n`, in order, or if a range differs in length from its block.
+pub fn decompose_blocks<C: Column>(
+    blocks: Vec<(Range<usize>, Vec<C>)>,
+    options: Option<LoPhatOptions>,
+) -> SerialDecomposition<C> {
+    let mut next_start = 0;
+    for (range, cols) in blocks.iter() {
+        assert_eq!(
+            range.start, next_start,
+            "Blocks should tile the columns in order"
+        );
+        assert_eq!(
+            range.len(),
+            cols.len(),
+            "Should provide a column for every index in the range of each block"
+        );
+        next_start = range.end;
+    }
+    let block_decompositions: Vec<(usize, SerialDecomposition<C>)> = blocks
+        .into_par_iter()
+        .map(|(range, cols)| {
+            // The pair filter is written in terms of global indices
+            let offset = range.start;
+            let block_options = options.clone().map(|mut block_options| {
+                block_options.accept_pair = block_options.accept_pair.map(|accept_pair| {
+                    Arc::new(move |birth, death| accept_pair(birth + offset, death + offset))
+                        as PairFilter
+                });
+                block_options
+            });
+            let decomposition = SerialAlgorithm::init(block_options)
+                .add_cols(cols.into_iter())
+                .decompose();
+            (range.start, decomposition)
+        })
+        .collect();
+    let offset_col = |col: C, offset: usize| {
+        let mut offset_col = C::new_with_dimension(col.dimension());
+        offset_col.add_entries(col.entries().map(|row| row + offset));
+        offset_col
+    };
+    let options = options.unwrap_or_default();
+    let mut r = Vec::with_capacity(next_start);
+    let mut v = options.maintain_v.then(|| Vec::with_capacity(next_start));
+    let mut low_inverse = HashMap::new();
+    for (offset, decomposition) in block_decompositions {
+        r.extend(
+            decomposition
+                .r
+                .into_iter()
+                .map(|col| offset_col(col, offset)),
+        );
+        if let (Some(v), Some(block_v)) = (v.as_mut(), decomposition.v) {
+            v.extend(block_v.into_iter().map(|col| offset_col(col, offset)));
+        }
+        low_inverse.extend(
+            decomposition
+                .low_inverse
+                .into_iter()
+                .map(|(birth, death)| (birth + offset, death + offset)),
+        );
+    }
+    SerialDecomposition {
+        r,
+        v,
+        low_inverse,
+        accept_pair: options.accept_pair.map(AcceptPair),
+        exhaustive: options.exhaustive,
+    }
 }
 
 impl<C: Column> Decomposition<C> for SerialDecomposition<C> {
-    type RColRef<'a> = &'a C where Self : 'a;
+    type RColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
     fn get_r_col(&self, index: usize) -> &C {
         &self.r[index]
     }
 
-    type VColRef<'a> = &'a C where Self: 'a;
+    type VColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
     fn get_v_col(&self, index: usize) -> Result<&C, NoVMatrixError> {
         Ok(&self.v.as_ref().ok_or(NoVMatrixError)?[index])
     }
@@ -174,13 +632,80 @@ impl<C: Column> Decomposition<C> for SerialDecomposition<C> {
     fn n_cols(&self) -> usize {
         self.r.len()
     }
+
+    fn diagram(&self) -> PersistenceDiagram {
+        diagram_from_low_inverse(&self.low_inverse, &self.r)
+    }
+
+    fn classify(&self, idx: usize) -> ColumnClass {
+        // With a pair filter, the column may have claimed a pivot below its lowest entry, or none at all
+        let claimed = self.r[idx]
+            .pivots_descending()
+            .find(|row| self.low_inverse.get(row) == Some(&idx));
+        if let Some(birth) = claimed {
+            return ColumnClass::Death(birth);
+        }
+        match self.low_inverse.get(&idx) {
+            Some(&death) => ColumnClass::Birth(death),
+            None => ColumnClass::Essential,
+        }
+    }
+}
+
+/// Return type of [`SerialAlgorithm::decompose_in_place`].
+/// The columns of R are borrowed from the matrix that was decomposed.
+pub struct InPlaceSerialDecomposition<'a, C: Column> {
+    r: &'a [C],
+    v: Option<Vec<C>>,
+    low_inverse: HashMap<usize, usize>,
+}
+
+impl<'a, C: Column> Decomposition<C> for InPlaceSerialDecomposition<'a, C> {
+    type RColRef<'b>
+        = &'b C
+    where
+        Self: 'b;
+    fn get_r_col(&self, index: usize) -> &C {
+        &self.r[index]
+    }
+
+    type VColRef<'b>
+        = &'b C
+    where
+        Self: 'b;
+    fn get_v_col(&self, index: usize) -> Result<&C, NoVMatrixError> {
+        Ok(&self.v.as_ref().ok_or(NoVMatrixError)?[index])
+    }
+
+    fn n_cols(&self) -> usize {
+        self.r.len()
+    }
+
+    fn diagram(&self) -> PersistenceDiagram {
+        diagram_from_low_inverse(&self.low_inverse, self.r)
+    }
+}
+
+// Reads off the diagram from the map from pivots to columns, without re-scanning the columns of R
+fn diagram_from_low_inverse<C: Column>(
+    low_inverse: &HashMap<usize, usize>,
+    r: &[C],
+) -> PersistenceDiagram {
+    let paired: HashSet<(usize, usize)> = low_inverse
+        .iter()
+        .map(|(&birth, &death)| (birth, death))
+        .collect();
+    let unpaired = (0..r.len())
+        .filter(|idx| !low_inverse.contains_key(idx) && r[*idx].is_cycle())
+        .collect();
+    PersistenceDiagram { unpaired, paired }
 }
 
 #[cfg(test)]
 mod tests {
-    use hashbrown::HashSet;
-
     use crate::{columns::VecColumn, utils::PersistenceDiagram};
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -223,8 +748,10 @@ mod tests {
     #[test]
     fn test_v_maintain() {
         let matrix = build_sphere_triangulation();
-        let mut options = LoPhatOptions::default();
-        options.maintain_v = true;
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
         let correct_diagram = PersistenceDiagram {
             unpaired: HashSet::from_iter(vec![0, 13]),
             paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
@@ -238,6 +765,748 @@ mod tests {
         }
         assert_eq!(computed_diagram, correct_diagram)
     }
+
+    #[test]
+    fn diagram_filtered_drops_short_pairs() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        assert_eq!(decomp.diagram_filtered(0), decomp.diagram());
+        let filtered_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12)]),
+        };
+        assert_eq!(decomp.diagram_filtered(3), filtered_diagram);
+    }
+
+    // A tall matrix which is not upper-triangular, so that births may come after deaths
+    fn build_tall_matrix() -> impl Iterator<Item = VecColumn> {
+        vec![(0, vec![]), (1, vec![3, 5]), (1, vec![2, 5])]
+            .into_iter()
+            .map(VecColumn::from)
+    }
+
+    #[test]
+    fn diagram_filtered_allows_birth_after_death() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_tall_matrix())
+            .decompose();
+        assert_eq!(
+            decomp.diagram().paired,
+            HashSet::from_iter(vec![(5, 1), (3, 2)])
+        );
+        assert_eq!(
+            decomp.diagram_filtered(2).paired,
+            HashSet::from_iter(vec![(5, 1)])
+        );
+    }
+
+    #[test]
+    fn diagram_restricted_to_subcomplex() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let all_indices: HashSet<usize> = (0..14).collect();
+        assert_eq!(decomp.diagram_restricted(&all_indices), decomp.diagram());
+        // The 1-skeleton of the sphere appears first, with three independent loops
+        let skeleton: HashSet<usize> = (0..10).collect();
+        let restricted_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 6, 8, 9, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7)]),
+        };
+        assert_eq!(decomp.diagram_restricted(&skeleton), restricted_diagram);
+    }
+
+    #[test]
+    fn pairing_array_matches_diagram() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let pair = decomp.pairing_array();
+        assert_eq!(pair.len(), 14);
+        for (i, partner) in pair.iter().enumerate() {
+            if let Some(j) = partner {
+                assert_eq!(pair[*j], Some(i));
+            }
+        }
+        let diagram = decomp.diagram();
+        for &(birth, death) in diagram.paired.iter() {
+            assert_eq!(pair[birth], Some(death));
+        }
+        for &idx in diagram.unpaired.iter() {
+            assert_eq!(pair[idx], None);
+        }
+        assert_eq!(pair.iter().filter(|p| p.is_none()).count(), 2);
+    }
+
+    #[test]
+    fn boundary_ranks_of_sphere() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        let ranks = decomp.boundary_ranks();
+        assert_eq!(ranks.get(&0), None);
+        assert_eq!(ranks.get(&1), Some(&3));
+        assert_eq!(ranks.get(&2), Some(&3));
+        // Betti numbers of the sphere are (1, 0, 1)
+        let rank = |d: usize| ranks.get(&d).copied().unwrap_or(0);
+        let betti: Vec<usize> = (0..3)
+            .map(|d| {
+                let n_cols = matrix.iter().filter(|col| col.dimension() == d).count();
+                n_cols - rank(d) - rank(d + 1)
+            })
+            .collect();
+        assert_eq!(betti, vec![1, 0, 1]);
+        assert_eq!(
+            decomp.betti_numbers(),
+            hashbrown::HashMap::from_iter(vec![(0, 1), (1, 0), (2, 1)])
+        );
+    }
+
+    #[test]
+    fn assume_reduced_reads_off_diagram() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let r: Vec<VecColumn> = (0..decomp.n_cols())
+            .map(|idx| decomp.get_r_col(idx).clone())
+            .collect();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            assume_reduced: true,
+            ..Default::default()
+        };
+        // Since V starts as the identity, it is unchanged if and only if no columns were added
+        fn check<D: Decomposition<VecColumn>>(
+            decomposition: D,
+            r: &[VecColumn],
+            diagram: &PersistenceDiagram,
+        ) {
+            assert_eq!(decomposition.diagram(), *diagram);
+            for (idx, col) in r.iter().enumerate() {
+                assert_eq!(*decomposition.get_r_col(idx), *col);
+                let v_col = decomposition.get_v_col(idx).unwrap();
+                assert_eq!(v_col.entries().collect::<Vec<_>>(), vec![idx]);
+            }
+        }
+        let diagram = decomp.diagram();
+        check(
+            SerialAlgorithm::init(Some(options.clone()))
+                .add_cols(r.iter().cloned())
+                .decompose(),
+            &r,
+            &diagram,
+        );
+        check(
+            crate::algorithms::LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(r.iter().cloned())
+                .decompose(),
+            &r,
+            &diagram,
+        );
+        check(
+            crate::algorithms::LockingAlgorithm::init(Some(options))
+                .add_cols(r.iter().cloned())
+                .decompose(),
+            &r,
+            &diagram,
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Input should be reduced")]
+    fn assume_reduced_checks_input_in_debug() {
+        let options = LoPhatOptions {
+            assume_reduced: true,
+            ..Default::default()
+        };
+        SerialAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+    }
+
+    // Generates a strict upper triangular matrix, alongside a random processing order
+    fn sut_matrix_with_order(size: usize) -> impl Strategy<Value = (Vec<VecColumn>, Vec<usize>)> {
+        let matrix: Vec<_> = (1..size)
+            .map(|i| hash_set(0..i, 0..i).prop_map(|set| VecColumn::from_iter_xor(0, set)))
+            .collect();
+        matrix.prop_flat_map(|matrix| {
+            let order = Just((0..matrix.len()).collect::<Vec<_>>()).prop_shuffle();
+            (Just(matrix), order)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn any_order_agrees_with_natural_order( (matrix, order) in sut_matrix_with_order(50) ) {
+            let options = LoPhatOptions { maintain_v: true, exhaustive: true, ..Default::default() };
+            let natural = SerialAlgorithm::init(Some(options.clone())).add_cols(matrix.iter().cloned()).decompose();
+            let ordered = SerialAlgorithm::init(Some(options)).add_cols(matrix.iter().cloned()).decompose_in_order(&order);
+            assert_eq!(natural.diagram(), ordered.diagram());
+            for (idx, col) in matrix.iter().enumerate() {
+                // The exhaustively reduced R is canonical, so is also independent of the order
+                assert_eq!(natural.get_r_col(idx), ordered.get_r_col(idx));
+                // Check that R = DV, with V upper triangular
+                let v_col = ordered.get_v_col(idx).unwrap();
+                assert!(v_col.entries().all(|entry| entry <= idx));
+                let mut dv_col = VecColumn::new_with_dimension(col.dimension());
+                for entry in v_col.entries() {
+                    dv_col.add_col(&matrix[entry]);
+                }
+                assert_eq!(&dv_col, ordered.get_r_col(idx));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Order should be a permutation")]
+    fn decompose_in_order_checks_permutation() {
+        SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose_in_order(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 12]);
+    }
+
+    #[test]
+    fn timed_decomposition_is_correct() {
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        let (decomp, elapsed) = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose_timed();
+        assert_eq!(decomp.diagram(), correct_diagram);
+        assert!(elapsed >= std::time::Duration::ZERO);
+        let (decomp, _) = crate::algorithms::LockingAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose_timed();
+        assert_eq!(decomp.diagram(), correct_diagram);
+    }
+
+    #[test]
+    fn apply_v_to_basis_vectors() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = SerialAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        for idx in 0..decomp.n_cols() {
+            let basis_vector = VecColumn::from((0, vec![idx]));
+            let v_col = decomp.apply_v(&basis_vector).unwrap();
+            assert!(v_col.entries().eq(decomp.get_v_col(idx).unwrap().entries()));
+        }
+        // Linear, so a sum of basis vectors maps to the sum of the V columns
+        let mut expected = decomp.get_v_col(12).unwrap().clone();
+        expected.add_col(decomp.get_v_col(13).unwrap());
+        let product = decomp.apply_v(&VecColumn::from((2, vec![12, 13]))).unwrap();
+        assert_eq!(product, expected);
+        assert!(decomp
+            .apply_v(&VecColumn::new_with_dimension(2))
+            .unwrap()
+            .is_cycle());
+        let no_v = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        assert!(no_v.apply_v(&VecColumn::from((0, vec![0]))).is_err());
+    }
+
+    #[test]
+    fn pop_then_append_restores_diagram() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let mut decomp = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        let full_diagram = decomp.diagram();
+        // Removing the triangles leaves the 1-skeleton, which is a valid decomposition in its own right
+        decomp.pop_columns(4);
+        let skeleton = SerialAlgorithm::init(Some(options))
+            .add_cols(matrix[..10].iter().cloned())
+            .decompose();
+        assert_eq!(decomp.n_cols(), 10);
+        assert_eq!(decomp.diagram(), skeleton.diagram());
+        assert_eq!(decomp.classify(9), ColumnClass::Essential);
+        assert_eq!(decomp.pair_of(9), None);
+        decomp.append_and_reduce(matrix[10..].iter().cloned());
+        assert_eq!(decomp.diagram(), full_diagram);
+        for idx in 0..matrix.len() {
+            assert!(decomp.get_v_col(idx).is_ok());
+        }
+        decomp.pop_columns(matrix.len());
+        assert_eq!(decomp.diagram(), PersistenceDiagram::default());
+    }
+
+    fn reject_pair(rejected: (usize, usize)) -> LoPhatOptions {
+        LoPhatOptions {
+            maintain_v: true,
+            accept_pair: Some(Arc::new(move |birth, death| (birth, death) != rejected)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejected_pair_reduces_below_pivot() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let decomp = SerialAlgorithm::init(Some(reject_pair((9, 11))))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        // Column 11 continues below row 9 and claims row 7, so column 12 claims row 9 and column 13 claims row 6
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![
+                (1, 4),
+                (2, 5),
+                (3, 7),
+                (8, 10),
+                (7, 11),
+                (9, 12),
+                (6, 13),
+            ]),
+        };
+        assert_eq!(decomp.diagram(), correct_diagram);
+        // Column 11 keeps its rejected entry, above the pivot that it claimed
+        assert_eq!(decomp.get_r_col(11).pivot(), Some(9));
+        assert_eq!(decomp.classify(11), ColumnClass::Death(7));
+        assert_eq!(decomp.classify(9), ColumnClass::Birth(12));
+        // Only earlier columns are added, so R = DV still holds
+        for j in 0..decomp.n_cols() {
+            let mut dv_col = VecColumn::new_with_dimension(0);
+            for i in decomp.get_v_col(j).unwrap().entries() {
+                dv_col.add_col(&matrix[i]);
+            }
+            assert!(dv_col.entries().eq(decomp.get_r_col(j).entries()));
+        }
+        // Accepting every pair gives the standard diagram
+        let options = LoPhatOptions {
+            accept_pair: Some(Arc::new(|_, _| true)),
+            ..Default::default()
+        };
+        let decomp = SerialAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let standard = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        assert_eq!(decomp.diagram(), standard.diagram());
+    }
+
+    #[test]
+    fn pair_filter_sees_global_indices_in_blocks() {
+        // A single vertex, followed by the sphere shifted along by one
+        let shifted_sphere: Vec<VecColumn> = build_sphere_triangulation()
+            .map(|col| VecColumn::from((col.dimension(), col.entries().map(|e| e + 1).collect())))
+            .collect();
+        let whole: Vec<VecColumn> = std::iter::once(VecColumn::new_with_dimension(0))
+            .chain(shifted_sphere.iter().cloned())
+            .collect();
+        let local_sphere: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let blocks = vec![
+            (0..1, vec![VecColumn::new_with_dimension(0)]),
+            (1..15, local_sphere),
+        ];
+        let from_blocks = decompose_blocks(blocks, Some(reject_pair((10, 12))));
+        let from_whole = SerialAlgorithm::init(Some(reject_pair((10, 12))))
+            .add_cols(whole.into_iter())
+            .decompose();
+        assert_eq!(from_blocks.diagram(), from_whole.diagram());
+        assert!(from_blocks.diagram().paired.contains(&(8, 12)));
+    }
+
+    #[test]
+    fn sphere_columns_by_dimension() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let by_dimension = decomp.columns_by_dimension();
+        assert_eq!(by_dimension.len(), 3);
+        assert_eq!(by_dimension[&0], vec![0, 1, 2, 3]);
+        assert_eq!(by_dimension[&1], vec![4, 5, 6, 7, 8, 9]);
+        assert_eq!(by_dimension[&2], vec![10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn in_place_agrees_with_owning() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let owning = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let mut matrix: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let in_place = SerialAlgorithm::decompose_in_place(&mut matrix, Some(options));
+        assert_eq!(in_place.diagram(), owning.diagram());
+        for idx in 0..owning.n_cols() {
+            assert_eq!(
+                in_place.get_v_col(idx).unwrap(),
+                owning.get_v_col(idx).unwrap()
+            );
+        }
+        // The caller's matrix now holds R
+        for (idx, col) in matrix.iter().enumerate() {
+            assert_eq!(col, owning.get_r_col(idx));
+        }
+        assert!(matrix[13].is_cycle());
+    }
+
+    #[test]
+    fn sphere_lifetimes() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        assert_eq!(decomp.lifetime(1), Some(3));
+        assert_eq!(decomp.lifetime(3), Some(4));
+        assert_eq!(decomp.lifetime(6), Some(6));
+        assert_eq!(decomp.lifetime(9), Some(2));
+        // Essential classes never die
+        assert_eq!(decomp.lifetime(0), None);
+        assert_eq!(decomp.lifetime(13), None);
+        // Death columns are not births
+        assert_eq!(decomp.lifetime(12), None);
+    }
+
+    #[test]
+    fn lifetime_of_birth_after_death() {
+        // Not upper-triangular, so that column 2 is born after column 1, which kills it
+        let matrix = vec![(0, vec![]), (1, vec![2]), (0, vec![])]
+            .into_iter()
+            .map(VecColumn::from);
+        let decomp = SerialAlgorithm::init(None).add_cols(matrix).decompose();
+        assert_eq!(decomp.classify(2), ColumnClass::Birth(1));
+        assert_eq!(decomp.lifetime(2), None);
+    }
+
+    #[test]
+    fn exhaustive_reduction_is_canonical() {
+        let matrix: Vec<VecColumn> = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![1, 2]),
+            (1, vec![0, 2]),
+            (2, vec![3, 4, 5]),
+        ]
+        .into_iter()
+        .map(VecColumn::from)
+        .collect();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let standard = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        let exhaustive = SerialAlgorithm::init(Some(LoPhatOptions {
+            exhaustive: true,
+            ..options
+        }))
+        .add_cols(matrix.iter().cloned())
+        .decompose();
+        assert_eq!(standard.diagram(), exhaustive.diagram());
+        // Entry 1 of column 4 is the pivot of column 3, so is eliminated
+        assert_eq!(standard.get_r_col(4), &VecColumn::from((1, vec![1, 2])));
+        assert_eq!(exhaustive.get_r_col(4), &VecColumn::from((1, vec![0, 2])));
+        assert_eq!(
+            exhaustive.get_v_col(4).unwrap(),
+            &VecColumn::from((1, vec![3, 4]))
+        );
+        // Other columns are already canonical
+        for idx in [3, 5, 6] {
+            assert_eq!(standard.get_r_col(idx), exhaustive.get_r_col(idx));
+        }
+    }
+
+    #[test]
+    fn pivots_round_trip_through_diagram() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let pivots = crate::utils::diagram_to_pivots(&decomp.diagram());
+        let low_inverse: hashbrown::HashMap<usize, usize> =
+            decomp.low_inverse.iter().map(|(&k, &v)| (k, v)).collect();
+        assert_eq!(pivots, low_inverse);
+        assert_eq!(pivots.get(&6), Some(&12));
+    }
+
+    #[test]
+    fn classify_uses_pivot_map() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let diagram = decomp.diagram();
+        assert_eq!(
+            diagram,
+            crate::algorithms::diagram_from_r_cols(decomp.r.iter(), decomp.n_cols())
+        );
+        assert_eq!(decomp.classify(0), ColumnClass::Essential);
+        assert_eq!(decomp.classify(6), ColumnClass::Birth(12));
+        assert_eq!(decomp.classify(12), ColumnClass::Death(6));
+        // Agrees with the provided implementation, which scans R
+        let scanning = crate::algorithms::LockFreeAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        for idx in 0..decomp.n_cols() {
+            assert_eq!(decomp.classify(idx), scanning.classify(idx));
+            assert_eq!(decomp.pair_of(idx), scanning.pair_of(idx));
+        }
+        for &(birth, death) in diagram.paired.iter() {
+            assert_eq!(decomp.pair_of(birth), Some(death));
+            assert_eq!(decomp.pair_of(death), Some(birth));
+        }
+    }
+
+    #[test]
+    fn full_window_equals_full_decomposition() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let full = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let window = SerialAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation())
+            .decompose_window(0..14);
+        assert_eq!(full.r, window.r);
+        assert_eq!(full.v, window.v);
+        assert_eq!(full.diagram(), window.diagram());
+    }
+
+    #[test]
+    fn windows_reduce_against_prior_columns() {
+        let full = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        // Reduce the vertices and edges first, then only the triangles
+        let partial = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose_window(0..10);
+        assert!(partial.r[10..]
+            .iter()
+            .zip(build_sphere_triangulation().skip(10))
+            .all(|(col, original)| *col == original));
+        let rest = SerialAlgorithm::init(None)
+            .add_cols(partial.r.into_iter())
+            .decompose_window(10..14);
+        assert_eq!(full.r, rest.r);
+        assert_eq!(full.diagram(), rest.diagram());
+    }
+
+    #[test]
+    fn set_dimensions_fixes_dimensionless_columns() {
+        let dims: Vec<usize> = build_sphere_triangulation()
+            .map(|col| col.dimension())
+            .collect();
+        let dimensionless = build_sphere_triangulation().map(|mut col| {
+            col.set_dimension(0);
+            col
+        });
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = SerialAlgorithm::init(Some(options))
+            .add_cols(dimensionless)
+            .set_dimensions(dims.iter().copied())
+            .decompose();
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        assert_eq!(decomp.diagram(), correct_diagram);
+        for (idx, &dim) in dims.iter().enumerate() {
+            assert_eq!(decomp.get_r_col(idx).dimension(), dim);
+            assert_eq!(decomp.get_v_col(idx).unwrap().dimension(), dim);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_dimensions_requires_one_per_column() {
+        SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .set_dimensions(vec![0, 0, 0].into_iter());
+    }
+
+    #[test]
+    fn counts_match_diagram() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        assert_eq!(decomp.pair_count(), decomp.diagram().paired.len());
+        assert_eq!(decomp.pair_count(), 6);
+        let essential = decomp.essential_count_by_dim();
+        assert_eq!(
+            essential,
+            hashbrown::HashMap::from_iter(vec![(0, 1), (2, 1)])
+        );
+    }
+
+    #[test]
+    fn bar_lengths_of_tall_matrix() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_tall_matrix())
+            .decompose();
+        // The pairs (5, 1) and (3, 2) both have birth after death
+        assert_eq!(decomp.bar_lengths(), vec![4, 1]);
+    }
+
+    #[test]
+    fn essential_counts_of_tall_matrix() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_tall_matrix())
+            .decompose();
+        // Column 0 is unpaired, whereas columns 1 and 2 are deaths
+        assert_eq!(
+            decomp.essential_count_by_dim(),
+            hashbrown::HashMap::from_iter(vec![(0, 1)])
+        );
+    }
+
+    #[test]
+    fn n_cols_so_far_tracks_add_cols() {
+        let matrix: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let algo = SerialAlgorithm::init(None);
+        assert_eq!(algo.n_cols_so_far(), 0);
+        let algo = algo.add_cols(matrix[..4].iter().cloned());
+        assert_eq!(algo.n_cols_so_far(), 4);
+        let algo = algo.add_cols(std::iter::empty());
+        assert_eq!(algo.n_cols_so_far(), 4);
+        let algo = algo.add_cols(matrix[4..].iter().cloned());
+        assert_eq!(algo.n_cols_so_far(), 14);
+        assert_eq!(algo.decompose().n_cols(), 14);
+    }
+
+    #[test]
+    fn cone_over_circle_is_contractible() {
+        let circle: Vec<VecColumn> = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![0, 2]),
+            (1, vec![1, 2]),
+        ]
+        .into_iter()
+        .map(VecColumn::from)
+        .collect();
+        let mut decomp = SerialAlgorithm::init(None)
+            .add_cols(circle.iter().cloned())
+            .decompose();
+        assert_eq!(decomp.diagram().unpaired, HashSet::from_iter(vec![0, 5]));
+        decomp.extend_with_cone(circle.into_iter());
+        assert_eq!(decomp.n_cols(), 13);
+        let diagram = decomp.diagram();
+        assert_eq!(diagram.unpaired, HashSet::from_iter(vec![0]));
+        assert_eq!(diagram.paired.len(), 6);
+    }
+
+    #[test]
+    fn blocks_agree_with_whole_matrix() {
+        let sphere: Vec<VecColumn> = build_sphere_triangulation().collect();
+        let triangle: Vec<VecColumn> = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![0, 2]),
+            (1, vec![1, 2]),
+            (2, vec![3, 4, 5]),
+        ]
+        .into_iter()
+        .map(VecColumn::from)
+        .collect();
+        let n_sphere = sphere.len();
+        let whole = sphere.iter().cloned().chain(triangle.iter().map(|col| {
+            VecColumn::from((
+                col.dimension(),
+                col.entries().map(|row| row + n_sphere).collect(),
+            ))
+        }));
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let whole_decomposition = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(whole)
+            .decompose();
+        let blocks = vec![
+            (0..n_sphere, sphere),
+            (n_sphere..n_sphere + triangle.len(), triangle),
+        ];
+        let block_decomposition = decompose_blocks(blocks, Some(options));
+        assert_eq!(block_decomposition.diagram(), whole_decomposition.diagram());
+        for idx in 0..whole_decomposition.n_cols() {
+            assert_eq!(
+                block_decomposition.get_r_col(idx),
+                whole_decomposition.get_r_col(idx)
+            );
+            assert_eq!(
+                block_decomposition.get_v_col(idx).unwrap(),
+                whole_decomposition.get_v_col(idx).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn blocks_must_tile_columns() {
+        let block: Vec<VecColumn> = vec![VecColumn::new_with_dimension(0)];
+        decompose_blocks(vec![(0..1, block.clone()), (2..3, block)], None);
+    }
+
+    #[test]
+    fn pretty_print_sphere() {
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = SerialAlgorithm::init(Some(options))
+            .add_cols(build_sphere_triangulation())
+            .decompose();
+        let expected = [
+            "R:",
+            "0(0): []",
+            "1(0): []",
+            "2(0): []",
+            "3(0): []",
+            "4(1): [0, 1*]",
+            "5(1): [0, 2*]",
+            "6(1): []",
+            "7(1): [0, 3*]",
+            "8(1): []",
+            "9(1): []",
+            "10(2): [4, 7, 8*]",
+            "11(2): [5, 7, 9*]",
+            "12(2): [4, 5, 6*]",
+            "13(2): []",
+            "V:",
+            "0(0): [0]",
+            "1(0): [1]",
+            "2(0): [2]",
+            "3(0): [3]",
+            "4(1): [4]",
+            "5(1): [5]",
+            "6(1): [4, 5, 6]",
+            "7(1): [7]",
+            "8(1): [4, 7, 8]",
+            "9(1): [5, 7, 9]",
+            "10(2): [10]",
+            "11(2): [11]",
+            "12(2): [10, 11, 12]",
+            "13(2): [10, 11, 12, 13]",
+        ]
+        .join("\n");
+        assert_eq!(decomp.pretty_print(), expected);
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -0,0 +1,101 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::sync::Mutex;
+
+use crate::columns::Column;
+use crate::utils::{write_column, ColumnReader};
+
+// Distinguishes the spill files of decompositions within the same process
+static NEXT_SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+// NOTE: We use `u64::MAX` as a sentinel value, meaning the column was not spilled.
+const NOT_SPILLED: u64 = u64::MAX;
+
+// A file holding columns of R (and V) which have been dropped from memory, in the format written by write_column.
+// Each spilled column is stored as its R column, followed by its V column if present.
+// The file is deleted when dropped.
+pub(crate) struct SpillFile {
+    path: PathBuf,
+    file: Mutex<File>,
+    // The byte offset of each spilled column within the file
+    offsets: Vec<u64>,
+    len: u64,
+}
+
+impl SpillFile {
+    pub(crate) fn create(dir: &Path) -> io::Result<Self> {
+        let id = NEXT_SPILL_ID.fetch_add(1, Relaxed);
+        let path = dir.join(format!("lophat-spill-{}-{id}.bin", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            offsets: vec![],
+            len: 0,
+        })
+    }
+
+    // Appends the provided columns, each given as its index alongside its R and V columns, to the end of the file
+    pub(crate) fn spill<C: Column, P: Deref<Target = (C, Option<C>)>>(
+        &mut self,
+        cols: impl Iterator<Item = (usize, P)>,
+    ) -> io::Result<()> {
+        let file = self
+            .file
+            .get_mut()
+            .expect("Spill file should not be poisoned");
+        file.seek(SeekFrom::Start(self.len))?;
+        let mut writer = BufWriter::new(file);
+        for (idx, pair) in cols {
+            if self.offsets.len() <= idx {
+                self.offsets.resize(idx + 1, NOT_SPILLED);
+            }
+            self.offsets[idx] = self.len;
+            for col in std::iter::once(&pair.0).chain(pair.1.as_ref()) {
+                write_column(col, &mut writer)?;
+                // The dimension and number of entries, followed by the entries
+                self.len += 8 * (2 + col.entries().count() as u64);
+            }
+        }
+        writer.flush()
+    }
+
+    // Reads back the R and V columns of `idx`, or returns None if it was never spilled
+    pub(crate) fn read<C: Column>(&self, idx: usize, has_v: bool) -> Option<(C, Option<C>)> {
+        let offset = *self
+            .offsets
+            .get(idx)
+            .filter(|&&offset| offset != NOT_SPILLED)?;
+        let mut file = self.file.lock().expect("Spill file should not be poisoned");
+        let pair = read_pair(&mut file, offset, has_v)
+            .expect("Should be able to read spilled column back from disk");
+        Some(pair)
+    }
+}
+
+fn read_pair<C: Column>(file: &mut File, offset: u64, has_v: bool) -> io::Result<(C, Option<C>)> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut reader = ColumnReader::new(BufReader::new(file));
+    let mut next = || {
+        reader
+            .next()
+            .unwrap_or_else(|| Err(io::ErrorKind::UnexpectedEof.into()))
+    };
+    let r_col = next()?;
+    let v_col = if has_v { Some(next()?) } else { None };
+    Ok((r_col, v_col))
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // Nothing useful can be done if the file cannot be removed
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
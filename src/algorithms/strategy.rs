@@ -0,0 +1,491 @@
+#[cfg(feature = "serde")]
+use crate::impl_rvd_serialize;
+
+use crate::{
+    algorithms::Decomposition, columns::Column, options::LoPhatOptions, utils::PersistenceDiagram,
+};
+
+use hashbrown::HashMap;
+
+use super::{
+    collect_dimensions, infer_column_height, ColumnClass, DecompositionAlgo, LockFreeAlgorithm,
+    LockingAlgorithm, NoVMatrixError, SerialAlgorithm,
+};
+
+/// A method of reducing a matrix, which can be plugged into a [`StrategyAlgorithm`] to reuse the [`DecompositionAlgo`] and [`Decomposition`] plumbing.
+///
+/// Implement this to experiment with a new reduction, e.g. offloading to a GPU or trying a different scheduler.
+/// The built-in algorithms are available as [`SerialStrategy`], [`LockFreeStrategy`] and [`LockingStrategy`].
+/// Each of these reduces every column with the same loop as its algorithm, so behaves exactly as that algorithm.
+pub trait ReductionStrategy<C: Column> {
+    /// Reduces the columns of R in `matrix`, in place, so that no two non-empty columns share a pivot.
+    /// * `matrix` - each column of R alongside its column of V, if V is being maintained.
+    ///   Every column operation applied to R should also be applied to V.
+    /// * `pivots` - to be filled with the index of the column with each pivot, keyed by pivot; initially empty.
+    /// * `options` - the options provided to the [`StrategyAlgorithm`], which the strategy may ignore.
+    fn reduce(
+        &self,
+        matrix: &mut [(C, Option<C>)],
+        pivots: &mut HashMap<usize, usize>,
+        options: &LoPhatOptions,
+    );
+}
+
+/// Reduces via the [`SerialAlgorithm`].
+/// The columns are moved into the algorithm and back out again, so are never copied.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SerialStrategy;
+
+/// Reduces via the [`LockFreeAlgorithm`].
+/// The columns are moved into the algorithm, but the reduced columns are copied back out, since the algorithm stores each behind an atomic pointer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LockFreeStrategy;
+
+/// Reduces via the [`LockingAlgorithm`].
+/// The columns are moved into the algorithm and back out again, so are never copied.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LockingStrategy;
+
+// A column of R alongside its column of V (if maintained), borrowed while it is added to another column
+pub(crate) trait PairRef<C> {
+    fn r_col(&self) -> &C;
+    fn v_col(&self) -> Option<&C>;
+}
+
+impl<C> PairRef<C> for (&C, Option<&C>) {
+    fn r_col(&self) -> &C {
+        self.0
+    }
+
+    fn v_col(&self) -> Option<&C> {
+        self.1
+    }
+}
+
+// Where the reduction of a column by reduce_column_against stopped
+pub(crate) enum ReducedColumn {
+    // The column is empty
+    Cycle,
+    // No column has claimed the pivot of the column
+    Unclaimed(usize),
+    // The pivot of the column is claimed by the later column `owner`
+    ClaimedLater { pivot: usize, owner: usize },
+}
+
+// Reduces column `j`, given as `r_col` and `v_col`, by repeatedly adding the earlier column which has claimed its pivot, in both R and V.
+// `claimed_by(l)` returns the index of the column which has claimed the pivot `l`, alongside that column, if any.
+// `after_add` is called on `r_col` after each addition.
+// This is the loop shared by the serial, lockfree and locking algorithms, which then differ in how they claim the pivot.
+pub(crate) fn reduce_column_against<C: Column, P: PairRef<C>>(
+    j: usize,
+    r_col: &mut C,
+    mut v_col: Option<&mut C>,
+    mut claimed_by: impl FnMut(usize) -> Option<(usize, P)>,
+    mut after_add: impl FnMut(&mut C),
+) -> ReducedColumn {
+    while let Some(pivot) = r_col.pivot() {
+        let Some((owner, column)) = claimed_by(pivot) else {
+            return ReducedColumn::Unclaimed(pivot);
+        };
+        assert_ne!(
+            owner, j,
+            "Should not reduce a column whose pivot it has claimed"
+        );
+        if owner > j {
+            return ReducedColumn::ClaimedLater { pivot, owner };
+        }
+        r_col.add_col(column.r_col());
+        after_add(r_col);
+        if let (Some(v_col), Some(owner_v_col)) = (v_col.as_deref_mut(), column.v_col()) {
+            v_col.add_col(owner_v_col);
+        }
+    }
+    ReducedColumn::Cycle
+}
+
+// The options passed on to the algorithm, which maintains V if and only if `matrix` does
+fn algorithm_options<C: Column>(
+    matrix: &[(C, Option<C>)],
+    options: &LoPhatOptions,
+) -> LoPhatOptions {
+    LoPhatOptions {
+        maintain_v: matrix.first().is_some_and(|(_, v_col)| v_col.is_some()),
+        ..options.clone()
+    }
+}
+
+// Moves the columns out of `matrix`, leaving empty columns of the same dimension until they are moved back
+fn take_columns<C: Column>(
+    matrix: &mut [(C, Option<C>)],
+) -> impl Iterator<Item = (C, Option<C>)> + '_ {
+    matrix.iter_mut().map(|(r_col, v_col)| {
+        let empty = C::new_with_dimension(r_col.dimension());
+        (std::mem::replace(r_col, empty), v_col.take())
+    })
+}
+
+impl<C: Column> ReductionStrategy<C> for SerialStrategy {
+    fn reduce(
+        &self,
+        matrix: &mut [(C, Option<C>)],
+        pivots: &mut HashMap<usize, usize>,
+        options: &LoPhatOptions,
+    ) {
+        let options = algorithm_options(matrix, options);
+        let (r, v, low_inverse) = SerialAlgorithm::init(Some(options))
+            .add_cols_with_v(take_columns(matrix))
+            .decompose()
+            .into_parts();
+        let mut v = v.map(Vec::into_iter);
+        for ((r_col, v_col), r_out) in matrix.iter_mut().zip(r) {
+            *r_col = r_out;
+            *v_col = v.as_mut().and_then(Iterator::next);
+        }
+        pivots.extend(low_inverse);
+    }
+}
+
+impl<C: Column + 'static> ReductionStrategy<C> for LockFreeStrategy {
+    fn reduce(
+        &self,
+        matrix: &mut [(C, Option<C>)],
+        pivots: &mut HashMap<usize, usize>,
+        options: &LoPhatOptions,
+    ) {
+        let options = algorithm_options(matrix, options);
+        let maintain_v = options.maintain_v;
+        let decomposition = LockFreeAlgorithm::init(Some(options))
+            .add_cols_with_v(take_columns(matrix))
+            .decompose();
+        for (idx, (r_col, v_col)) in matrix.iter_mut().enumerate() {
+            *r_col = decomposition.get_r_col_owned(idx);
+            if maintain_v {
+                let v_out = decomposition
+                    .get_v_col_owned(idx)
+                    .expect("V should be maintained for every column");
+                *v_col = Some(v_out);
+            }
+        }
+        pivots.extend(decomposition.claimed_pivots());
+    }
+}
+
+impl<C: Column + 'static> ReductionStrategy<C> for LockingStrategy {
+    fn reduce(
+        &self,
+        matrix: &mut [(C, Option<C>)],
+        pivots: &mut HashMap<usize, usize>,
+        options: &LoPhatOptions,
+    ) {
+        let options = algorithm_options(matrix, options);
+        let (columns, claimed) = LockingAlgorithm::init(Some(options))
+            .add_cols_with_v(take_columns(matrix))
+            .decompose_into_parts();
+        for (slot, column) in matrix.iter_mut().zip(columns) {
+            *slot = column;
+        }
+        pivots.extend(claimed);
+    }
+}
+
+/// Collects the input matrix and decomposes it with a pluggable [`ReductionStrategy`].
+///
+/// If constructed via [`init`](DecompositionAlgo::init), the default value of the strategy is used; otherwise see [`with_strategy`](Self::with_strategy).
+/// In either case, the strategy must implement [`Default`] for this to implement [`DecompositionAlgo`].
+/// V is initialised to the identity if [`maintain_v`](LoPhatOptions::maintain_v) is set, and the options are passed on to the strategy.
+pub struct StrategyAlgorithm<C: Column, S: ReductionStrategy<C>> {
+    matrix: Vec<(C, Option<C>)>,
+    options: LoPhatOptions,
+    strategy: S,
+}
+
+impl<C: Column, S: ReductionStrategy<C>> StrategyAlgorithm<C, S> {
+    /// Initialises the algorithm with the given strategy.
+    pub fn with_strategy(strategy: S, options: Option<LoPhatOptions>) -> Self {
+        Self {
+            matrix: vec![],
+            options: options.unwrap_or_default(),
+            strategy,
+        }
+    }
+}
+
+impl<C: Column, S: ReductionStrategy<C> + Default> DecompositionAlgo<C>
+    for StrategyAlgorithm<C, S>
+{
+    type Options = LoPhatOptions;
+
+    fn init(options: Option<Self::Options>) -> Self {
+        Self::with_strategy(S::default(), options)
+    }
+
+    fn add_cols(mut self, cols: impl Iterator<Item = C>) -> Self {
+        let first_idx = self.matrix.len();
+        let maintain_v = self.options.maintain_v;
+        self.matrix.extend(cols.enumerate().map(|(idx, r_col)| {
+            let v_col = maintain_v.then(|| {
+                let mut v_col = C::new_with_dimension(r_col.dimension());
+                v_col.add_entry(first_idx + idx);
+                v_col
+            });
+            (r_col, v_col)
+        }));
+        self
+    }
+
+    fn add_entries(mut self, entries: impl Iterator<Item = (usize, usize)>) -> Self {
+        for (row, col) in entries {
+            let (r_col, _) = self
+                .matrix
+                .get_mut(col)
+                .expect("Column index should correspond to a pre-existing column");
+            r_col.add_entry(row);
+        }
+        self
+    }
+
+    fn set_dimensions(mut self, dims: impl Iterator<Item = usize>) -> Self {
+        let dims = collect_dimensions(dims, self.matrix.len());
+        for ((r_col, v_col), dim) in self.matrix.iter_mut().zip(dims) {
+            r_col.set_dimension(dim);
+            if let Some(v_col) = v_col.as_mut() {
+                v_col.set_dimension(dim);
+            }
+        }
+        self
+    }
+
+    fn n_cols_so_far(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn lophat_options(&self) -> Option<&LoPhatOptions> {
+        Some(&self.options)
+    }
+
+    // Whether column_height is used is up to the strategy
+    fn inferred_column_height(&self) -> usize {
+        infer_column_height(self.matrix.iter().map(|(r_col, _)| r_col.pivot()))
+    }
+
+    type Decomposition = StrategyDecomposition<C>;
+
+    fn decompose(mut self) -> Self::Decomposition {
+        let mut pivots = HashMap::new();
+        self.strategy
+            .reduce(&mut self.matrix, &mut pivots, &self.options);
+        let (r, v): (Vec<C>, Vec<Option<C>>) = self.matrix.into_iter().unzip();
+        let v = self.options.maintain_v.then(|| {
+            v.into_iter()
+                .map(|v_col| v_col.expect("Strategy should maintain V for every column"))
+                .collect()
+        });
+        StrategyDecomposition { r, v, pivots }
+    }
+}
+
+/// Return type of [`StrategyAlgorithm`].
+/// Retains the map from pivots to columns produced by the strategy, which is used to answer [`column_with_pivot`](Decomposition::column_with_pivot), [`classify`](Decomposition::classify) and [`diagram`](Decomposition::diagram).
+pub struct StrategyDecomposition<C: Column> {
+    r: Vec<C>,
+    v: Option<Vec<C>>,
+    pivots: HashMap<usize, usize>,
+}
+
+impl<C: Column> Decomposition<C> for StrategyDecomposition<C> {
+    type RColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
+    fn get_r_col(&self, index: usize) -> &C {
+        &self.r[index]
+    }
+
+    type VColRef<'a>
+        = &'a C
+    where
+        Self: 'a;
+    fn get_v_col(&self, index: usize) -> Result<&C, NoVMatrixError> {
+        Ok(&self.v.as_ref().ok_or(NoVMatrixError)?[index])
+    }
+
+    fn n_cols(&self) -> usize {
+        self.r.len()
+    }
+
+    fn column_with_pivot(&self, l: usize) -> Option<usize> {
+        self.pivots.get(&l).copied()
+    }
+
+    // Pairs are read from the map, rather than the columns, since with a pair filter a column may not claim its lowest entry
+    fn diagram(&self) -> PersistenceDiagram {
+        let paired = self
+            .pivots
+            .iter()
+            .map(|(&birth, &death)| (birth, death))
+            .collect();
+        let unpaired = (0..self.r.len())
+            .filter(|idx| !self.pivots.contains_key(idx) && self.r[*idx].is_cycle())
+            .collect();
+        PersistenceDiagram { unpaired, paired }
+    }
+
+    fn classify(&self, idx: usize) -> ColumnClass {
+        let claimed = self.r[idx]
+            .pivots_descending()
+            .find(|row| self.pivots.get(row) == Some(&idx));
+        if let Some(birth) = claimed {
+            return ColumnClass::Death(birth);
+        }
+        match self.pivots.get(&idx) {
+            Some(&death) => ColumnClass::Birth(death),
+            None => ColumnClass::Essential,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_rvd_serialize!(StrategyDecomposition);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::VecColumn;
+    use crate::utils::fixtures::build_sphere_triangulation;
+    use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+    use std::sync::Arc;
+
+    // Delegates to the serial strategy, counting the number of reductions
+    #[derive(Default)]
+    struct CountingStrategy(Arc<AtomicUsize>);
+
+    impl ReductionStrategy<VecColumn> for CountingStrategy {
+        fn reduce(
+            &self,
+            matrix: &mut [(VecColumn, Option<VecColumn>)],
+            pivots: &mut HashMap<usize, usize>,
+            options: &LoPhatOptions,
+        ) {
+            self.0.fetch_add(1, Relaxed);
+            SerialStrategy.reduce(matrix, pivots, options);
+        }
+    }
+
+    // Checks that decomposing the sphere with the given strategy agrees with the serial algorithm
+    fn check_strategy(strategy: impl ReductionStrategy<VecColumn> + Default) {
+        let matrix = build_sphere_triangulation();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let expected = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        let decomp = StrategyAlgorithm::with_strategy(strategy, Some(options))
+            .add_cols(matrix.iter().cloned())
+            .decompose();
+        assert_eq!(decomp.diagram(), expected.diagram());
+        for idx in 0..decomp.n_cols() {
+            let mut dv_col = VecColumn::new_with_dimension(0);
+            for i in decomp.get_v_col(idx).unwrap().entries() {
+                dv_col.add_col(&matrix[i]);
+            }
+            assert!(dv_col.entries().eq(decomp.get_r_col(idx).entries()));
+            assert_eq!(decomp.pair_of(idx), expected.pair_of(idx));
+            if let Some(pivot) = decomp.get_r_col(idx).pivot() {
+                assert_eq!(decomp.column_with_pivot(pivot), Some(idx));
+            }
+        }
+    }
+
+    #[test]
+    fn built_in_strategies_agree_with_serial() {
+        check_strategy(SerialStrategy);
+        check_strategy(LockFreeStrategy);
+        check_strategy(LockingStrategy);
+    }
+
+    #[test]
+    fn built_in_strategies_apply_operations_to_existing_v() {
+        let matrix = build_sphere_triangulation();
+        // Start from a V which is not the identity, with R = DV
+        let mut input: Vec<_> = matrix
+            .iter()
+            .enumerate()
+            .map(|(idx, col)| {
+                (
+                    col.clone(),
+                    Some(VecColumn::from((col.dimension(), vec![idx]))),
+                )
+            })
+            .collect();
+        input[11].0.add_col(&matrix[10]);
+        input[11].1.as_mut().unwrap().add_entry(10);
+        let strategies: [&dyn ReductionStrategy<VecColumn>; 3] =
+            [&SerialStrategy, &LockFreeStrategy, &LockingStrategy];
+        for strategy in strategies {
+            let mut reduced = input.clone();
+            let mut pivots = HashMap::new();
+            strategy.reduce(&mut reduced, &mut pivots, &LoPhatOptions::default());
+            for (idx, (r_col, v_col)) in reduced.iter().enumerate() {
+                let mut dv_col = VecColumn::new_with_dimension(0);
+                for i in v_col.as_ref().unwrap().entries() {
+                    dv_col.add_col(&matrix[i]);
+                }
+                assert!(dv_col.entries().eq(r_col.entries()));
+                if let Some(pivot) = r_col.pivot() {
+                    assert_eq!(pivots.get(&pivot), Some(&idx));
+                }
+            }
+            assert_eq!(pivots.len(), 6);
+        }
+    }
+
+    #[test]
+    fn pivots_are_those_claimed_with_pair_filter() {
+        let options = LoPhatOptions {
+            accept_pair: Some(Arc::new(|birth, death| (birth, death) != (1, 4))),
+            ..Default::default()
+        };
+        let expected = SerialAlgorithm::init(Some(options.clone()))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let decomp = StrategyAlgorithm::with_strategy(SerialStrategy, Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        // Column 4 keeps the rejected pivot 1, but claims row 0, while column 6 claims row 1
+        assert_eq!(decomp.get_r_col(4).pivot(), Some(1));
+        assert_eq!(decomp.column_with_pivot(0), Some(4));
+        assert_eq!(decomp.column_with_pivot(1), Some(6));
+        assert_eq!(decomp.diagram(), expected.diagram());
+        for idx in 0..decomp.n_cols() {
+            assert_eq!(decomp.classify(idx), expected.classify(idx));
+        }
+    }
+
+    #[test]
+    fn custom_strategy_is_used() {
+        let count = Arc::new(AtomicUsize::new(0));
+        check_strategy(CountingStrategy(count.clone()));
+        assert_eq!(count.load(Relaxed), 1);
+    }
+
+    #[test]
+    fn try_decompose_validates_clearing() {
+        let options = LoPhatOptions {
+            clearing: true,
+            column_height: Some(20),
+            ..Default::default()
+        };
+        let result = StrategyAlgorithm::<_, SerialStrategy>::init(Some(options))
+            .add_cols(build_sphere_triangulation().into_iter())
+            .try_decompose();
+        assert_eq!(
+            result.err(),
+            Some(crate::error::LophatError::NonSquareWithClearing {
+                column_height: 20,
+                n_cols: 14
+            })
+        );
+    }
+}
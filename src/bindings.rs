@@ -1,31 +1,35 @@
+use hashbrown::HashMap;
 use pyo3::prelude::*;
 use pyo3::types::PyIterator;
 
 use crate::algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm};
-use crate::columns::Column;
 use crate::columns::VecColumn;
-use crate::options::LoPhatOptions;
+use crate::options::{LoPhatOptions, SchedulePolicy};
 use crate::utils::{anti_transpose, PersistenceDiagram};
 
+// Collects the input matrix, provided as either a list or an iterator of columns
+fn collect_matrix(py: Python<'_>, matrix: &PyAny) -> Vec<VecColumn> {
+    if let Ok(matrix_as_vec) = matrix.extract::<Vec<(usize, Vec<usize>)>>() {
+        matrix_as_vec.into_iter().map(VecColumn::from).collect()
+    } else if let Ok(py_iter) = PyIterator::from_object(py, matrix) {
+        py_iter
+            .map(|col| {
+                col.and_then(PyAny::extract::<(usize, Vec<usize>)>)
+                    .map(VecColumn::from)
+                    .expect("Column is a list of unsigned integers")
+            })
+            .collect()
+    } else {
+        panic!("Could not coerce input matrix into List[List[int]] | Iterator[List[int]]");
+    }
+}
+
 fn compute_pairings_anti_transpose(
     py: Python<'_>,
     matrix: &PyAny,
     options: Option<LoPhatOptions>,
 ) -> PersistenceDiagram {
-    let matrix_as_vec: Vec<_> =
-        if let Ok(matrix_as_vec) = matrix.extract::<Vec<(usize, Vec<usize>)>>() {
-            matrix_as_vec.into_iter().map(VecColumn::from).collect()
-        } else if let Ok(py_iter) = PyIterator::from_object(py, matrix) {
-            py_iter
-                .map(|col| {
-                    col.and_then(PyAny::extract::<(usize, Vec<usize>)>)
-                        .map(VecColumn::from)
-                        .expect("Column is a list of unsigned integers")
-                })
-                .collect()
-        } else {
-            panic!("Could not coerce input matrix into List[List[int]] | Iterator[List[int]]");
-        };
+    let matrix_as_vec = collect_matrix(py, matrix);
     let width = matrix_as_vec.len();
     let at: Vec<_> = anti_transpose(&matrix_as_vec);
     let dgm = {
@@ -73,55 +77,29 @@ struct PersistenceDiagramWithReps {
 }
 
 #[pyfunction]
+#[pyo3(signature = (matrix, options=None, parallel_reps=false))]
 fn compute_pairings_with_reps(
     py: Python<'_>,
     matrix: &PyAny,
     options: Option<LoPhatOptions>,
+    parallel_reps: bool,
 ) -> PersistenceDiagramWithReps {
     // Overwrite maintain_v in options
-    let mut options = options.unwrap_or(LoPhatOptions::default());
+    let mut options = options.unwrap_or_default();
     options.maintain_v = true;
     let options = Some(options);
     // Run R=DV decomposition
-    let decomposition = if let Ok(matrix_as_vec) = matrix.extract::<Vec<(usize, Vec<usize>)>>() {
-        let matrix_as_rs_iter = matrix_as_vec.into_iter().map(VecColumn::from);
-        LockFreeAlgorithm::init(options)
-            .add_cols(matrix_as_rs_iter)
-            .decompose()
-    } else if let Ok(py_iter) = PyIterator::from_object(py, matrix) {
-        let matrix_as_rs_iter = py_iter.map(|col| {
-            col.and_then(PyAny::extract::<(usize, Vec<usize>)>)
-                .map(VecColumn::from)
-                .expect("Column is a list of unsigned integers")
-        });
-        LockFreeAlgorithm::init(options)
-            .add_cols(matrix_as_rs_iter)
-            .decompose()
+    let decomposition = LockFreeAlgorithm::init(options)
+        .add_cols(collect_matrix(py, matrix).into_iter())
+        .decompose();
+    // Read off diagram and pull out representatives
+    let reps = if parallel_reps {
+        decomposition.all_representatives_parallel()
     } else {
-        panic!("Could not coerce input matrix into List[List[int]] | Iterator[List[int]]");
+        decomposition.all_representatives()
     };
-    // Read off diagram and pull out representatives
-    let mut diagram = decomposition.diagram();
-    let (paired, paired_reps): (Vec<_>, Vec<Vec<_>>) = diagram
-        .paired
-        .drain()
-        .map(|pairing| {
-            (
-                pairing,
-                decomposition.get_r_col(pairing.1).entries().collect(),
-            )
-        })
-        .unzip();
-    let (unpaired, unpaired_reps): (Vec<_>, Vec<Vec<_>>) = diagram
-        .unpaired
-        .drain()
-        .map(|birth| {
-            (
-                birth,
-                decomposition.get_v_col(birth).unwrap().entries().collect(),
-            )
-        })
-        .unzip();
+    let (paired, paired_reps) = reps.paired.into_iter().unzip();
+    let (unpaired, unpaired_reps) = reps.unpaired.into_iter().unzip();
     PersistenceDiagramWithReps {
         paired,
         unpaired,
@@ -145,11 +123,27 @@ fn compute_pairings(
     }
 }
 
+#[pyfunction]
+#[pyo3(signature = (matrix, options=None))]
+fn betti_numbers(
+    py: Python<'_>,
+    matrix: &PyAny,
+    options: Option<LoPhatOptions>,
+) -> HashMap<usize, usize> {
+    let matrix = collect_matrix(py, matrix);
+    LockFreeAlgorithm::init(options)
+        .add_cols(matrix.into_iter())
+        .decompose()
+        .betti_numbers()
+}
+
 // A Python module implemented in Rust.
 #[pymodule]
 fn lophat(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compute_pairings, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairings_with_reps, m)?)?;
+    m.add_function(wrap_pyfunction!(betti_numbers, m)?)?;
     m.add_class::<LoPhatOptions>()?;
+    m.add_class::<SchedulePolicy>()?;
     Ok(())
 }
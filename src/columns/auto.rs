@@ -0,0 +1,274 @@
+use super::{BitSetColumn, Column, ColumnMode, VecColumn};
+
+// A column is stored densely once at least one in this many of the rows up to its pivot are non-zero.
+// This is roughly where a bit per row costs less memory than a usize per entry.
+const DENSITY_THRESHOLD: usize = usize::BITS as usize;
+
+#[derive(Debug, Clone)]
+enum AutoColumnInternal {
+    Sparse(VecColumn),
+    Dense(BitSetColumn),
+}
+
+impl Default for AutoColumnInternal {
+    fn default() -> Self {
+        Self::Sparse(VecColumn::default())
+    }
+}
+
+/// The output type of [`AutoColumn::entries`].
+pub enum AutoColumnIter<'a> {
+    Sparse(<VecColumn as Column>::EntriesIter<'a>),
+    Dense(<BitSetColumn as Column>::EntriesIter<'a>),
+}
+
+impl<'a> Iterator for AutoColumnIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            AutoColumnIter::Sparse(x) => x.next(),
+            AutoColumnIter::Dense(x) => x.next(),
+        }
+    }
+}
+
+/// A column which chooses its representation by density when it is constructed.
+///
+/// * Sparse columns are represented as a [`VecColumn`].
+/// * Dense columns, in which at least one in 64 of the rows up to the pivot are non-zero, are represented as a [`BitSetColumn`].
+///
+/// The representation is chosen when the column is constructed via [`AutoColumn::from`], [`set_entries`](Column::set_entries),
+/// or by calling [`add_entries`](Column::add_entries) on an empty column (as in [`build_matrix`](super::build_matrix)).
+/// It is then fixed during reduction, with [`add_col`](Column::add_col) handling operands of differing representations.
+/// Hence a matrix with a mixture of sparse and dense columns can make use of both representations.
+#[derive(Debug, Default, Clone)]
+pub struct AutoColumn {
+    internal: AutoColumnInternal,
+}
+
+impl AutoColumn {
+    /// Returns whether the column is represented as a [`BitSetColumn`], rather than a [`VecColumn`].
+    pub fn is_dense(&self) -> bool {
+        matches!(self.internal, AutoColumnInternal::Dense(_))
+    }
+
+    // Chooses the representation based on the density of the sorted, distinct `entries`
+    fn from_sorted(dimension: usize, entries: Vec<usize>) -> Self {
+        let height = entries.last().map_or(0, |pivot| pivot + 1);
+        let internal = if entries.len() * DENSITY_THRESHOLD >= height && !entries.is_empty() {
+            let mut col = BitSetColumn::with_capacity(dimension, height);
+            col.add_entries(entries.into_iter());
+            AutoColumnInternal::Dense(col)
+        } else {
+            AutoColumnInternal::Sparse(VecColumn::from((dimension, entries)))
+        };
+        Self { internal }
+    }
+}
+
+impl Column for AutoColumn {
+    fn pivot(&self) -> Option<usize> {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => x.pivot(),
+            AutoColumnInternal::Dense(x) => x.pivot(),
+        }
+    }
+
+    fn add_col(&mut self, other: &Self) {
+        match (&mut self.internal, &other.internal) {
+            (AutoColumnInternal::Sparse(x), AutoColumnInternal::Sparse(y)) => x.add_col(y),
+            (AutoColumnInternal::Dense(x), AutoColumnInternal::Dense(y)) => x.add_col(y),
+            // Toggling each bit is cheap, so add the sparse column one entry at a time
+            (AutoColumnInternal::Dense(x), AutoColumnInternal::Sparse(y)) => {
+                x.add_entries(y.entries())
+            }
+            // Entries are sorted and merged in a single pass
+            (AutoColumnInternal::Sparse(x), AutoColumnInternal::Dense(y)) => {
+                x.add_entries(y.entries())
+            }
+        }
+    }
+
+    fn add_entry(&mut self, entry: usize) {
+        match &mut self.internal {
+            AutoColumnInternal::Sparse(x) => x.add_entry(entry),
+            AutoColumnInternal::Dense(x) => x.add_entry(entry),
+        }
+    }
+
+    fn has_entry(&self, entry: &usize) -> bool {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => x.has_entry(entry),
+            AutoColumnInternal::Dense(x) => x.has_entry(entry),
+        }
+    }
+
+    type EntriesIter<'a> = AutoColumnIter<'a>;
+
+    fn entries<'a>(&'a self) -> Self::EntriesIter<'a> {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => AutoColumnIter::Sparse(x.entries()),
+            AutoColumnInternal::Dense(x) => AutoColumnIter::Dense(x.entries()),
+        }
+    }
+
+    // Exactly one of the two iterators is present
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        let (sparse, dense) = match &self.internal {
+            AutoColumnInternal::Sparse(x) => (Some(x.pivots_descending()), None),
+            AutoColumnInternal::Dense(x) => (None, Some(x.pivots_descending())),
+        };
+        sparse
+            .into_iter()
+            .flatten()
+            .chain(dense.into_iter().flatten())
+    }
+
+    type EntriesRepr = Vec<usize>;
+
+    fn set_entries(&mut self, entries: Self::EntriesRepr) {
+        *self = Self::from_sorted(self.dimension(), entries);
+    }
+
+    fn dimension(&self) -> usize {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => x.dimension(),
+            AutoColumnInternal::Dense(x) => x.dimension(),
+        }
+    }
+
+    fn set_dimension(&mut self, dimension: usize) {
+        match &mut self.internal {
+            AutoColumnInternal::Sparse(x) => x.set_dimension(dimension),
+            AutoColumnInternal::Dense(x) => x.set_dimension(dimension),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => x.is_empty(),
+            AutoColumnInternal::Dense(x) => x.is_empty(),
+        }
+    }
+
+    // The representation is fixed at construction
+    fn set_mode(&mut self, _mode: ColumnMode) {}
+
+    // Adding entries to an empty column constructs it, so we choose the representation here
+    fn add_entries<B: Iterator<Item = usize>>(&mut self, entries: B) {
+        if self.is_empty() {
+            let col = VecColumn::from_iter_xor(self.dimension(), entries);
+            *self = Self::from_sorted(col.dimension(), col.entries().collect());
+        } else {
+            match &mut self.internal {
+                AutoColumnInternal::Sparse(x) => x.add_entries(entries),
+                AutoColumnInternal::Dense(x) => x.add_entries(entries),
+            }
+        }
+    }
+
+    // Only the dense representation can make use of reserved storage
+    fn reserve(&mut self, column_height: usize) {
+        if let AutoColumnInternal::Dense(x) = &mut self.internal {
+            x.reserve(column_height);
+        }
+    }
+
+    fn as_vec_column(&self) -> Option<&VecColumn> {
+        match &self.internal {
+            AutoColumnInternal::Sparse(x) => Some(x),
+            AutoColumnInternal::Dense(_) => None,
+        }
+    }
+
+    fn clone_from_col(&mut self, other: &Self) {
+        match (&mut self.internal, &other.internal) {
+            (AutoColumnInternal::Sparse(x), AutoColumnInternal::Sparse(y)) => x.clone_from_col(y),
+            (AutoColumnInternal::Dense(x), AutoColumnInternal::Dense(y)) => x.clone_from_col(y),
+            _ => *self = other.clone(),
+        }
+    }
+}
+
+// Two columns are equal if they have the same entries, regardless of representation
+impl PartialEq for AutoColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimension() == other.dimension()
+            && self.pivots_descending().eq(other.pivots_descending())
+    }
+}
+
+impl From<(usize, Vec<usize>)> for AutoColumn {
+    /// Constructs an `AutoColumn`, from a tuple where
+    /// `entries` is the vector of non-zero indices, sorted in increasing order.
+    fn from((dimension, entries): (usize, Vec<usize>)) -> Self {
+        Self::from_sorted(dimension, entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm};
+    use crate::columns::build_matrix;
+    use crate::options::LoPhatOptions;
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
+
+    #[test]
+    fn representation_chosen_by_density() {
+        assert!(!AutoColumn::new_with_dimension(0).is_dense());
+        assert!(AutoColumn::from((1, vec![0, 1, 2])).is_dense());
+        assert!(!AutoColumn::from((1, vec![0, 1000])).is_dense());
+        let mut col = AutoColumn::new_with_dimension(1);
+        col.add_entries((0..1000).step_by(2));
+        assert!(col.is_dense());
+        // Mixed representations agree with VecColumn
+        let mut sparse = AutoColumn::from((1, vec![3, 500, 1001]));
+        sparse.add_col(&col);
+        col.add_col(&AutoColumn::from((1, vec![3, 500, 1001])));
+        assert!(!sparse.is_dense());
+        assert!(col.is_dense());
+        assert_eq!(sparse, col);
+        let mut expected = VecColumn::from((1, (0..1000).step_by(2).collect()));
+        expected.add_col(&VecColumn::from((1, vec![3, 500, 1001])));
+        assert!(col.pivots_descending().eq(expected.pivots_descending()));
+    }
+
+    // Generates a strict upper triangular matrix, in which each column is either sparse or dense
+    fn mixed_density_matrix(size: usize) -> impl Strategy<Value = Vec<(usize, Vec<usize>)>> {
+        (1..size)
+            .map(|i| {
+                let max_entries = if i % 3 == 0 { i } else { 2 };
+                hash_set(0..i, 0..max_entries).prop_map(|set| {
+                    let mut entries: Vec<usize> = set.into_iter().collect();
+                    entries.sort_unstable();
+                    (0, entries)
+                })
+            })
+            .collect::<Vec<_>>()
+    }
+
+    proptest! {
+        #[test]
+        fn auto_agrees_with_vec( spec in mixed_density_matrix(200) ) {
+            let options = LoPhatOptions {
+                clearing: false,
+                maintain_v: true,
+                ..Default::default()
+            };
+            let auto = LockFreeAlgorithm::init(Some(options.clone()))
+                .add_cols(build_matrix::<AutoColumn>(&spec).into_iter())
+                .decompose();
+            let vec = LockFreeAlgorithm::init(Some(options))
+                .add_cols(build_matrix::<VecColumn>(&spec).into_iter())
+                .decompose();
+            assert_eq!(auto.diagram(), vec.diagram());
+            for idx in 0..spec.len() {
+                assert!(auto.get_r_col(idx).pivots_descending().eq(vec.get_r_col(idx).pivots_descending()));
+                assert!(auto.get_v_col(idx).unwrap().pivots_descending().eq(vec.get_v_col(idx).unwrap().pivots_descending()));
+            }
+        }
+    }
+}
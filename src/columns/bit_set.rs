@@ -1,18 +1,54 @@
 use bit_set::BitSet;
 
 use super::{Column, ColumnMode};
+
+// Number of bits in each block of the underlying bit vector
+const BLOCK_BITS: usize = u32::BITS as usize;
 /// A column represented by a bit vector (with a set-like interface) of the non-zero indices.
 ///
+/// Although the bit vector is stored in `u32` blocks, indices are `usize` throughout, so there is no cap at `u32::MAX`.
+/// However, the storage grows with the largest index, so very large indices are expensive.
+///
 /// To construct call [`BitSetColumn::from`] or use [`BitSetColumn::new_with_dimension`] and [`BitSetColumn::add_entries`]
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub struct BitSetColumn {
     boundary: BitSet,
     dimension: usize,
 }
 
+// Implemented by hand so that clone_from re-uses the existing allocation
+impl Clone for BitSetColumn {
+    fn clone(&self) -> Self {
+        Self {
+            boundary: self.boundary.clone(),
+            dimension: self.dimension,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.boundary.clone_from(&source.boundary);
+        self.dimension = source.dimension;
+    }
+}
+
+impl BitSetColumn {
+    /// Init an empty column with the given dimension and enough storage to hold entries in the range `0..nbits` without reallocating.
+    pub fn with_capacity(dimension: usize, nbits: usize) -> Self {
+        Self {
+            boundary: BitSet::with_capacity(nbits),
+            dimension,
+        }
+    }
+
+    /// Returns the number of bits that the column can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.boundary.capacity()
+    }
+}
+
 impl Column for BitSetColumn {
     fn pivot(&self) -> Option<usize> {
-        self.boundary.iter().max()
+        self.pivots_descending().next()
     }
 
     fn add_col(&mut self, other: &Self) {
@@ -20,10 +56,9 @@ impl Column for BitSetColumn {
     }
 
     fn add_entry(&mut self, entry: usize) {
-        if self.has_entry(&entry) {
+        // insert only fails if the entry is already present, so we avoid a separate lookup
+        if !self.boundary.insert(entry) {
             self.boundary.remove(entry);
-        } else {
-            self.boundary.insert(entry);
         }
     }
 
@@ -37,6 +72,23 @@ impl Column for BitSetColumn {
         self.boundary.iter()
     }
 
+    // Scans the blocks from the top, so that the pivot is found without visiting lower blocks
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        let blocks = self.boundary.get_ref().blocks();
+        let n_blocks = blocks.len();
+        blocks.rev().enumerate().flat_map(move |(k, mut block)| {
+            let offset = (n_blocks - 1 - k) * BLOCK_BITS;
+            std::iter::from_fn(move || {
+                if block == 0 {
+                    return None;
+                }
+                let bit = (BLOCK_BITS - 1) - block.leading_zeros() as usize;
+                block ^= 1 << bit;
+                Some(offset + bit)
+            })
+        })
+    }
+
     type EntriesRepr = BitSet;
 
     fn set_entries(&mut self, entries: Self::EntriesRepr) {
@@ -51,7 +103,7 @@ impl Column for BitSetColumn {
         self.dimension = dimension;
     }
 
-    fn is_cycle(&self) -> bool {
+    fn is_empty(&self) -> bool {
         self.boundary.is_empty()
     }
 
@@ -64,6 +116,18 @@ impl Column for BitSetColumn {
 
     // No difference for this representation
     fn set_mode(&mut self, _mode: ColumnMode) {}
+
+    fn reserve(&mut self, column_height: usize) {
+        self.boundary.reserve_len(column_height);
+    }
+
+    fn clone_from_col(&mut self, other: &Self) {
+        self.clone_from(other);
+    }
+
+    fn sym_diff_len(&self, other: &Self) -> usize {
+        self.boundary.symmetric_difference(&other.boundary).count()
+    }
 }
 
 impl From<(usize, BitSet)> for BitSetColumn {
@@ -76,3 +140,83 @@ impl From<(usize, BitSet)> for BitSetColumn {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserving_avoids_reallocation() {
+        let mut col = BitSetColumn::with_capacity(1, 1000);
+        let capacity = col.capacity();
+        assert!(capacity >= 1000);
+        col.add_entries((0..1000).step_by(7));
+        assert_eq!(col.capacity(), capacity);
+
+        let mut col = BitSetColumn::new_with_dimension(1);
+        col.reserve(2000);
+        let capacity = col.capacity();
+        assert!(capacity >= 2000);
+        col.add_entry(1999);
+        col.add_col(&BitSetColumn::from((1, BitSet::from_iter(vec![0, 1500]))));
+        assert_eq!(col.capacity(), capacity);
+        assert_eq!(col.pivot(), Some(1999));
+    }
+
+    #[test]
+    fn pivots_descending_matches_entries() {
+        let entries = vec![0, 5, 31, 32, 63, 64, 100, 1000];
+        let col = BitSetColumn::from((1, BitSet::from_iter(entries.clone())));
+        let descending: Vec<usize> = col.pivots_descending().collect();
+        assert_eq!(descending, entries.into_iter().rev().collect::<Vec<_>>());
+        assert_eq!(col.pivot(), Some(1000));
+        let mut col = BitSetColumn::with_capacity(1, 1000);
+        assert_eq!(col.pivot(), None);
+        col.add_entry(40);
+        assert_eq!(col.pivot(), Some(40));
+    }
+
+    #[test]
+    fn toggling_twice_is_identity() {
+        let original = BitSetColumn::from((1, BitSet::from_iter(vec![2, 40, 77])));
+        for entry in [0, 2, 40, 77, 100, 5000] {
+            let mut col = original.clone();
+            col.add_entry(entry);
+            assert_ne!(col.has_entry(&entry), original.has_entry(&entry));
+            col.add_entry(entry);
+            assert_eq!(
+                col.entries().collect::<Vec<_>>(),
+                original.entries().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "allocates 512MiB"]
+    fn indices_beyond_u32_are_not_truncated() {
+        let large = u32::MAX as usize + 1;
+        let mut col = BitSetColumn::new_with_dimension(1);
+        col.add_entries(vec![3, large - 1, large, large + 40].into_iter());
+        assert_eq!(col.pivot(), Some(large + 40));
+        assert!(col.has_entry(&large));
+        assert!(!col.has_entry(&0));
+        assert_eq!(
+            col.pivots_descending().collect::<Vec<_>>(),
+            vec![large + 40, large, large - 1, 3]
+        );
+        assert_eq!(
+            col.entries().collect::<Vec<_>>(),
+            vec![3, large - 1, large, large + 40]
+        );
+    }
+
+    #[test]
+    fn clone_from_col_reuses_allocation() {
+        let source = BitSetColumn::from((1, BitSet::from_iter(vec![3, 64, 100])));
+        let mut dest = BitSetColumn::with_capacity(0, 1000);
+        let capacity = dest.capacity();
+        dest.clone_from_col(&source);
+        assert_eq!(dest, source);
+        assert_eq!(dest.capacity(), capacity);
+    }
+}
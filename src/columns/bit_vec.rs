@@ -0,0 +1,91 @@
+use bitvec::slice::IterOnes;
+use bitvec::vec::BitVec;
+
+use super::{Column, ColumnMode};
+
+/// A column represented by a [`BitVec`], in which bit `i` is set if and only if index `i` is non-zero.
+///
+/// Column addition is performed word-by-word and the pivot is found by scanning backwards from the last word,
+/// via [`last_one`](bitvec::slice::BitSlice::last_one).
+/// Requires the `bitvec` feature.
+///
+/// To construct call [`BitVecColumn::from`] or use [`BitVecColumn::new_with_dimension`] and [`BitVecColumn::add_entries`]
+#[derive(Debug, Default, Clone)]
+pub struct BitVecColumn {
+    boundary: BitVec,
+    dimension: usize,
+}
+
+impl Column for BitVecColumn {
+    fn pivot(&self) -> Option<usize> {
+        self.boundary.last_one()
+    }
+
+    fn add_col(&mut self, other: &Self) {
+        if self.boundary.len() < other.boundary.len() {
+            self.boundary.resize(other.boundary.len(), false);
+        }
+        self.boundary[..other.boundary.len()] ^= other.boundary.as_bitslice();
+    }
+
+    fn add_entry(&mut self, entry: usize) {
+        if self.boundary.len() <= entry {
+            self.boundary.resize(entry + 1, false);
+        }
+        let current = self.boundary[entry];
+        self.boundary.set(entry, !current);
+    }
+
+    fn has_entry(&self, entry: &usize) -> bool {
+        self.boundary.get(*entry).is_some_and(|bit| *bit)
+    }
+
+    type EntriesIter<'a> = IterOnes<'a, usize, bitvec::order::Lsb0>;
+
+    fn entries<'a>(&'a self) -> Self::EntriesIter<'a> {
+        self.boundary.iter_ones()
+    }
+
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        self.boundary.iter_ones().rev()
+    }
+
+    type EntriesRepr = BitVec;
+
+    fn set_entries(&mut self, entries: Self::EntriesRepr) {
+        self.boundary = entries;
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimension: usize) {
+        self.dimension = dimension;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.boundary.not_any()
+    }
+
+    // No difference for this representation
+    fn set_mode(&mut self, _mode: ColumnMode) {}
+}
+
+// Two columns are equal if they have the same non-zero entries, regardless of the length of the underlying BitVec
+impl PartialEq for BitVecColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimension == other.dimension && self.entries().eq(other.entries())
+    }
+}
+
+impl From<(usize, BitVec)> for BitVecColumn {
+    /// Constructs a `BitVecColumn`, from a tuple where
+    /// `boundary` has bit `i` set if and only if index `i` is non-zero.
+    fn from((dimension, boundary): (usize, BitVec)) -> Self {
+        Self {
+            boundary,
+            dimension,
+        }
+    }
+}
@@ -46,9 +46,13 @@ impl Column for BitSetVecHybridColumn {
     }
 
     fn add_col(&mut self, other: &Self) {
-        // We do this because it is assumes you are adding a Vec into a BitSet
-        // therefore no way to optimise over consuming the iterator
-        self.add_entries(other.entries())
+        match (&mut self.internal, &other.internal) {
+            // Both working, so we can use a single symmetric difference
+            (HybridColumnInternal::BitSet(x), HybridColumnInternal::BitSet(y)) => x.add_col(y),
+            (HybridColumnInternal::Vec(x), HybridColumnInternal::Vec(y)) => x.add_col(y),
+            // Typically adding a Vec into a BitSet, so no way to optimise over consuming the iterator
+            _ => self.add_entries(other.entries()),
+        }
     }
 
     fn add_entry(&mut self, entry: usize) {
@@ -95,6 +99,20 @@ impl Column for BitSetVecHybridColumn {
         }
     }
 
+    // Only the working representation can make use of reserved storage
+    fn reserve(&mut self, column_height: usize) {
+        if let HybridColumnInternal::BitSet(x) = &mut self.internal {
+            x.reserve(column_height);
+        }
+    }
+
+    fn as_vec_column(&self) -> Option<&VecColumn> {
+        match &self.internal {
+            HybridColumnInternal::BitSet(_) => None,
+            HybridColumnInternal::Vec(x) => Some(x),
+        }
+    }
+
     fn set_mode(&mut self, mode: ColumnMode) {
         match (mode, &self.internal) {
             (ColumnMode::Working, HybridColumnInternal::Vec(_)) => {
@@ -107,7 +125,7 @@ impl Column for BitSetVecHybridColumn {
                 vec_column.add_entries(self.entries());
                 self.internal = HybridColumnInternal::Vec(vec_column);
             }
-            _ => return,
+            _ => (),
         }
     }
 }
@@ -119,3 +137,40 @@ impl From<(usize, Vec<usize>)> for BitSetVecHybridColumn {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn working_hybrid(entries: Vec<usize>) -> BitSetVecHybridColumn {
+        let mut col = BitSetVecHybridColumn::from((1, entries));
+        col.set_mode(ColumnMode::Working);
+        col
+    }
+
+    fn sorted_entries<C: Column>(col: &C) -> Vec<usize> {
+        let mut entries: Vec<usize> = col.entries().collect();
+        entries.sort_unstable();
+        entries
+    }
+
+    #[test]
+    fn working_add_col_matches_bit_set() {
+        let a = vec![0, 3, 5, 64, 100];
+        let b = vec![3, 4, 64, 99, 200];
+        let mut hybrid = working_hybrid(a.clone());
+        hybrid.add_col(&working_hybrid(b.clone()));
+        assert!(matches!(hybrid.internal, HybridColumnInternal::BitSet(_)));
+        let mut bit_set = BitSetColumn::new_with_dimension(1);
+        bit_set.add_entries(a.iter().copied());
+        let mut other = BitSetColumn::new_with_dimension(1);
+        other.add_entries(b.iter().copied());
+        bit_set.add_col(&other);
+        assert_eq!(sorted_entries(&hybrid), sorted_entries(&bit_set));
+        assert_eq!(sorted_entries(&hybrid), vec![0, 4, 5, 99, 100, 200]);
+        // Mixed representations still agree
+        let mut mixed = working_hybrid(a);
+        mixed.add_col(&BitSetVecHybridColumn::from((1, b)));
+        assert_eq!(sorted_entries(&mixed), sorted_entries(&bit_set));
+    }
+}
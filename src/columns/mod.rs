@@ -1,12 +1,22 @@
 //! Representations of columns of a Z_2 matrix, complying to a common interface.
 
+use std::cmp::Ordering;
+
+mod auto;
 mod bit_set;
+#[cfg(feature = "bitvec")]
+mod bit_vec;
 mod hybrid;
 mod vec;
+mod wide;
 
+pub use self::auto::{AutoColumn, AutoColumnIter};
 pub use self::bit_set::BitSetColumn;
+#[cfg(feature = "bitvec")]
+pub use bit_vec::BitVecColumn;
 pub use hybrid::BitSetVecHybridColumn;
-pub use vec::VecColumn;
+pub use vec::{Index, VecColumn, VecColumnIter, VecColumnT};
+pub use wide::{WideColumn, WideColumnIter};
 
 /// Enum representing the differnt modes that the column is in, which may impact the optimal representation.
 #[derive(Debug, Clone, Copy)]
@@ -38,6 +48,15 @@ pub trait Column: Sync + Clone + Send + From<(usize, Self::EntriesRepr)> {
         Self: 'a;
     /// Returns the entries of the columns as an iterator over the non-zero indices (not necessarily sorted)
     fn entries<'a>(&'a self) -> Self::EntriesIter<'a>;
+    /// Returns the non-zero indices of the column, from highest to lowest.
+    /// In particular, the first index yielded is the [`pivot`](Self::pivot).
+    /// Provided implementation collects and sorts [`Self::entries`].
+    /// You may wish to provide a more efficient implementation
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        let mut entries: Vec<usize> = self.entries().collect();
+        entries.sort_unstable();
+        entries.into_iter().rev()
+    }
     /// A format that the user can provide the entries of the column in, in order to efficiently construct the column.
     /// The `Default` should correspond to the empty column
     type EntriesRepr: Default;
@@ -52,13 +71,21 @@ pub trait Column: Sync + Clone + Send + From<(usize, Self::EntriesRepr)> {
     /// Only relevant for certain representations.
     fn set_mode(&mut self, mode: ColumnMode);
 
-    /// Returns whether or not the column is a cycle, i.e. has no entries.
+    /// Returns whether or not the column has no entries, e.g. after it has been cleared.
     /// Provided implementation makes call to [`Self::pivot`].
     /// You may wish to provide a more efficient implementation
-    fn is_cycle(&self) -> bool {
+    fn is_empty(&self) -> bool {
         self.pivot().is_none()
     }
 
+    /// Returns whether or not the column is a cycle, i.e. has no entries.
+    /// This is the topological reading of [`Self::is_empty`], for use when interpreting a reduced column;
+    /// prefer [`Self::is_empty`] when checking the state of the data structure.
+    /// Provided implementation makes call to [`Self::is_empty`].
+    fn is_cycle(&self) -> bool {
+        self.is_empty()
+    }
+
     /// Returns whether or not the column is a boundary, i.e. is non-empty.
     /// Provided implementation negates [`Self::is_cycle`]
     fn is_boundary(&self) -> bool {
@@ -77,8 +104,192 @@ pub trait Column: Sync + Clone + Send + From<(usize, Self::EntriesRepr)> {
         Self::from((dimension, Self::EntriesRepr::default()))
     }
 
+    /// Overwrite `self` with a copy of `other`.
+    /// Implementations should re-use the existing allocation of `self` where possible.
+    /// Provided implementation is `*self = other.clone()`.
+    fn clone_from_col(&mut self, other: &Self) {
+        *self = other.clone();
+    }
+
+    /// Hint that all entries of the column will lie in the range `0..column_height`,
+    /// so that the column may allocate sufficient storage up front.
+    /// Provided implementation does nothing.
+    fn reserve(&mut self, _column_height: usize) {}
+
+    /// Returns the column as a [`VecColumn`] if it is already stored as one, so that conversion code can avoid rebuilding it.
+    /// Provided implementation returns `None`.
+    fn as_vec_column(&self) -> Option<&VecColumn> {
+        None
+    }
+
     /// Removes all entries from the column
     fn clear_entries(&mut self) {
         self.set_entries(Self::EntriesRepr::default())
     }
+
+    /// Returns the number of entries in the sum of `self` and `other`, without modifying either column.
+    /// Provided implementation merges the two [`pivots_descending`](Self::pivots_descending) iterators.
+    /// You may wish to provide a more efficient implementation
+    fn sym_diff_len(&self, other: &Self) -> usize {
+        let mut ours = self.pivots_descending().peekable();
+        let mut theirs = other.pivots_descending().peekable();
+        let mut len = 0;
+        loop {
+            match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) => match a.cmp(b) {
+                    Ordering::Greater => {
+                        len += 1;
+                        ours.next();
+                    }
+                    Ordering::Less => {
+                        len += 1;
+                        theirs.next();
+                    }
+                    Ordering::Equal => {
+                        ours.next();
+                        theirs.next();
+                    }
+                },
+                (Some(_), None) => return len + ours.count(),
+                (None, _) => return len + theirs.count(),
+            }
+        }
+    }
+}
+
+/// Read-only access to the columns of a matrix, which need not be stored explicitly.
+///
+/// Implemented for slices of columns, which are simply cloned on access.
+/// Other implementors may compute each column on demand, e.g. [`AntiTransposeView`](crate::utils::AntiTransposeView).
+pub trait ColumnAccessor<C: Column>: Sync {
+    /// The number of columns in the matrix.
+    fn n_cols(&self) -> usize;
+    /// The dimension of column `j`, which should be cheap to compute.
+    fn dimension(&self, j: usize) -> usize;
+    /// Returns an owned copy of column `j`.
+    fn column(&self, j: usize) -> C;
+}
+
+impl<C: Column> ColumnAccessor<C> for [C] {
+    fn n_cols(&self) -> usize {
+        self.len()
+    }
+
+    fn dimension(&self, j: usize) -> usize {
+        self[j].dimension()
+    }
+
+    fn column(&self, j: usize) -> C {
+        self[j].clone()
+    }
+}
+
+/// Constructs a matrix of any [`Column`] implementor from a specification of each column as `(dimension, entries)`.
+/// Useful for running the same matrix through different column representations, e.g. in benchmarks.
+pub fn build_matrix<C: Column>(spec: &[(usize, Vec<usize>)]) -> Vec<C> {
+    spec.iter()
+        .map(|(dimension, entries)| {
+            let mut col = C::new_with_dimension(*dimension);
+            col.add_entries(entries.iter().copied());
+            col
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
+
+    fn check_sym_diff_len<C: Column>(a: &[usize], b: &[usize]) {
+        let a: C = build_matrix(&[(0, a.to_vec())]).pop().unwrap();
+        let b: C = build_matrix(&[(0, b.to_vec())]).pop().unwrap();
+        let mut sum = a.clone();
+        sum.add_col(&b);
+        assert_eq!(a.sym_diff_len(&b), sum.entries().count());
+        assert_eq!(b.sym_diff_len(&a), sum.entries().count());
+    }
+
+    proptest! {
+        #[test]
+        fn sym_diff_len_agrees_with_add_col(
+            a in hash_set(0..200usize, 0..50),
+            b in hash_set(0..200usize, 0..50),
+        ) {
+            let a: Vec<usize> = a.into_iter().collect();
+            let b: Vec<usize> = b.into_iter().collect();
+            check_sym_diff_len::<VecColumn>(&a, &b);
+            check_sym_diff_len::<VecColumnT<u32>>(&a, &b);
+            check_sym_diff_len::<BitSetColumn>(&a, &b);
+            check_sym_diff_len::<BitSetVecHybridColumn>(&a, &b);
+            check_sym_diff_len::<WideColumn>(&a, &b);
+            check_sym_diff_len::<AutoColumn>(&a, &b);
+        }
+    }
+
+    #[test]
+    fn build_matrix_agrees_across_representations() {
+        let spec = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![1, 0]),
+            (1, vec![0, 2]),
+            (1, vec![2, 1]),
+            (2, vec![3, 4, 5]),
+        ];
+        let vec_matrix: Vec<VecColumn> = build_matrix(&spec);
+        let bit_set_matrix: Vec<BitSetColumn> = build_matrix(&spec);
+        let hybrid_matrix: Vec<BitSetVecHybridColumn> = build_matrix(&spec);
+        assert_eq!(vec_matrix[3], VecColumn::from((1, vec![0, 1])));
+        for ((vec_col, bit_set_col), hybrid_col) in vec_matrix
+            .iter()
+            .zip(bit_set_matrix.iter())
+            .zip(hybrid_matrix.iter())
+        {
+            assert_eq!(vec_col.dimension(), bit_set_col.dimension());
+            assert!(vec_col.entries().eq(bit_set_col.entries()));
+            assert_eq!(vec_col.dimension(), hybrid_col.dimension());
+            assert!(vec_col.entries().eq(hybrid_col.entries()));
+        }
+    }
+
+    // is_empty describes the data, is_cycle the chain it represents, but the two always agree
+    fn check_is_empty<C: Column>() {
+        let mut col: C = build_matrix(&[(1, vec![0, 2])]).pop().unwrap();
+        assert!(!col.is_empty() && !col.is_cycle() && col.is_boundary());
+        // A column emptied by clearing
+        col.clear_entries();
+        assert!(col.is_empty() && col.is_cycle());
+        // A column reduced to zero represents a cycle
+        let mut reduced: C = build_matrix(&[(1, vec![0, 2])]).pop().unwrap();
+        reduced.add_col(&build_matrix(&[(1, vec![0, 2])]).pop().unwrap());
+        assert!(reduced.is_empty() && reduced.is_cycle() && !reduced.is_boundary());
+    }
+
+    #[test]
+    fn as_vec_column_borrows_vec_backed_columns() {
+        let vec_col = VecColumn::from((1, vec![0, 2]));
+        assert!(std::ptr::eq(vec_col.as_vec_column().unwrap(), &vec_col));
+        let narrow: VecColumnT<u32> = build_matrix(&[(1, vec![0, 2])]).pop().unwrap();
+        assert!(narrow.as_vec_column().is_none());
+        let mut hybrid: BitSetVecHybridColumn = build_matrix(&[(1, vec![0, 2])]).pop().unwrap();
+        hybrid.set_mode(ColumnMode::Storage);
+        assert_eq!(hybrid.as_vec_column(), Some(&vec_col));
+        hybrid.set_mode(ColumnMode::Working);
+        assert!(hybrid.as_vec_column().is_none());
+        let bit_set: BitSetColumn = build_matrix(&[(1, vec![0, 2])]).pop().unwrap();
+        assert!(bit_set.as_vec_column().is_none());
+    }
+
+    #[test]
+    fn is_empty_agrees_with_is_cycle() {
+        check_is_empty::<VecColumn>();
+        check_is_empty::<VecColumnT<u32>>();
+        check_is_empty::<BitSetColumn>();
+        check_is_empty::<BitSetVecHybridColumn>();
+        check_is_empty::<WideColumn>();
+        check_is_empty::<AutoColumn>();
+    }
 }
@@ -1,25 +1,105 @@
+use std::any::Any;
 use std::cmp::Ordering;
+use std::fmt::Debug;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::{Column, ColumnMode};
 
-/// A column represented by an increasing vector of the non-zero indices.
+/// Unsigned integer types which can be used to store the non-zero indices of a [`VecColumnT`].
 ///
-/// To construct call [`VecColumn::from`] or use [`VecColumn::new_with_dimension`] and [`VecColumn::add_entries`]
+/// Indices are converted to and from `usize` at the boundary of the [`Column`] interface.
+pub trait Index: Copy + Ord + Default + Debug + Send + Sync + 'static {
+    /// Converts `idx` to this type, returning `None` if it does not fit.
+    fn try_from_usize(idx: usize) -> Option<Self>;
+    /// Converts back to a `usize`.
+    fn to_usize(self) -> usize;
+
+    /// As [`Self::try_from_usize`] but panics if `idx` does not fit.
+    fn from_usize(idx: usize) -> Self {
+        Self::try_from_usize(idx).expect("Index should fit in the index type of the column")
+    }
+}
+
+macro_rules! impl_index {
+    ($t:ty) => {
+        impl Index for $t {
+            fn try_from_usize(idx: usize) -> Option<Self> {
+                <$t>::try_from(idx).ok()
+            }
+
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+        }
+    };
+}
+
+impl_index!(usize);
+impl_index!(u64);
+impl_index!(u32);
+impl_index!(u16);
+
+/// A column represented by an increasing vector of the non-zero indices, stored with index type `I`.
+///
+/// For matrices with fewer than `u32::MAX` rows, [`VecColumnT<u32>`] uses half the memory of [`VecColumn`] on 64-bit platforms.
+///
+/// To construct call [`VecColumnT::from`] or use [`VecColumnT::new_with_dimension`] and [`VecColumnT::add_entries`]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Default, Clone, PartialEq)]
-pub struct VecColumn {
-    boundary: Vec<usize>,
+#[derive(Debug, Default, PartialEq)]
+pub struct VecColumnT<I: Index> {
+    boundary: Vec<I>,
     dimension: usize,
 }
 
-impl VecColumn {
+// Implemented by hand so that clone_from re-uses the existing allocation
+impl<I: Index> Clone for VecColumnT<I> {
+    fn clone(&self) -> Self {
+        Self {
+            boundary: self.boundary.clone(),
+            dimension: self.dimension,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.boundary.clone_from(&source.boundary);
+        self.dimension = source.dimension;
+    }
+}
+
+/// A column represented by an increasing vector of the non-zero indices.
+///
+/// To construct call [`VecColumn::from`] or use [`VecColumn::new_with_dimension`] and [`VecColumn::add_entries`]
+pub type VecColumn = VecColumnT<usize>;
+
+impl<I: Index> VecColumnT<I> {
+    /// Constructs a column from entries provided in any order, possibly with repeats.
+    /// Since we work over Z_2, an entry appearing an even number of times cancels and an odd number of times is kept.
+    ///
+    /// Runs in O(n log n) time, rather than the O(n²) of repeatedly calling [`add_entry`](Column::add_entry).
+    pub fn from_iter_xor(dimension: usize, entries: impl IntoIterator<Item = usize>) -> Self {
+        let mut sorted: Vec<I> = entries.into_iter().map(I::from_usize).collect();
+        sorted.sort_unstable();
+        let mut boundary: Vec<I> = Vec::with_capacity(sorted.len());
+        for entry in sorted {
+            // Repeated entries are adjacent, so cancel with the previous entry if equal
+            if boundary.last() == Some(&entry) {
+                boundary.pop();
+            } else {
+                boundary.push(entry);
+            }
+        }
+        Self {
+            boundary,
+            dimension,
+        }
+    }
+
     // Returns the index where we should try to insert next entry
-    fn add_entry_starting_at(&mut self, entry: usize, starting_idx: usize) -> usize {
+    fn add_entry_starting_at(&mut self, entry: I, starting_idx: usize) -> usize {
         let mut working_idx = starting_idx;
-        while let Some(value_at_idx) = self.boundary.iter().nth(working_idx) {
+        while let Some(value_at_idx) = self.boundary.get(working_idx) {
             match value_at_idx.cmp(&entry) {
                 Ordering::Less => {
                     working_idx += 1;
@@ -37,13 +117,28 @@ impl VecColumn {
         }
         // Bigger than all idxs in col - add to end
         self.boundary.push(entry);
-        return self.boundary.len() - 1;
+        self.boundary.len() - 1
+    }
+}
+
+/// The output type of [`VecColumnT::entries`], converting each index to `usize`.
+pub struct VecColumnIter<'a, I: Index>(std::slice::Iter<'a, I>);
+
+impl<'a, I: Index> Iterator for VecColumnIter<'a, I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|idx| idx.to_usize())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
-impl Column for VecColumn {
+impl<I: Index> Column for VecColumnT<I> {
     fn pivot(&self) -> Option<usize> {
-        self.boundary.iter().last().copied()
+        self.boundary.last().map(|idx| idx.to_usize())
     }
 
     fn add_col(&mut self, other: &Self) {
@@ -54,20 +149,28 @@ impl Column for VecColumn {
     }
 
     fn add_entry(&mut self, entry: usize) {
-        self.add_entry_starting_at(entry, 0);
+        self.add_entry_starting_at(I::from_usize(entry), 0);
     }
 
+    // The boundary is kept sorted, so we can binary search
     fn has_entry(&self, entry: &usize) -> bool {
-        self.boundary.contains(entry)
+        match I::try_from_usize(*entry) {
+            Some(entry) => self.boundary.binary_search(&entry).is_ok(),
+            None => false,
+        }
     }
 
-    type EntriesIter<'a> = std::iter::Copied<std::slice::Iter<'a, usize>>;
+    type EntriesIter<'a> = VecColumnIter<'a, I>;
 
     fn entries<'a>(&'a self) -> Self::EntriesIter<'a> {
-        self.boundary.iter().copied()
+        VecColumnIter(self.boundary.iter())
+    }
+
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        self.boundary.iter().rev().map(|idx| idx.to_usize())
     }
 
-    type EntriesRepr = Vec<usize>;
+    type EntriesRepr = Vec<I>;
 
     fn set_entries(&mut self, entries: Self::EntriesRepr) {
         self.boundary = entries;
@@ -81,7 +184,7 @@ impl Column for VecColumn {
         self.dimension = dimension;
     }
 
-    fn is_cycle(&self) -> bool {
+    fn is_empty(&self) -> bool {
         self.boundary.is_empty()
     }
 
@@ -94,15 +197,140 @@ impl Column for VecColumn {
 
     // No difference for this representation
     fn set_mode(&mut self, _mode: ColumnMode) {}
+
+    // Sort the new entries once and merge, rather than inserting each entry individually
+    fn add_entries<B: Iterator<Item = usize>>(&mut self, entries: B) {
+        let other = Self::from_iter_xor(self.dimension, entries);
+        self.add_col(&other);
+    }
+
+    fn clone_from_col(&mut self, other: &Self) {
+        self.clone_from(other);
+    }
+
+    // Single merge walk over the two sorted boundaries
+    fn sym_diff_len(&self, other: &Self) -> usize {
+        let (mut i, mut j, mut len) = (0, 0, 0);
+        while i < self.boundary.len() && j < other.boundary.len() {
+            match self.boundary[i].cmp(&other.boundary[j]) {
+                Ordering::Less => {
+                    len += 1;
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    len += 1;
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        len + (self.boundary.len() - i) + (other.boundary.len() - j)
+    }
+
+    // Only the usize index type is a VecColumn
+    fn as_vec_column(&self) -> Option<&VecColumn> {
+        (self as &dyn Any).downcast_ref()
+    }
 }
 
-impl From<(usize, Vec<usize>)> for VecColumn {
-    /// Constructs a `VecColumn`, from a tuple where
+impl<I: Index> From<(usize, Vec<I>)> for VecColumnT<I> {
+    /// Constructs a `VecColumnT`, from a tuple where
     /// `boundary` is the vector of non-zero indices, sorted in increasing order.
-    fn from((dimension, boundary): (usize, Vec<usize>)) -> Self {
+    fn from((dimension, boundary): (usize, Vec<I>)) -> Self {
         Self {
             boundary,
             dimension,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u32_column_uses_half_memory() {
+        let entries: Vec<usize> = (0..1000).map(|i| 3 * i).collect();
+        let mut col_usize = VecColumn::new_with_dimension(1);
+        col_usize.set_entries(entries.clone());
+        let mut col_u32 = VecColumnT::<u32>::new_with_dimension(1);
+        col_u32.set_entries(entries.iter().map(|&i| u32::from_usize(i)).collect());
+        assert!(col_usize.entries().eq(col_u32.entries()));
+        assert_eq!(col_usize.pivot(), col_u32.pivot());
+        let bytes_usize = std::mem::size_of_val(col_usize.boundary.as_slice());
+        let bytes_u32 = std::mem::size_of_val(col_u32.boundary.as_slice());
+        assert_eq!(2 * bytes_u32, bytes_usize);
+    }
+
+    #[test]
+    fn from_iter_xor_cancels_repeats() {
+        let col = VecColumn::from_iter_xor(1, vec![7, 2, 5, 2, 9, 7, 7, 0, 2, 5, 5, 5]);
+        assert_eq!(col, VecColumn::from((1, vec![0, 2, 7, 9])));
+        let mut expected = VecColumn::new_with_dimension(1);
+        expected.add_entries(vec![7, 2, 5, 2, 9, 7, 7, 0, 2, 5, 5, 5].into_iter());
+        assert_eq!(col, expected);
+        assert!(VecColumn::from_iter_xor(0, vec![3, 3]).is_cycle());
+    }
+
+    #[test]
+    fn add_entries_agrees_with_add_entry() {
+        let entries = vec![9, 2, 5, 2, 11, 0, 7, 5, 5];
+        let mut col = VecColumn::from((1, vec![0, 3, 9]));
+        let mut expected = col.clone();
+        col.add_entries(entries.iter().copied());
+        for &entry in entries.iter() {
+            expected.add_entry(entry);
+        }
+        assert_eq!(col, expected);
+        assert_eq!(col, VecColumn::from((1, vec![3, 5, 7, 11])));
+    }
+
+    #[test]
+    fn clone_from_col_reuses_allocation() {
+        let source = VecColumn::from((2, vec![1, 4, 9]));
+        let mut dest = VecColumn::new_with_dimension(0);
+        dest.set_entries(Vec::with_capacity(100));
+        let capacity = dest.boundary.capacity();
+        let ptr = dest.boundary.as_ptr();
+        dest.clone_from_col(&source);
+        assert_eq!(dest, source);
+        assert_eq!(dest.boundary.capacity(), capacity);
+        assert_eq!(dest.boundary.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn pivots_descending_starts_at_pivot() {
+        let col = VecColumn::from((1, vec![0, 3, 8, 12]));
+        assert_eq!(col.pivots_descending().next(), col.pivot());
+        assert_eq!(
+            col.pivots_descending().collect::<Vec<_>>(),
+            vec![12, 8, 3, 0]
+        );
+        let col = VecColumnT::<u32>::from((1, vec![2, 5]));
+        assert_eq!(col.pivots_descending().collect::<Vec<_>>(), vec![5, 2]);
+        assert_eq!(
+            VecColumn::new_with_dimension(0).pivots_descending().next(),
+            None
+        );
+    }
+
+    #[test]
+    fn u32_column_rejects_large_entries() {
+        let col = VecColumnT::<u32>::from((0, vec![1, 2, 3]));
+        assert!(!col.has_entry(&(u32::MAX as usize + 2)));
+        assert!(col.has_entry(&2));
+    }
+
+    #[test]
+    fn has_entry_agrees_with_linear_scan() {
+        let entries: Vec<usize> = (0..100_000).map(|i| 3 * i + 1).collect();
+        let col = VecColumn::from((1, entries.clone()));
+        for probe in (0..1000).chain(299_000..300_005) {
+            assert_eq!(col.has_entry(&probe), entries.contains(&probe));
+        }
+        assert!(!VecColumn::new_with_dimension(0).has_entry(&0));
+    }
+}
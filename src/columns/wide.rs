@@ -0,0 +1,252 @@
+use rayon::prelude::*;
+
+use super::{Column, ColumnMode};
+
+// Number of bits in each word of the column
+const WORD_BITS: usize = u64::BITS as usize;
+// Columns with at least this many words are added and scanned in parallel
+const PARALLEL_WORDS: usize = 1 << 12;
+// Number of words in each chunk of work, when adding in parallel
+const CHUNK_WORDS: usize = 1 << 10;
+
+/// A column represented by a dense vector of words, in which bit `i` is set if and only if index `i` is non-zero.
+/// Once a column spans at least `2^18` rows, [`add_col`](Column::add_col), [`pivot`](Column::pivot) and [`is_cycle`](Column::is_cycle)
+/// are split into chunks of words and run in parallel using rayon.
+///
+/// This only helps for very wide columns, e.g. those with millions of entries arising from certain dualised matrices.
+/// For typical columns, the parallel overhead outweighs the gain and the dense storage wastes memory,
+/// so you should prefer [`VecColumn`](super::VecColumn) or [`BitSetColumn`](super::BitSetColumn).
+///
+/// To construct call [`WideColumn::from`] or use [`WideColumn::new_with_dimension`] and [`WideColumn::add_entries`]
+#[derive(Debug, Default, Clone)]
+pub struct WideColumn {
+    words: Vec<u64>,
+    dimension: usize,
+}
+
+impl WideColumn {
+    fn is_parallel(&self) -> bool {
+        self.words.len() >= PARALLEL_WORDS
+    }
+
+    // Returns the index of the last non-zero word
+    fn last_non_zero_word(&self) -> Option<usize> {
+        if self.is_parallel() {
+            self.words.par_iter().position_last(|&word| word != 0)
+        } else {
+            self.words.iter().rposition(|&word| word != 0)
+        }
+    }
+}
+
+/// The output type of [`WideColumn::entries`], yielding the non-zero indices in increasing order.
+pub struct WideColumnIter<'a> {
+    words: std::slice::Iter<'a, u64>,
+    offset: usize,
+    current: u64,
+}
+
+impl<'a> Iterator for WideColumnIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.offset += WORD_BITS;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        // Offset is one word ahead, since it is incremented on loading each word
+        Some(self.offset - WORD_BITS + bit)
+    }
+}
+
+impl Column for WideColumn {
+    fn pivot(&self) -> Option<usize> {
+        let idx = self.last_non_zero_word()?;
+        let word = self.words[idx];
+        Some(idx * WORD_BITS + (WORD_BITS - 1) - word.leading_zeros() as usize)
+    }
+
+    fn add_col(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        let words = &mut self.words[..other.words.len()];
+        if other.is_parallel() {
+            words
+                .par_chunks_mut(CHUNK_WORDS)
+                .zip(other.words.par_chunks(CHUNK_WORDS))
+                .for_each(|(chunk, other_chunk)| {
+                    for (word, other_word) in chunk.iter_mut().zip(other_chunk) {
+                        *word ^= other_word;
+                    }
+                });
+        } else {
+            for (word, other_word) in words.iter_mut().zip(other.words.iter()) {
+                *word ^= other_word;
+            }
+        }
+    }
+
+    fn add_entry(&mut self, entry: usize) {
+        let idx = entry / WORD_BITS;
+        if self.words.len() <= idx {
+            self.words.resize(idx + 1, 0);
+        }
+        self.words[idx] ^= 1 << (entry % WORD_BITS);
+    }
+
+    fn has_entry(&self, entry: &usize) -> bool {
+        self.words
+            .get(entry / WORD_BITS)
+            .is_some_and(|word| word & (1 << (entry % WORD_BITS)) != 0)
+    }
+
+    type EntriesIter<'a> = WideColumnIter<'a>;
+
+    fn entries<'a>(&'a self) -> Self::EntriesIter<'a> {
+        WideColumnIter {
+            words: self.words.iter(),
+            offset: 0,
+            current: 0,
+        }
+    }
+
+    // Scans the words from the top, so that the pivot is found without visiting lower words
+    fn pivots_descending(&self) -> impl Iterator<Item = usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(|(idx, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = (WORD_BITS - 1) - word.leading_zeros() as usize;
+                    word ^= 1 << bit;
+                    Some(idx * WORD_BITS + bit)
+                })
+            })
+    }
+
+    type EntriesRepr = Vec<u64>;
+
+    fn set_entries(&mut self, entries: Self::EntriesRepr) {
+        self.words = entries;
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn set_dimension(&mut self, dimension: usize) {
+        self.dimension = dimension;
+    }
+
+    fn is_empty(&self) -> bool {
+        if self.is_parallel() {
+            self.words.par_iter().all(|&word| word == 0)
+        } else {
+            self.words.iter().all(|&word| word == 0)
+        }
+    }
+
+    // No difference for this representation
+    fn set_mode(&mut self, _mode: ColumnMode) {}
+
+    fn reserve(&mut self, column_height: usize) {
+        let n_words = column_height.div_ceil(WORD_BITS);
+        self.words.reserve(n_words.saturating_sub(self.words.len()));
+    }
+
+    fn clone_from_col(&mut self, other: &Self) {
+        self.clone_from(other);
+    }
+}
+
+// Two columns are equal if they have the same non-zero entries, regardless of the number of trailing zero words
+impl PartialEq for WideColumn {
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        self.dimension == other.dimension
+            && longer[..shorter.len()] == shorter[..]
+            && longer[shorter.len()..].iter().all(|&word| word == 0)
+    }
+}
+
+impl From<(usize, Vec<u64>)> for WideColumn {
+    /// Constructs a `WideColumn`, from a tuple where
+    /// `words` has bit `i % 64` of word `i / 64` set if and only if index `i` is non-zero.
+    fn from((dimension, words): (usize, Vec<u64>)) -> Self {
+        Self { words, dimension }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::VecColumn;
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
+
+    fn sorted(entries: impl Iterator<Item = usize>) -> Vec<usize> {
+        let mut entries: Vec<usize> = entries.collect();
+        entries.sort_unstable();
+        entries
+    }
+
+    fn with_entries(dimension: usize, entries: impl Iterator<Item = usize>) -> WideColumn {
+        let mut col = WideColumn::new_with_dimension(dimension);
+        col.add_entries(entries);
+        col
+    }
+
+    proptest! {
+        #[test]
+        fn wide_agrees_with_vec(
+            first in hash_set(0..1000usize, 0..200),
+            second in hash_set(0..1000usize, 0..200),
+        ) {
+            let mut wide = WideColumn::new_with_dimension(1);
+            wide.add_entries(first.iter().copied());
+            let mut vec = VecColumn::new_with_dimension(1);
+            vec.add_entries(first.iter().copied());
+            assert_eq!(sorted(wide.entries()), sorted(vec.entries()));
+            assert_eq!(wide.pivot(), vec.pivot());
+            let mut wide_other = WideColumn::new_with_dimension(1);
+            wide_other.add_entries(second.iter().copied());
+            let mut vec_other = VecColumn::new_with_dimension(1);
+            vec_other.add_entries(second.iter().copied());
+            wide.add_col(&wide_other);
+            vec.add_col(&vec_other);
+            assert_eq!(wide.entries().collect::<Vec<_>>(), sorted(vec.entries()));
+            assert!(wide.pivots_descending().eq(vec.pivots_descending()));
+            assert_eq!(wide.pivot(), vec.pivot());
+            assert_eq!(wide.is_cycle(), vec.is_cycle());
+        }
+    }
+
+    #[test]
+    fn million_entry_columns_add_in_parallel() {
+        let n = 2_000_000;
+        let mut evens = with_entries(1, (0..n).step_by(2));
+        let multiples_of_three = with_entries(1, (0..n).step_by(3));
+        assert!(evens.is_parallel());
+        assert_eq!(evens.pivot(), Some(n - 2));
+        evens.add_col(&multiples_of_three);
+        // Indices divisible by exactly one of 2 and 3
+        let expected = (0..n).filter(|i| (i % 2 == 0) != (i % 3 == 0));
+        assert!(evens.entries().eq(expected));
+        assert_eq!(evens.pivot(), Some(n - 4));
+        evens.add_col(&evens.clone());
+        assert!(evens.is_cycle());
+        assert_eq!(evens, WideColumn::new_with_dimension(1));
+    }
+}
@@ -0,0 +1,136 @@
+//! A unified error type for the fallible APIs of this crate.
+
+use std::fmt;
+
+use crate::algorithms::NoVMatrixError;
+
+/// The errors which may be returned by the fallible methods of this crate,
+/// such as [`try_add_entries`](crate::algorithms::DecompositionAlgo::try_add_entries) and [`try_decompose`](crate::algorithms::DecompositionAlgo::try_decompose).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LophatError {
+    /// Attempted to query a column of V from a decomposition in which V was not maintained.
+    NoVMatrix,
+    /// Referenced column `col` but the matrix only has `n_cols` columns.
+    ColumnOutOfRange { col: usize, n_cols: usize },
+    /// Entry `(row, col)` has a row index outside of the range `0..column_height`.
+    RowOutOfRange {
+        row: usize,
+        col: usize,
+        column_height: usize,
+    },
+    /// The clearing optimisation was requested but the matrix is not square.
+    NonSquareWithClearing { column_height: usize, n_cols: usize },
+    /// The simplex in position `face` appears after its coface, in position `coface`.
+    InvalidFiltrationOrder { face: usize, coface: usize },
+    /// The simplex in position `coface` has a face which is not present in the filtration.
+    MissingFace { coface: usize },
+    /// A serialized decomposition could not be parsed or is malformed.
+    InvalidFileFormat { reason: String },
+    /// The named option was set, but is not supported by the algorithm.
+    UnsupportedOption { option: &'static str },
+}
+
+impl fmt::Display for LophatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LophatError::NoVMatrix => write!(f, "V matrix was not maintained by the decomposition"),
+            LophatError::ColumnOutOfRange { col, n_cols } => write!(
+                f,
+                "Column index {col} is out of range for a matrix with {n_cols} columns"
+            ),
+            LophatError::RowOutOfRange {
+                row,
+                col,
+                column_height,
+            } => write!(
+                f,
+                "Entry (row {row}, column {col}) has row index beyond column_height ({column_height})"
+            ),
+            LophatError::NonSquareWithClearing {
+                column_height,
+                n_cols,
+            } => write!(
+                f,
+                "Cannot employ clearing on a non-square matrix (column_height {column_height}, {n_cols} columns)"
+            ),
+            LophatError::InvalidFiltrationOrder { face, coface } => write!(
+                f,
+                "Face {face} appears after its coface {coface} in the filtration"
+            ),
+            LophatError::MissingFace { coface } => write!(
+                f,
+                "Simplex {coface} has a face which is not present in the filtration"
+            ),
+            LophatError::InvalidFileFormat { reason } => {
+                write!(f, "Invalid decomposition file: {reason}")
+            }
+            LophatError::UnsupportedOption { option } => {
+                write!(f, "Option {option} is not supported by this algorithm")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LophatError {}
+
+impl From<NoVMatrixError> for LophatError {
+    fn from(_: NoVMatrixError) -> Self {
+        LophatError::NoVMatrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_messages() {
+        assert_eq!(
+            LophatError::from(NoVMatrixError).to_string(),
+            "V matrix was not maintained by the decomposition"
+        );
+        assert_eq!(
+            LophatError::ColumnOutOfRange { col: 7, n_cols: 3 }.to_string(),
+            "Column index 7 is out of range for a matrix with 3 columns"
+        );
+        assert_eq!(
+            LophatError::RowOutOfRange {
+                row: 5,
+                col: 2,
+                column_height: 5
+            }
+            .to_string(),
+            "Entry (row 5, column 2) has row index beyond column_height (5)"
+        );
+        assert_eq!(
+            LophatError::NonSquareWithClearing {
+                column_height: 10,
+                n_cols: 4
+            }
+            .to_string(),
+            "Cannot employ clearing on a non-square matrix (column_height 10, 4 columns)"
+        );
+        assert_eq!(
+            LophatError::InvalidFiltrationOrder { face: 3, coface: 1 }.to_string(),
+            "Face 3 appears after its coface 1 in the filtration"
+        );
+        assert_eq!(
+            LophatError::MissingFace { coface: 4 }.to_string(),
+            "Simplex 4 has a face which is not present in the filtration"
+        );
+        assert_eq!(
+            LophatError::InvalidFileFormat {
+                reason: "R has 2 columns but V has 3".to_string()
+            }
+            .to_string(),
+            "Invalid decomposition file: R has 2 columns but V has 3"
+        );
+        assert_eq!(
+            LophatError::UnsupportedOption {
+                option: "accept_pair"
+            }
+            .to_string(),
+            "Option accept_pair is not supported by this algorithm"
+        );
+    }
+}
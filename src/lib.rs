@@ -62,6 +62,7 @@
 
 pub mod algorithms;
 pub mod columns;
+pub mod error;
 pub mod options;
 pub mod utils;
 
@@ -1,13 +1,18 @@
 //! Options for all algorithms.
 //! Soon to be deprecated in favour of an option struct per algorithm.
 
+use std::sync::Arc;
+
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+/// A predicate `accept_pair(birth, death)`, deciding whether a column `death` may be paired with row `birth`, see [`accept_pair`](LoPhatOptions::accept_pair).
+pub type PairFilter = Arc<dyn Fn(usize, usize) -> bool + Send + Sync>;
+
 /// A simple struct for specifying options for R=DV decompositions.
 /// Soon to be deprecated in favour of an option struct per algorithm.
-#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
-#[derive(Copy, Clone)]
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Clone)]
 pub struct LoPhatOptions {
     /// If true, returns full R=DV decomposition, otherwise the resulting decomposition will always return `None` from [`get_v_col`](crate::algorithms::Decomposition::get_v_col).
     pub maintain_v: bool,
@@ -16,30 +21,100 @@ pub struct LoPhatOptions {
     ///   Only relevant for lockfree algorithm.
     pub num_threads: usize,
     ///  An optional hint to the height of the columns.
-    ///   If `None`, assumed to be `matrix.collect().len()`.
+    ///   If `None`, inferred as one more than the largest row index in any column,
+    ///   see [`inferred_column_height`](crate::algorithms::DecompositionAlgo::inferred_column_height).
     ///   All indices must lie in the range `0..column_height`.
+    ///   If set, [`add_entries`](crate::algorithms::DecompositionAlgo::add_entries) panics on any row outside of this range
+    ///   and each column is asked to [`reserve`](crate::columns::Column::reserve) storage for this height.
     ///   Only relevant for lockfree algorithm.
     pub column_height: Option<usize>,
     ///  When splitting work, don't reduce chunks to smaller than this size.
+    ///   Only used with [`SchedulePolicy::Static`].
     ///   Only relevant for lockfree algorithm.
     pub min_chunk_len: usize,
+    ///  How columns are split into chunks of work, when reducing in parallel.
+    ///   Only relevant for lockfree algorithm.
+    pub schedule: SchedulePolicy,
     ///  Whether to employ the clearing optimisation.
     ///   Note, if input matrix is not square then can't use this optimisation since it assumes D*D = 0.
     ///   Only relevant for lockfree algorithm.
     pub clearing: bool,
+    ///  When clearing, only mark columns as cleared rather than eagerly overwriting them with an empty column.
+    ///   The decomposition then synthesises an empty R column whenever a cleared column is read,
+    ///   which saves a parallel write per cleared column at the cost of a bit lookup on every read of R.
+    ///   If maintaining V, the V column of a cleared column must still be written, so the saving is lost.
+    ///   Only relevant for lockfree algorithm.
+    pub lazy_clearing: bool,
+    ///  After reducing each column, additionally eliminate every entry below the pivot which is the pivot of an earlier column.
+    ///   This produces the canonical, exhaustively reduced form of R (used by some definitions of representative cycles).
+    ///   The diagram is unchanged, but reduction is slower.
+    ///   Only relevant for serial algorithm.
+    pub exhaustive: bool,
+    ///  If provided, the worker threads are named `{thread_name_prefix}-{index}`, e.g. to identify them when profiling.
+    ///   Requires the `local_thread_pool` feature.
+    ///   Only relevant for lockfree and locking algorithms.
+    pub thread_name_prefix: Option<String>,
+    ///  If provided, the stack size (in bytes) of each worker thread, otherwise rayon's default is used.
+    ///   Requires the `local_thread_pool` feature.
+    ///   Only relevant for lockfree and locking algorithms.
+    pub stack_size: Option<usize>,
+    ///  Assume that the input matrix is already reduced (e.g. it is R from a prior decomposition), and so skip reduction entirely.
+    ///   The diagram is then read off directly from the pivots of the input.
+    ///   In debug builds, panics if two columns of the input share a pivot.
+    pub assume_reduced: bool,
+    ///  If provided, only reduce columns of dimension at most `max_homology_dim + 1`, since these suffice to compute homology up to `max_homology_dim`.
+    ///   Columns of higher dimension are left unreduced, so features with birth dimension above `max_homology_dim`
+    ///   (including essential classes) are not correctly reported and should be discarded from the diagram.
+    ///   Only relevant for lockfree algorithm.
+    pub max_homology_dim: Option<usize>,
+    ///  When clearing with `maintain_v`, whether to write the V column of each cleared column (a copy of the R column which cleared it).
+    ///   If false, the V column of a cleared column is left empty, saving the copy, so R=DV no longer holds in these columns.
+    ///   Since cleared columns are always deaths, the diagram and the representatives of all features are unaffected.
+    ///   Only relevant for lockfree and locking algorithms.
+    pub v_on_clear: bool,
+    ///  If provided, whenever a working column has more than `max_col_len` entries, all but the largest `max_col_len` entries are dropped.
+    ///   This bounds the fill-in of each column, at the cost of exactness: the computed diagram is only an approximation,
+    ///   in which features may be paired differently or be missing, spurious features may appear, and R=DV no longer holds.
+    ///   Since a truncated column need not be a boundary, this should be combined with [`clearing`](Self::clearing) disabled;
+    ///   otherwise clearing with truncated columns can destroy features in the dimension below.
+    ///   If any column was truncated, [`is_approximate`](crate::algorithms::Decomposition::is_approximate) returns true.
+    ///   Only relevant for lockfree algorithm.
+    pub max_col_len: Option<usize>,
+    ///  If provided, consulted whenever a column `death` is about to be paired with row `birth`, i.e. the column is reduced and no earlier column claims that row.
+    ///   If the predicate returns false, the column continues reducing below `birth`, as if that row were unavailable:
+    ///   each lower entry is either eliminated using the earlier column which claims it, or offered to the predicate in turn.
+    ///   A column all of whose entries are rejected claims no pivot, and so appears in neither the pairings nor the unpaired columns of the diagram.
+    ///   This is an extension hook, e.g. for extended persistence or for skipping pairs that cross a barrier.
+    ///   **Note:** unless the predicate accepts every pair, the resulting diagram is not the standard persistence diagram of the input,
+    ///   and a column may claim a row below its lowest entry, so only methods answered from the decomposition's map from pivots to columns
+    ///   (such as [`diagram`](crate::algorithms::Decomposition::diagram) and [`classify`](crate::algorithms::Decomposition::classify)) reflect the filtered pairing.
+    ///   Not available from Python.
+    ///   Only relevant for serial algorithm; the lockfree and locking algorithms reject it.
+    pub accept_pair: Option<PairFilter>,
 }
 
 #[cfg(feature = "python")]
 #[pymethods]
 impl LoPhatOptions {
     #[new]
-    #[pyo3(signature = (maintain_v=false, num_threads=0, column_height=None, min_chunk_len=1, clearing=true))]
+    // Mirrors the fields of the struct, so that each can be passed as a keyword argument from Python
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (maintain_v=false, num_threads=0, column_height=None, min_chunk_len=1, clearing=true, schedule=SchedulePolicy::Static, lazy_clearing=false, exhaustive=false, thread_name_prefix=None, stack_size=None, assume_reduced=false, max_homology_dim=None, v_on_clear=true, max_col_len=None))]
     fn new(
         maintain_v: bool,
         num_threads: usize,
         column_height: Option<usize>,
         min_chunk_len: usize,
         clearing: bool,
+        schedule: SchedulePolicy,
+        lazy_clearing: bool,
+        exhaustive: bool,
+        thread_name_prefix: Option<String>,
+        stack_size: Option<usize>,
+        assume_reduced: bool,
+        max_homology_dim: Option<usize>,
+        v_on_clear: bool,
+        max_col_len: Option<usize>,
     ) -> Self {
         LoPhatOptions {
             maintain_v,
@@ -47,8 +122,159 @@ impl LoPhatOptions {
             column_height,
             min_chunk_len,
             clearing,
+            schedule,
+            lazy_clearing,
+            exhaustive,
+            thread_name_prefix,
+            stack_size,
+            assume_reduced,
+            max_homology_dim,
+            v_on_clear,
+            max_col_len,
+            accept_pair: None,
         }
     }
+
+    // Every field except accept_pair, which cannot be converted to or from Python, is exposed as a property
+    #[getter]
+    fn get_maintain_v(&self) -> bool {
+        self.maintain_v
+    }
+
+    #[setter]
+    fn set_maintain_v(&mut self, maintain_v: bool) {
+        self.maintain_v = maintain_v;
+    }
+
+    #[getter]
+    fn get_num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    #[setter]
+    fn set_num_threads(&mut self, num_threads: usize) {
+        self.num_threads = num_threads;
+    }
+
+    #[getter]
+    fn get_column_height(&self) -> Option<usize> {
+        self.column_height
+    }
+
+    #[setter]
+    fn set_column_height(&mut self, column_height: Option<usize>) {
+        self.column_height = column_height;
+    }
+
+    #[getter]
+    fn get_min_chunk_len(&self) -> usize {
+        self.min_chunk_len
+    }
+
+    #[setter]
+    fn set_min_chunk_len(&mut self, min_chunk_len: usize) {
+        self.min_chunk_len = min_chunk_len;
+    }
+
+    #[getter]
+    fn get_schedule(&self) -> SchedulePolicy {
+        self.schedule
+    }
+
+    #[setter]
+    fn set_schedule(&mut self, schedule: SchedulePolicy) {
+        self.schedule = schedule;
+    }
+
+    #[getter]
+    fn get_clearing(&self) -> bool {
+        self.clearing
+    }
+
+    #[setter]
+    fn set_clearing(&mut self, clearing: bool) {
+        self.clearing = clearing;
+    }
+
+    #[getter]
+    fn get_lazy_clearing(&self) -> bool {
+        self.lazy_clearing
+    }
+
+    #[setter]
+    fn set_lazy_clearing(&mut self, lazy_clearing: bool) {
+        self.lazy_clearing = lazy_clearing;
+    }
+
+    #[getter]
+    fn get_exhaustive(&self) -> bool {
+        self.exhaustive
+    }
+
+    #[setter]
+    fn set_exhaustive(&mut self, exhaustive: bool) {
+        self.exhaustive = exhaustive;
+    }
+
+    #[getter]
+    fn get_thread_name_prefix(&self) -> Option<String> {
+        self.thread_name_prefix.clone()
+    }
+
+    #[setter]
+    fn set_thread_name_prefix(&mut self, thread_name_prefix: Option<String>) {
+        self.thread_name_prefix = thread_name_prefix;
+    }
+
+    #[getter]
+    fn get_stack_size(&self) -> Option<usize> {
+        self.stack_size
+    }
+
+    #[setter]
+    fn set_stack_size(&mut self, stack_size: Option<usize>) {
+        self.stack_size = stack_size;
+    }
+
+    #[getter]
+    fn get_assume_reduced(&self) -> bool {
+        self.assume_reduced
+    }
+
+    #[setter]
+    fn set_assume_reduced(&mut self, assume_reduced: bool) {
+        self.assume_reduced = assume_reduced;
+    }
+
+    #[getter]
+    fn get_max_homology_dim(&self) -> Option<usize> {
+        self.max_homology_dim
+    }
+
+    #[setter]
+    fn set_max_homology_dim(&mut self, max_homology_dim: Option<usize>) {
+        self.max_homology_dim = max_homology_dim;
+    }
+
+    #[getter]
+    fn get_v_on_clear(&self) -> bool {
+        self.v_on_clear
+    }
+
+    #[setter]
+    fn set_v_on_clear(&mut self, v_on_clear: bool) {
+        self.v_on_clear = v_on_clear;
+    }
+
+    #[getter]
+    fn get_max_col_len(&self) -> Option<usize> {
+        self.max_col_len
+    }
+
+    #[setter]
+    fn set_max_col_len(&mut self, max_col_len: Option<usize>) {
+        self.max_col_len = max_col_len;
+    }
 }
 
 impl Default for LoPhatOptions {
@@ -59,6 +285,30 @@ impl Default for LoPhatOptions {
             column_height: None,
             min_chunk_len: 1,
             clearing: true,
+            schedule: SchedulePolicy::default(),
+            lazy_clearing: false,
+            exhaustive: false,
+            thread_name_prefix: None,
+            stack_size: None,
+            assume_reduced: false,
+            max_homology_dim: None,
+            v_on_clear: true,
+            max_col_len: None,
+            accept_pair: None,
         }
     }
 }
+
+/// The policy used to split the columns of a given dimension into chunks of work, to be reduced in parallel.
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SchedulePolicy {
+    /// Columns are split into chunks of at least [`min_chunk_len`](LoPhatOptions::min_chunk_len).
+    #[default]
+    Static,
+    /// Chunks are split adaptively by rayon, becoming smaller as threads steal work from each other.
+    Guided,
+    /// Every column is a separate piece of work, which may be stolen by any thread.
+    /// Best suited to matrices in which a few columns dominate the work.
+    Dynamic,
+}
@@ -1,9 +1,56 @@
-use crate::columns::Column;
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::columns::{Column, ColumnAccessor};
 
 /// Anti-transposes the input matrix (e.g. to compute cohomology).
 /// * `matrix` - a reference to a collected matrix (vector of columns).
+///
+/// Assumes that input matrix is square.
+pub fn anti_transpose<C: Column>(matrix: &[C]) -> Vec<C> {
+    anti_transpose_dims(matrix, 0..usize::MAX)
+}
+
+/// Transposes the input matrix, e.g. to convert a coboundary matrix into a boundary matrix, or vice versa.
+/// * `matrix` - a reference to a collected matrix (vector of columns).
+///
+/// Unlike [`anti_transpose`], the order of the columns is preserved, so the output is not suitable for computing cohomology.
+/// Column `i` of the output has the same dimension as column `i` of the input, i.e. dimensions label the simplex indexing the column.
+///
+/// The rows of each output column are collected before the column is built, requiring temporary storage of one index per non-zero entry.
+///
+/// Assumes that input matrix is square.
+pub fn transpose<C: Column>(matrix: &[C]) -> Vec<C> {
+    // Rows are pushed in increasing order, so each output column is built from sorted entries
+    let mut rows: Vec<Vec<usize>> = vec![vec![]; matrix.len()];
+    for (j, col) in matrix.iter().enumerate() {
+        for i in col.entries() {
+            rows[i].push(j);
+        }
+    }
+    matrix
+        .iter()
+        .zip(rows)
+        .map(|(col, rows)| {
+            let mut return_col = C::new_with_dimension(col.dimension());
+            return_col.add_entries(rows.into_iter());
+            return_col
+        })
+        .collect()
+}
+
+/// Anti-transposes the input matrix, as in [`anti_transpose`], but only populates those output columns whose dimension lies in `keep`.
+/// The remaining columns are left empty, so that reducing them is trivial.
+/// * `matrix` - a reference to a collected matrix (vector of columns).
+/// * `keep` - the range of dimensions to populate, measured *after* anti-transposing (i.e. `max_dim - dimension`).
+///
+/// After decomposing the output, a pairing `(birth, death)` is correct whenever the dimension of `death` lies in `keep`.
+/// However, an unpaired column of dimension `p` is only guaranteed to be unpaired in the full computation if both `p` and `p + 1` lie in `keep`.
+/// Hence, to compute cohomology in a single (post-transpose) dimension `p`, you should use `keep = p..(p + 2)`.
+///
 /// Assumes that input matrix is square.
-pub fn anti_transpose<C: Column>(matrix: &Vec<C>) -> Vec<C> {
+pub fn anti_transpose_dims<C: Column>(matrix: &[C], keep: Range<usize>) -> Vec<C> {
     let matrix_width = matrix.len();
     let max_dim = matrix.iter().map(|col| col.dimension()).max().unwrap_or(0);
     let mut return_matrix: Vec<_> = matrix
@@ -13,16 +60,147 @@ pub fn anti_transpose<C: Column>(matrix: &Vec<C>) -> Vec<C> {
         .collect();
     for (j, col) in matrix.iter().enumerate() {
         for i in col.entries() {
-            return_matrix[matrix_width - 1 - i].add_entry(matrix_width - 1 - j);
+            let return_col = &mut return_matrix[matrix_width - 1 - i];
+            if keep.contains(&return_col.dimension()) {
+                return_col.add_entry(matrix_width - 1 - j);
+            }
         }
     }
     return_matrix
 }
 
+/// Anti-transposes the input matrix in parallel, producing the same output as [`anti_transpose`].
+/// * `matrix` - a reference to a collected matrix (vector of columns).
+///
+/// The non-zero entries of the output are collected and sorted by column in parallel, after which each output column is built in parallel.
+/// This requires temporary storage of two indices per non-zero entry.
+///
+/// Assumes that input matrix is square.
+pub fn anti_transpose_parallel<C: Column>(matrix: &[C]) -> Vec<C> {
+    let matrix_width = matrix.len();
+    let max_dim = matrix
+        .par_iter()
+        .map(|col| col.dimension())
+        .max()
+        .unwrap_or(0);
+    // Each entry (i, j) of the input becomes the entry (n - 1 - j) of output column (n - 1 - i)
+    let mut entries: Vec<(usize, usize)> = matrix
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(j, col)| {
+            col.entries()
+                .map(move |i| (matrix_width - 1 - i, matrix_width - 1 - j))
+        })
+        .collect();
+    entries.par_sort_unstable();
+    (0..matrix_width)
+        .into_par_iter()
+        .map(|out_idx| {
+            let mut col =
+                C::new_with_dimension(max_dim - matrix[matrix_width - 1 - out_idx].dimension());
+            let start = entries.partition_point(|&(out_col, _)| out_col < out_idx);
+            let end = entries.partition_point(|&(out_col, _)| out_col <= out_idx);
+            col.add_entries(entries[start..end].iter().map(|&(_, row)| row));
+            col
+        })
+        .collect()
+}
+
+/// A view of the anti-transpose of a matrix, as computed by [`anti_transpose`], in which the columns are never stored as `C`.
+/// Instead, each column is built on demand from a compact index of the cofaces of every column, computed once by [`new`](Self::new).
+///
+/// The index stores one index per non-zero entry of the matrix, plus one offset per column, in two contiguous arrays.
+/// Building a column then takes time linear in its number of entries, so building every column is linear in the number of non-zero entries, as for [`anti_transpose`].
+/// Pass to [`LockFreeAlgorithm::decompose_accessor`](crate::algorithms::LockFreeAlgorithm::decompose_accessor) so that,
+/// with clearing, columns which are cleared are never built at all.
+/// Note that every other column is still built into R when its dimension is reduced, so the saving over materialising the anti-transpose
+/// is the cleared columns, together with the per-column overhead of `C`.
+///
+/// Assumes that input matrix is square.
+pub struct AntiTransposeView<'a, C: Column> {
+    matrix: &'a [C],
+    max_dim: usize,
+    // The cofaces of column `i` of the original matrix are `cofaces[offsets[i]..offsets[i + 1]]`, in increasing order
+    offsets: Vec<usize>,
+    cofaces: Vec<usize>,
+}
+
+impl<'a, C: Column> AntiTransposeView<'a, C> {
+    /// Constructs a view of the anti-transpose of `matrix`, indexing the cofaces of every column in two passes over the matrix.
+    pub fn new(matrix: &'a [C]) -> Self {
+        let max_dim = matrix.iter().map(|col| col.dimension()).max().unwrap_or(0);
+        // Count the cofaces of each column, then take prefix sums
+        let mut offsets = vec![0; matrix.len() + 1];
+        for col in matrix {
+            for face in col.entries() {
+                offsets[face + 1] += 1;
+            }
+        }
+        for i in 0..matrix.len() {
+            offsets[i + 1] += offsets[i];
+        }
+        // Cofaces are visited in increasing order, so each range is filled in increasing order
+        let mut next = offsets.clone();
+        let mut cofaces = vec![0; offsets[matrix.len()]];
+        for (coface, col) in matrix.iter().enumerate() {
+            for face in col.entries() {
+                cofaces[next[face]] = coface;
+                next[face] += 1;
+            }
+        }
+        Self {
+            matrix,
+            max_dim,
+            offsets,
+            cofaces,
+        }
+    }
+}
+
+impl<'a, C: Column> ColumnAccessor<C> for AntiTransposeView<'a, C> {
+    fn n_cols(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn dimension(&self, j: usize) -> usize {
+        let original = self.matrix.len() - 1 - j;
+        self.max_dim - self.matrix[original].dimension()
+    }
+
+    // Column j of the anti-transpose is the coboundary of the original column `n - 1 - j`
+    fn column(&self, j: usize) -> C {
+        let width = self.matrix.len();
+        let original = width - 1 - j;
+        let mut col = C::new_with_dimension(self.max_dim - self.matrix[original].dimension());
+        let cofaces = &self.cofaces[self.offsets[original]..self.offsets[original + 1]];
+        // Reversing the cofaces yields the rows in increasing order
+        col.add_entries(cofaces.iter().rev().map(|coface| width - 1 - coface));
+        col
+    }
+}
+
+/// Re-indexes representatives, assuming that they were produced from an anti-transposed matrix (e.g. cocycles read from V).
+/// Each index `i` is sent to `matrix_size - 1 - i` and then each representative is sorted.
+/// Requires `matrix_size` - the size of the decomposed matrix, assumed to be square.
+///
+/// Complements [`PersistenceDiagram::anti_transpose`](crate::utils::PersistenceDiagram::anti_transpose).
+pub fn map_cohomology_reps(reps: Vec<Vec<usize>>, matrix_size: usize) -> Vec<Vec<usize>> {
+    reps.into_iter()
+        .map(|rep| {
+            let mut new_rep: Vec<usize> = rep.into_iter().map(|i| matrix_size - 1 - i).collect();
+            new_rep.sort_unstable();
+            new_rep
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm, SerialAlgorithm};
     use crate::columns::VecColumn;
+    use crate::options::LoPhatOptions;
+    use crate::utils::PersistenceDiagram;
 
     fn build_sphere_triangulation() -> Vec<VecColumn> {
         vec![
@@ -74,10 +252,146 @@ mod tests {
         let matrix_at = build_sphere_triangulation_at();
         let at: Vec<VecColumn> = anti_transpose(&matrix);
         assert_eq!(at, matrix_at);
+        assert_eq!(anti_transpose_parallel(&matrix), matrix_at);
+    }
+
+    #[test]
+    fn coboundary_agrees_with_boundary() {
+        let matrix = build_sphere_triangulation();
+        let coboundary: Vec<VecColumn> = transpose(&matrix);
+        // The cofaces of vertex 0 are the edges 4, 5 and 7
+        assert_eq!(coboundary[0], VecColumn::from((0, vec![4, 5, 7])));
+        assert_eq!(transpose(&coboundary), matrix);
+        let expected = SerialAlgorithm::init(None)
+            .add_cols(matrix.iter().cloned())
+            .decompose()
+            .diagram();
+        let serial = SerialAlgorithm::init(None).decompose_from_coboundary(&coboundary);
+        assert_eq!(serial.diagram(), expected);
+        let lockfree = LockFreeAlgorithm::init(None).decompose_from_coboundary(&coboundary);
+        assert_eq!(lockfree.diagram(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "Should call decompose_from_coboundary before pushing any columns")]
+    fn coboundary_after_pushing_panics() {
+        let matrix = build_sphere_triangulation();
+        let coboundary: Vec<VecColumn> = transpose(&matrix);
+        SerialAlgorithm::init(None)
+            .add_cols(matrix[..1].iter().cloned())
+            .decompose_from_coboundary(&coboundary);
+    }
+
+    #[test]
+    fn view_columns_match_anti_transpose() {
+        let matrix = build_sphere_triangulation();
+        let view = AntiTransposeView::new(&matrix);
+        let at = build_sphere_triangulation_at();
+        assert_eq!(view.n_cols(), at.len());
+        for (j, col) in at.iter().enumerate() {
+            assert_eq!(view.dimension(j), col.dimension());
+            assert_eq!(&view.column(j), col);
+        }
     }
+
+    #[test]
+    fn view_accepts_ungraded_matrix() {
+        // The edge 2 has a face of dimension 1
+        let matrix: Vec<VecColumn> = vec![(0, vec![]), (1, vec![]), (1, vec![1])]
+            .into_iter()
+            .map(VecColumn::from)
+            .collect();
+        let view = AntiTransposeView::new(&matrix);
+        for (j, col) in anti_transpose(&matrix).iter().enumerate() {
+            assert_eq!(&view.column(j), col);
+        }
+    }
+
+    #[test]
+    fn cohomology_reps_map_with_diagram() {
+        let matrix = build_sphere_triangulation();
+        let size = matrix.len();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = SerialAlgorithm::init(Some(options))
+            .add_cols(anti_transpose(&matrix).into_iter())
+            .decompose();
+        let at_diagram = decomp.diagram();
+        let mut at_pairs: Vec<_> = at_diagram.paired.iter().copied().collect();
+        at_pairs.sort();
+        let at_reps: Vec<Vec<usize>> = at_pairs
+            .iter()
+            .map(|&(_, death)| decomp.get_v_col(death).unwrap().entries().collect())
+            .collect();
+        let reps = map_cohomology_reps(at_reps, size);
+        let diagram = at_diagram.anti_transpose(size);
+        for (&(at_birth, at_death), rep) in at_pairs.iter().zip(reps.iter()) {
+            // The cocycle is born at the (re-indexed) death of the anti-transposed pair
+            let pair = (size - 1 - at_death, size - 1 - at_birth);
+            assert!(diagram.paired.contains(&pair));
+            assert!(rep.contains(&pair.0));
+            assert!(rep.windows(2).all(|w| w[0] < w[1]));
+            assert!(rep
+                .iter()
+                .all(|&i| matrix[i].dimension() == matrix[pair.0].dimension()));
+        }
+    }
+
+    #[test]
+    fn restricted_cohomology_agrees_for_vertices() {
+        let matrix = build_sphere_triangulation();
+        let size = matrix.len();
+        let compute_diagram = |at: Vec<VecColumn>| {
+            SerialAlgorithm::init(None)
+                .add_cols(at.into_iter())
+                .decompose()
+                .diagram()
+                .anti_transpose(size)
+        };
+        let full = compute_diagram(anti_transpose(&matrix));
+        // Vertices have post-transpose dimension 2
+        let restricted_at = anti_transpose_dims(&matrix, 2..4);
+        // Columns corresponding to edges are not populated
+        assert!(restricted_at[4..10].iter().all(|col| col.is_cycle()));
+        let restricted = compute_diagram(restricted_at);
+        let vertex_features = |diagram: &PersistenceDiagram| {
+            let mut paired: Vec<_> = diagram
+                .paired
+                .iter()
+                .copied()
+                .filter(|&(birth, _)| matrix[birth].dimension() == 0)
+                .collect();
+            let mut unpaired: Vec<_> = diagram
+                .unpaired
+                .iter()
+                .copied()
+                .filter(|&idx| matrix[idx].dimension() == 0)
+                .collect();
+            paired.sort();
+            unpaired.sort();
+            (paired, unpaired)
+        };
+        assert_eq!(vertex_features(&full), vertex_features(&restricted));
+        assert_eq!(
+            vertex_features(&full),
+            (vec![(1, 4), (2, 5), (3, 7)], vec![0])
+        );
+    }
+
     use proptest::collection::hash_set;
     use proptest::prelude::*;
 
+    proptest! {
+        #[test]
+        fn parallel_agrees_with_serial( matrix in sut_matrix(100) ) {
+            let serial: Vec<VecColumn> = anti_transpose(&matrix);
+            let parallel: Vec<VecColumn> = anti_transpose_parallel(&matrix);
+            assert_eq!(serial, parallel);
+        }
+    }
+
     proptest! {
         #[test]
         fn at_at_is_identity( matrix in sut_matrix(100) ) {
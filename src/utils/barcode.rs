@@ -0,0 +1,208 @@
+use std::io::{self, BufRead, Write};
+
+use crate::utils::PersistenceDiagram;
+
+/// A single bar of a [`Barcode`], i.e. a feature in terms of filtration values rather than column indices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    /// The dimension of the feature.
+    pub dimension: usize,
+    /// The filtration value at which the feature is born.
+    pub birth: f64,
+    /// The filtration value at which the feature dies, or `f64::INFINITY` for an essential feature.
+    pub death: f64,
+}
+
+impl Bar {
+    /// Returns whether the feature never dies.
+    pub fn is_essential(&self) -> bool {
+        self.death == f64::INFINITY
+    }
+}
+
+/// A persistence barcode, i.e. a collection of bars, each with a dimension and real-valued birth and death.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Barcode {
+    /// The bars, in no particular order.
+    pub bars: Vec<Bar>,
+}
+
+impl Barcode {
+    /// Converts a diagram of column indices into a barcode.
+    /// * `diagram` - the pairings, e.g. from [`diagram`](crate::algorithms::Decomposition::diagram).
+    /// * `dims` - the dimension of each column of the decomposed matrix.
+    /// * `values` - the filtration value of each column of the decomposed matrix, e.g. from [`order_filtration`](crate::utils::order_filtration).
+    ///
+    /// Bars of length zero, arising from simplices with equal filtration values, are kept.
+    pub fn from_diagram(diagram: &PersistenceDiagram, dims: &[usize], values: &[f64]) -> Self {
+        let paired = diagram.paired.iter().map(|&(birth, death)| Bar {
+            dimension: dims[birth],
+            birth: values[birth],
+            death: values[death],
+        });
+        let unpaired = diagram.unpaired.iter().map(|&birth| Bar {
+            dimension: dims[birth],
+            birth: values[birth],
+            death: f64::INFINITY,
+        });
+        Self {
+            bars: paired.chain(unpaired).collect(),
+        }
+    }
+
+    /// Sorts the bars by dimension, then birth and then death.
+    pub fn sort(&mut self) {
+        self.bars.sort_by(|a, b| {
+            a.dimension
+                .cmp(&b.dimension)
+                .then(a.birth.total_cmp(&b.birth))
+                .then(a.death.total_cmp(&b.death))
+        });
+    }
+}
+
+/// Writes the barcode in the plain text format used by gudhi, with one bar per line as `dimension birth death`.
+/// Essential bars are written with death `inf`.
+/// Bars are sorted by dimension and then birth.
+pub fn write_barcode_gudhi<W: Write>(barcode: &Barcode, w: &mut W) -> io::Result<()> {
+    let mut sorted = barcode.clone();
+    sorted.sort();
+    for bar in sorted.bars {
+        writeln!(w, "{} {} {}", bar.dimension, bar.birth, bar.death)?;
+    }
+    Ok(())
+}
+
+/// Reads a barcode in the plain text format written by [`write_barcode_gudhi`].
+/// Also reads the output of gudhi's `write_persistence_diagram`, in which each line is prefixed by the characteristic of the coefficient field, which is ignored.
+/// Blank lines and lines starting with `#` are ignored.
+/// Returns an error with kind [`InvalidData`](io::ErrorKind::InvalidData) if some line is malformed.
+pub fn read_barcode_gudhi<R: BufRead>(reader: R) -> io::Result<Barcode> {
+    let mut bars = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        bars.push(parse_bar(line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Could not parse bar from line: {line}"),
+            )
+        })?);
+    }
+    Ok(Barcode { bars })
+}
+
+// Parses a line of the form `[field] dimension birth death`
+fn parse_bar(line: &str) -> Option<Bar> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let (dimension, birth, death) = match tokens.as_slice() {
+        [dimension, birth, death] | [_, dimension, birth, death] => (
+            dimension.parse().ok()?,
+            birth.parse().ok()?,
+            death.parse().ok()?,
+        ),
+        _ => return None,
+    };
+    Some(Bar {
+        dimension,
+        birth,
+        death,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+    use crate::columns::Column;
+    use crate::utils::order_filtration;
+
+    // The barcode of a filled triangle, with edges appearing at 1, 2 and 3 and the triangle at 4.
+    // As written by write_barcode_gudhi, sorted by dimension and then birth.
+    const TRIANGLE_BARCODE: &str = "0 0 1\n0 0 2\n0 0 inf\n1 3 4\n";
+
+    // The same barcode, as written by gudhi's write_persistence_diagram over Z_2.
+    // Regenerated and checked against gudhi by tests/test_gudhi_fixture.py.
+    const TRIANGLE_GUDHI_FIXTURE: &str = include_str!("../../tests/fixtures/triangle_gudhi.pers");
+
+    fn triangle_barcode() -> Barcode {
+        let simplices = vec![
+            (0.0, vec![0]),
+            (0.0, vec![1]),
+            (0.0, vec![2]),
+            (1.0, vec![0, 1]),
+            (2.0, vec![1, 2]),
+            (3.0, vec![0, 2]),
+            (4.0, vec![0, 1, 2]),
+        ];
+        let (matrix, values) = order_filtration(simplices);
+        let dims: Vec<usize> = matrix.iter().map(|col| col.dimension()).collect();
+        let diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        Barcode::from_diagram(&diagram, &dims, &values)
+    }
+
+    #[test]
+    fn writes_sorted_bars() {
+        let mut bytes = vec![];
+        write_barcode_gudhi(&triangle_barcode(), &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), TRIANGLE_BARCODE);
+    }
+
+    #[test]
+    fn matches_gudhi_fixture() {
+        let mut read = read_barcode_gudhi(TRIANGLE_GUDHI_FIXTURE.as_bytes()).unwrap();
+        let mut expected = triangle_barcode();
+        read.sort();
+        expected.sort();
+        assert_eq!(read, expected);
+        assert_eq!(read.bars.iter().filter(|bar| bar.is_essential()).count(), 1);
+        // Our own output reads back to the same barcode
+        let mut written = read_barcode_gudhi(TRIANGLE_BARCODE.as_bytes()).unwrap();
+        written.sort();
+        assert_eq!(written, read);
+    }
+
+    #[test]
+    fn round_trip() {
+        let barcode = Barcode {
+            bars: vec![
+                Bar {
+                    dimension: 2,
+                    birth: 0.1,
+                    death: f64::INFINITY,
+                },
+                Bar {
+                    dimension: 0,
+                    birth: -1.5,
+                    death: 1e-7,
+                },
+                Bar {
+                    dimension: 0,
+                    birth: -2.0,
+                    death: 3.25,
+                },
+            ],
+        };
+        let mut bytes = vec![];
+        write_barcode_gudhi(&barcode, &mut bytes).unwrap();
+        let read = read_barcode_gudhi(bytes.as_slice()).unwrap();
+        let mut expected = barcode.clone();
+        expected.sort();
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        let input = "# comment\n\n0 1 2\n0 1\n";
+        let err = read_barcode_gudhi(input.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(read_barcode_gudhi("2 0 1 2 3\n".as_bytes()).is_err());
+        assert!(read_barcode_gudhi("2 0 one 2\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::columns::{Column, ColumnMode};
+
+/// Checks whether the provided square matrix D satisfies D² = 0 (over Z_2), as is required of any boundary matrix.
+///
+/// If the check fails, returns `Err((i, j))` where `(i, j)` is the first non-zero entry of D², ordered first by column `j` and then by row `i`.
+///
+/// This check takes O(n²) time in the worst case and so is never run automatically; call it explicitly when debugging your input.
+///
+/// Panics if some column of D has an entry with index greater than or equal to the number of columns.
+pub fn is_boundary_matrix<C: Column>(matrix: &[C]) -> Result<(), (usize, usize)> {
+    for (j, col) in matrix.iter().enumerate() {
+        // Sum up the boundaries of the faces of column j
+        let mut composite = C::new_with_dimension(col.dimension().saturating_sub(2));
+        composite.set_mode(ColumnMode::Working);
+        for face in col.entries() {
+            let face_col = matrix
+                .get(face)
+                .expect("Matrix should be square, with entries indexing into its columns");
+            composite.add_col(face_col);
+        }
+        if let Some(i) = composite.entries().min() {
+            return Err((i, j));
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether the provided matrix is strictly upper triangular, i.e. every entry of column `j` has row index less than `j`.
+/// This holds for the boundary matrix of any valid filtration, in which faces precede their cofaces.
+///
+/// Returns `None` if the check passes, and otherwise the first entry `(i, j)` with `i >= j`, ordered first by column `j` and then by row `i`.
+///
+/// This check takes time linear in the number of entries.
+pub fn is_upper_triangular<C: Column>(matrix: &[C]) -> Option<(usize, usize)> {
+    matrix
+        .iter()
+        .enumerate()
+        .find_map(|(j, col)| col.entries().filter(|&i| i >= j).min().map(|i| (i, j)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::BitSetColumn;
+    use crate::utils::fixtures::build_sphere_triangulation;
+
+    #[test]
+    fn sphere_is_boundary_matrix() {
+        let matrix = build_sphere_triangulation();
+        assert_eq!(is_boundary_matrix(&matrix), Ok(()));
+        let bit_set_matrix: Vec<BitSetColumn> = matrix
+            .iter()
+            .map(|col| {
+                let mut new_col = BitSetColumn::new_with_dimension(col.dimension());
+                new_col.add_entries(col.entries());
+                new_col
+            })
+            .collect();
+        assert_eq!(is_boundary_matrix(&bit_set_matrix), Ok(()));
+    }
+
+    #[test]
+    fn broken_face_is_reported() {
+        let mut matrix = build_sphere_triangulation();
+        // Replace the face 8 = [1, 3] of triangle 10 = [0, 1, 3] with 9 = [2, 3]
+        matrix[10] = (2, vec![4, 7, 9]).into();
+        // Boundary of 10 is now [0, 1] + [0, 3] + [2, 3] = [1, 2] which has boundary 1 + 2
+        assert_eq!(is_boundary_matrix(&matrix), Err((1, 10)));
+    }
+
+    #[test]
+    fn sphere_is_upper_triangular() {
+        assert_eq!(is_upper_triangular(&build_sphere_triangulation()), None);
+    }
+
+    #[test]
+    fn below_diagonal_entry_is_reported() {
+        let mut matrix = build_sphere_triangulation();
+        // Edge 5 = [0, 2] now has a face appearing after it
+        matrix[5] = (1, vec![0, 6]).into();
+        matrix[7] = (1, vec![7, 9]).into();
+        assert_eq!(is_upper_triangular(&matrix), Some((6, 5)));
+    }
+}
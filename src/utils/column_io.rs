@@ -0,0 +1,168 @@
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::{algorithms::Decomposition, columns::Column};
+
+// Each column is written as `dimension`, `n_entries` and then `n_entries` indices.
+// All values are written as little-endian u64.
+
+pub(crate) fn write_u64<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+// Returns Ok(None) if the reader is already at EOF
+pub(crate) fn read_u64<R: Read>(reader: &mut R) -> io::Result<Option<usize>> {
+    let mut buf = [0u8; 8];
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    usize::try_from(u64::from_le_bytes(buf))
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes a single column in the length-prefixed column format, read by [`ColumnReader`].
+pub fn write_column<C: Column, W: Write>(column: &C, writer: &mut W) -> io::Result<()> {
+    let entries: Vec<usize> = column.entries().collect();
+    write_u64(writer, column.dimension())?;
+    write_u64(writer, entries.len())?;
+    for entry in entries {
+        write_u64(writer, entry)?;
+    }
+    Ok(())
+}
+
+/// Writes each of the provided columns in the length-prefixed column format, read by [`ColumnReader`].
+pub fn write_columns<C: Column, W: Write>(
+    columns: impl Iterator<Item = impl Deref<Target = C>>,
+    writer: &mut W,
+) -> io::Result<()> {
+    for column in columns {
+        write_column(column.deref(), writer)?;
+    }
+    Ok(())
+}
+
+/// Writes the columns of R from the provided decomposition in the length-prefixed column format, read by [`ColumnReader`].
+pub fn write_r_cols<C: Column, Algo: Decomposition<C>, W: Write>(
+    algo: &Algo,
+    writer: &mut W,
+) -> io::Result<()> {
+    let r_col_iter = (0..algo.n_cols()).map(|idx| algo.get_r_col(idx));
+    write_columns(r_col_iter, writer)
+}
+
+/// An iterator which incrementally parses columns from a reader, in the length-prefixed format written by [`write_columns`].
+///
+/// Each column is stored as its dimension, followed by the number of entries, followed by the entries.
+/// All integers are stored as little-endian `u64`.
+/// Iteration ends when the reader is exhausted at a column boundary; a partially written column yields an error.
+pub struct ColumnReader<R: Read, C: Column> {
+    reader: R,
+    finished: bool,
+    phantom: PhantomData<C>,
+}
+
+impl<R: Read, C: Column> ColumnReader<R, C> {
+    /// Start parsing columns from the provided reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            finished: false,
+            phantom: PhantomData,
+        }
+    }
+
+    fn read_column(&mut self) -> io::Result<Option<C>> {
+        let dimension = match read_u64(&mut self.reader)? {
+            Some(dimension) => dimension,
+            None => return Ok(None),
+        };
+        let n_entries = read_u64(&mut self.reader)?.ok_or(io::ErrorKind::UnexpectedEof)?;
+        // Collect the entries first, so that they can be added in one go rather than one at a time.
+        // We do not trust n_entries enough to pre-allocate.
+        let mut entries = vec![];
+        for _ in 0..n_entries {
+            entries.push(read_u64(&mut self.reader)?.ok_or(io::ErrorKind::UnexpectedEof)?);
+        }
+        let mut column = C::new_with_dimension(dimension);
+        column.add_entries(entries.into_iter());
+        Ok(Some(column))
+    }
+}
+
+impl<R: Read, C: Column> Iterator for ColumnReader<R, C> {
+    type Item = io::Result<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let column = self.read_column().transpose();
+        // Stop after an error or EOF
+        if !matches!(column, Some(Ok(_))) {
+            self.finished = true;
+        }
+        column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{DecompositionAlgo, LockFreeAlgorithm, SerialAlgorithm};
+    use crate::columns::VecColumn;
+    use crate::utils::fixtures::build_sphere_triangulation;
+
+    #[test]
+    fn build_algorithm_from_reader() {
+        let matrix = build_sphere_triangulation();
+        let mut bytes: Vec<u8> = vec![];
+        write_columns(matrix.iter(), &mut bytes).unwrap();
+        let from_reader = LockFreeAlgorithm::<VecColumn>::init(None)
+            .add_cols_from_reader(bytes.as_slice())
+            .unwrap()
+            .decompose();
+        let from_vec = LockFreeAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose();
+        assert_eq!(from_reader.diagram(), from_vec.diagram());
+    }
+
+    #[test]
+    fn r_cols_round_trip() {
+        let decomp = SerialAlgorithm::init(None)
+            .add_cols(build_sphere_triangulation().into_iter())
+            .decompose();
+        let mut bytes: Vec<u8> = vec![];
+        write_r_cols(&decomp, &mut bytes).unwrap();
+        let r: Vec<VecColumn> = ColumnReader::new(bytes.as_slice())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(r.len(), decomp.n_cols());
+        for (idx, col) in r.iter().enumerate() {
+            assert_eq!(col, decomp.get_r_col(idx));
+        }
+    }
+
+    #[test]
+    fn truncated_reader_errors() {
+        let mut bytes: Vec<u8> = vec![];
+        write_columns(build_sphere_triangulation().iter(), &mut bytes).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        let result =
+            SerialAlgorithm::<VecColumn>::init(None).add_cols_from_reader(bytes.as_slice());
+        assert_eq!(
+            result.err().map(|e| e.kind()),
+            Some(io::ErrorKind::UnexpectedEof)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::columns::VecColumn;
+
+/// How the pixels (or voxels) of an image are turned into the cells of a cubical complex, see [`CubicalComplexBuilder`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CubicalConstruction {
+    /// Each pixel is a vertex and each higher dimensional cell takes the largest value of its vertices.
+    /// Pixels are connected only to those sharing a face, e.g. 4-connectivity in 2D.
+    #[default]
+    V,
+    /// Each pixel is a top dimensional cell and each lower dimensional cell takes the smallest value of the top dimensional cells containing it.
+    /// Pixels are connected to all those sharing a vertex, e.g. 8-connectivity in 2D.
+    T,
+}
+
+/// Constructs the boundary matrix of the cubical complex filtered by the values of an image, e.g. a 2D image of pixels or a 3D image of voxels.
+///
+/// # Example
+/// ```
+/// use lophat::{
+///     algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm},
+///     columns::Column,
+///     utils::{Barcode, CubicalComplexBuilder, CubicalConstruction},
+/// };
+///
+/// // A 2x2 image, in row-major order
+/// let (matrix, values) = CubicalComplexBuilder::new(vec![0.0, 2.0, 3.0, 1.0], &[2, 2])
+///     .with_construction(CubicalConstruction::V)
+///     .build();
+/// let dims: Vec<usize> = matrix.iter().map(|col| col.dimension()).collect();
+/// let diagram = LockFreeAlgorithm::init(None)
+///     .add_cols(matrix.into_iter())
+///     .decompose()
+///     .diagram();
+/// let barcode = Barcode::from_diagram(&diagram, &dims, &values);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CubicalComplexBuilder {
+    values: Vec<f64>,
+    shape: Vec<usize>,
+    construction: CubicalConstruction,
+}
+
+impl CubicalComplexBuilder {
+    /// Starts building the complex of an image, using the [`V`](CubicalConstruction::V) construction.
+    /// * `values` - the value of each pixel, in row-major order (i.e. the last axis varies fastest).
+    /// * `shape` - the number of pixels along each axis.
+    ///
+    /// Panics if the number of values differs from the number of pixels, or if some axis has no pixels.
+    pub fn new(values: Vec<f64>, shape: &[usize]) -> Self {
+        assert!(
+            shape.iter().all(|&n| n > 0),
+            "Should have at least one pixel along each axis"
+        );
+        assert_eq!(
+            values.len(),
+            shape.iter().product::<usize>(),
+            "Should provide a value for every pixel"
+        );
+        Self {
+            values,
+            shape: shape.to_vec(),
+            construction: CubicalConstruction::default(),
+        }
+    }
+
+    /// Sets how pixels are turned into cells.
+    pub fn with_construction(mut self, construction: CubicalConstruction) -> Self {
+        self.construction = construction;
+        self
+    }
+
+    /// Builds the boundary matrix, sorted into a valid filtration order, and the filtration value of each column.
+    ///
+    /// Ties in filtration value are resolved by placing lower dimensional cells first, so every face appears before its cofaces.
+    /// The dimension of each column is the dimension of its cell.
+    pub fn build(&self) -> (Vec<VecColumn>, Vec<f64>) {
+        // Cells are indexed by coordinates in which even entries are vertices and odd entries are edges along that axis
+        let extent: Vec<usize> = self
+            .shape
+            .iter()
+            .map(|&n| match self.construction {
+                CubicalConstruction::V => 2 * n - 1,
+                CubicalConstruction::T => 2 * n + 1,
+            })
+            .collect();
+        let n_cells: usize = extent.iter().product();
+        let mut dims = Vec::with_capacity(n_cells);
+        let mut values = Vec::with_capacity(n_cells);
+        for cell in 0..n_cells {
+            let coords = unflatten(cell, &extent);
+            dims.push(coords.iter().filter(|&&c| c % 2 == 1).count());
+            values.push(self.cell_value(coords, &extent));
+        }
+        let mut order: Vec<usize> = (0..n_cells).collect();
+        order.sort_by(|&a, &b| {
+            values[a]
+                .total_cmp(&values[b])
+                .then(dims[a].cmp(&dims[b]))
+                .then(a.cmp(&b))
+        });
+        let mut column_of = vec![0; n_cells];
+        for (col, &cell) in order.iter().enumerate() {
+            column_of[cell] = col;
+        }
+        let matrix = order
+            .iter()
+            .map(|&cell| {
+                let mut coords = unflatten(cell, &extent);
+                let mut faces = vec![];
+                for axis in 0..coords.len() {
+                    let c = coords[axis];
+                    if c % 2 == 1 {
+                        for face_c in [c - 1, c + 1] {
+                            coords[axis] = face_c;
+                            faces.push(column_of[flatten(&coords, &extent)]);
+                        }
+                        coords[axis] = c;
+                    }
+                }
+                faces.sort_unstable();
+                VecColumn::from((dims[cell], faces))
+            })
+            .collect();
+        let values = order.iter().map(|&cell| values[cell]).collect();
+        (matrix, values)
+    }
+
+    // The filtration value of the cell with the given coordinates
+    fn cell_value(&self, mut coords: Vec<usize>, extent: &[usize]) -> f64 {
+        let pixel_value = |pixel_coords: &[usize]| {
+            let pixel: Vec<usize> = pixel_coords.iter().map(|&c| c / 2).collect();
+            self.values[flatten(&pixel, &self.shape)]
+        };
+        let mut value = match self.construction {
+            CubicalConstruction::V => f64::NEG_INFINITY,
+            CubicalConstruction::T => f64::INFINITY,
+        };
+        match self.construction {
+            // Pixels are the vertices, at even coordinates
+            CubicalConstruction::V => {
+                let axes: Vec<usize> = (0..coords.len()).filter(|&k| coords[k] % 2 == 1).collect();
+                for_each_neighbour(&mut coords, &axes, extent, &mut |vertex| {
+                    value = value.max(pixel_value(vertex));
+                });
+            }
+            // Pixels are the top dimensional cells, at odd coordinates
+            CubicalConstruction::T => {
+                let axes: Vec<usize> = (0..coords.len()).filter(|&k| coords[k] % 2 == 0).collect();
+                for_each_neighbour(&mut coords, &axes, extent, &mut |top_cell| {
+                    value = value.min(pixel_value(top_cell));
+                });
+            }
+        }
+        value
+    }
+}
+
+// Converts coordinates into an index, in row-major order
+fn flatten(coords: &[usize], extent: &[usize]) -> usize {
+    coords
+        .iter()
+        .zip(extent)
+        .fold(0, |idx, (&c, &n)| idx * n + c)
+}
+
+// Converts an index into coordinates, in row-major order
+fn unflatten(mut idx: usize, extent: &[usize]) -> Vec<usize> {
+    let mut coords = vec![0; extent.len()];
+    for (c, &n) in coords.iter_mut().zip(extent).rev() {
+        *c = idx % n;
+        idx /= n;
+    }
+    coords
+}
+
+// Calls `f` on every cell obtained by moving one step in either direction along each of `axes`, staying within the grid
+fn for_each_neighbour(
+    coords: &mut [usize],
+    axes: &[usize],
+    extent: &[usize],
+    f: &mut impl FnMut(&[usize]),
+) {
+    let Some((&axis, rest)) = axes.split_first() else {
+        f(coords);
+        return;
+    };
+    let c = coords[axis];
+    if c > 0 {
+        coords[axis] = c - 1;
+        for_each_neighbour(coords, rest, extent, f);
+    }
+    if c + 1 < extent[axis] {
+        coords[axis] = c + 1;
+        for_each_neighbour(coords, rest, extent, f);
+    }
+    coords[axis] = c;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+    use crate::columns::Column;
+    use crate::utils::{is_boundary_matrix, Barcode};
+
+    // The bars of positive length, sorted
+    fn barcode_of(builder: CubicalComplexBuilder) -> Vec<(usize, f64, f64)> {
+        let (matrix, values) = builder.build();
+        assert_eq!(is_boundary_matrix(&matrix), Ok(()));
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        let dims: Vec<usize> = matrix.iter().map(|col| col.dimension()).collect();
+        let diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        let mut barcode = Barcode::from_diagram(&diagram, &dims, &values);
+        barcode.sort();
+        barcode
+            .bars
+            .into_iter()
+            .filter(|bar| bar.death > bar.birth)
+            .map(|bar| (bar.dimension, bar.birth, bar.death))
+            .collect()
+    }
+
+    #[test]
+    fn two_by_two_image() {
+        let builder = CubicalComplexBuilder::new(vec![0.0, 2.0, 3.0, 1.0], &[2, 2]);
+        let (matrix, _) = builder.build();
+        // 4 vertices, 4 edges and 1 square
+        assert_eq!(matrix.len(), 9);
+        assert_eq!(
+            barcode_of(builder),
+            vec![(0, 0.0, f64::INFINITY), (0, 1.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn constructions_differ_in_connectivity() {
+        // The two minima only share a vertex, so are joined immediately in the T construction
+        let values = vec![0.0, 2.0, 2.0, 0.0];
+        let builder = CubicalComplexBuilder::new(values, &[2, 2]);
+        assert_eq!(
+            barcode_of(builder.clone()),
+            vec![(0, 0.0, 2.0), (0, 0.0, f64::INFINITY)]
+        );
+        let builder = builder.with_construction(CubicalConstruction::T);
+        // 9 vertices, 12 edges and 4 squares
+        assert_eq!(builder.build().0.len(), 25);
+        assert_eq!(barcode_of(builder), vec![(0, 0.0, f64::INFINITY)]);
+    }
+
+    #[test]
+    fn annulus_has_a_loop() {
+        // A 3x3 image with a high centre pixel
+        let mut values = vec![0.0; 9];
+        values[4] = 5.0;
+        let builder = CubicalComplexBuilder::new(values, &[3, 3]);
+        assert_eq!(
+            barcode_of(builder),
+            vec![(0, 0.0, f64::INFINITY), (1, 0.0, 5.0)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn values_must_match_shape() {
+        CubicalComplexBuilder::new(vec![0.0; 3], &[2, 2]);
+    }
+}
@@ -0,0 +1,70 @@
+use ndarray::Array2;
+
+use crate::columns::VecColumn;
+
+/// Converts a dense boolean matrix into a vector of columns, suitable for passing to [`add_cols`](crate::algorithms::DecompositionAlgo::add_cols).
+/// * `m` - the matrix, in which `m[[i, j]]` is `true` if row `i` of column `j` is non-zero.
+/// * `dims` - the dimension of each column.
+///
+/// Panics if the length of `dims` differs from the number of columns of `m`.
+pub fn from_ndarray(m: &Array2<bool>, dims: &[usize]) -> Vec<VecColumn> {
+    assert_eq!(
+        m.ncols(),
+        dims.len(),
+        "Should provide a dimension for every column of the matrix"
+    );
+    m.columns()
+        .into_iter()
+        .zip(dims)
+        .map(|(col, &dim)| {
+            let entries: Vec<usize> = col
+                .iter()
+                .enumerate()
+                .filter_map(|(row, &is_set)| is_set.then_some(row))
+                .collect();
+            VecColumn::from((dim, entries))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+    use crate::utils::PersistenceDiagram;
+    use hashbrown::HashSet;
+    use ndarray::array;
+
+    #[test]
+    fn filled_triangle_from_dense() {
+        // Three vertices, three edges and a triangle
+        let m = array![
+            [false, false, false, true, true, false, false],
+            [false, false, false, true, false, true, false],
+            [false, false, false, false, true, true, false],
+            [false, false, false, false, false, false, true],
+            [false, false, false, false, false, false, true],
+            [false, false, false, false, false, false, true],
+            [false, false, false, false, false, false, false],
+        ];
+        let matrix = from_ndarray(&m, &[0, 0, 0, 1, 1, 1, 2]);
+        assert_eq!(matrix[4], VecColumn::from((1, vec![0, 2])));
+        assert_eq!(matrix[6], VecColumn::from((2, vec![3, 4, 5])));
+        let diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        let correct_diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![(1, 3), (2, 4), (5, 6)]),
+        };
+        assert_eq!(diagram, correct_diagram);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dims_must_match_columns() {
+        let m = Array2::from_elem((2, 2), false);
+        from_ndarray(&m, &[0]);
+    }
+}
@@ -1,4 +1,7 @@
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
+use rayon::prelude::*;
+
+use crate::error::LophatError;
 
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
@@ -17,6 +20,7 @@ pub struct PersistenceDiagram {
 impl PersistenceDiagram {
     /// Re-indexes a persistence diagram, assuming that it was produced from an anti-transposed matrix.
     /// Requires `matrix_size` - the size of the decomposed matrix, assumed to be square.
+    /// Indices are not checked against `matrix_size`; use [`try_anti_transpose`](Self::try_anti_transpose) if unsure.
     pub fn anti_transpose(mut self, matrix_size: usize) -> Self {
         let new_paired = self
             .paired
@@ -32,6 +36,88 @@ impl PersistenceDiagram {
         self.unpaired = new_unpaired;
         self
     }
+
+    /// As for [`anti_transpose`](Self::anti_transpose) but first checks that every index is less than `matrix_size`.
+    /// Returns [`LophatError::ColumnOutOfRange`] for the first offending index found, rather than silently wrapping around.
+    pub fn try_anti_transpose(self, matrix_size: usize) -> Result<Self, LophatError> {
+        let out_of_range = self
+            .paired
+            .iter()
+            .flat_map(|&(birth, death)| [birth, death])
+            .chain(self.unpaired.iter().copied())
+            .find(|&idx| idx >= matrix_size);
+        match out_of_range {
+            Some(col) => Err(LophatError::ColumnOutOfRange {
+                col,
+                n_cols: matrix_size,
+            }),
+            None => Ok(self.anti_transpose(matrix_size)),
+        }
+    }
+
+    /// As for [`anti_transpose`](Self::anti_transpose) but the pairs and unpaired indices are re-indexed in parallel, using rayon.
+    /// Useful for large diagrams, e.g. after a parallel cohomology computation.
+    ///
+    /// Panics if some index is not less than `matrix_size`, rather than silently wrapping around.
+    pub fn anti_transpose_parallel(self, matrix_size: usize) -> Self {
+        let reindex = |idx: usize| {
+            (matrix_size - 1)
+                .checked_sub(idx)
+                .expect("Index should be less than matrix_size")
+        };
+        Self {
+            paired: self
+                .paired
+                .into_par_iter()
+                .map(|(b, d)| (reindex(d), reindex(b)))
+                .collect(),
+            unpaired: self.unpaired.into_par_iter().map(reindex).collect(),
+        }
+    }
+
+    /// Shifts every index of the diagram up by `by`.
+    /// Useful for placing the diagram of one piece of a decomposed complex (e.g. a connected component) within the indexing of the whole, see [`union`](Self::union).
+    pub fn offset(self, by: usize) -> Self {
+        Self {
+            unpaired: self.unpaired.into_iter().map(|idx| idx + by).collect(),
+            paired: self
+                .paired
+                .into_iter()
+                .map(|(birth, death)| (birth + by, death + by))
+                .collect(),
+        }
+    }
+
+    /// Merges the pairings and unpaired columns of two diagrams.
+    ///
+    /// The indices are not adjusted, so the diagrams should already refer to disjoint sets of columns, e.g. via [`offset`](Self::offset).
+    /// For example, if D is block diagonal with blocks D1 and D2 then its diagram is `diagram1.union(diagram2.offset(n1))`, where `n1` is the number of columns of D1.
+    pub fn union(mut self, other: Self) -> Self {
+        self.unpaired.extend(other.unpaired);
+        self.paired.extend(other.paired);
+        self
+    }
+
+    /// Returns all features as a single list of `(birth, death)` pairs, sorted by birth.
+    /// Finite features appear as `(birth, Some(death))` and essential (unpaired) classes as `(birth, None)`.
+    pub fn as_pairs_with_infinity(&self) -> Vec<(usize, Option<usize>)> {
+        let mut pairs: Vec<_> = self
+            .paired
+            .iter()
+            .map(|&(birth, death)| (birth, Some(death)))
+            .chain(self.unpaired.iter().map(|&birth| (birth, None)))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// Reconstructs the map from pivots to columns from a persistence diagram, i.e. each pairing `(birth, death)` becomes the entry `birth -> death`.
+/// Since column `death` of R has pivot `birth`, this is the map from each pivot row to the column of R with that pivot.
+///
+/// Useful for seeding a reduction from a prior result, e.g. when appending columns to an already decomposed matrix.
+pub fn diagram_to_pivots(diagram: &PersistenceDiagram) -> HashMap<usize, usize> {
+    diagram.paired.iter().copied().collect()
 }
 
 impl std::fmt::Display for PersistenceDiagram {
@@ -62,3 +148,103 @@ impl PersistenceDiagram {
         self.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::hash_set;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn parallel_anti_transpose_agrees_with_serial(
+            indices in hash_set(0..500usize, 0..200),
+            n_unpaired in 0..20usize,
+        ) {
+            let mut indices: Vec<usize> = indices.into_iter().collect();
+            let unpaired: HashSet<usize> = indices.drain(..n_unpaired.min(indices.len())).collect();
+            let paired: HashSet<(usize, usize)> = indices
+                .chunks_exact(2)
+                .map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1])))
+                .collect();
+            let diagram = PersistenceDiagram { unpaired, paired };
+            assert_eq!(
+                diagram.clone().anti_transpose_parallel(500),
+                diagram.anti_transpose(500)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn parallel_anti_transpose_checks_indices() {
+        let diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![(1, 4)]),
+        };
+        diagram.anti_transpose_parallel(4);
+    }
+
+    #[test]
+    fn try_anti_transpose_checks_indices() {
+        let diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 3)]),
+        };
+        let transposed = diagram.clone().try_anti_transpose(5).unwrap();
+        assert_eq!(transposed, diagram.clone().anti_transpose(5));
+        assert_eq!(transposed.unpaired, HashSet::from_iter(vec![4]));
+        assert_eq!(transposed.paired, HashSet::from_iter(vec![(0, 3), (1, 2)]));
+        assert_eq!(
+            diagram.clone().try_anti_transpose(4),
+            Err(LophatError::ColumnOutOfRange { col: 4, n_cols: 4 })
+        );
+        assert!(diagram.try_anti_transpose(0).is_err());
+    }
+
+    #[test]
+    fn union_of_offset_components() {
+        // Two filled triangles, each with 7 columns
+        let triangle = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![(1, 3), (2, 4), (5, 6)]),
+        };
+        let combined = triangle.clone().union(triangle.clone().offset(7));
+        assert_eq!(combined.unpaired, HashSet::from_iter(vec![0, 7]));
+        assert_eq!(
+            combined.paired,
+            HashSet::from_iter(vec![(1, 3), (2, 4), (5, 6), (8, 10), (9, 11), (12, 13)])
+        );
+        // No index appears in more than one feature
+        let indices: Vec<usize> = combined
+            .as_pairs_with_infinity()
+            .into_iter()
+            .flat_map(|(birth, death)| std::iter::once(birth).chain(death))
+            .collect();
+        assert_eq!(indices.len(), 14);
+        assert_eq!(HashSet::<usize>::from_iter(indices).len(), 14);
+        assert_eq!(triangle.clone().offset(0), triangle);
+    }
+
+    #[test]
+    fn sphere_essential_classes_have_no_death() {
+        let diagram = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0, 13]),
+            paired: HashSet::from_iter(vec![(1, 4), (2, 5), (3, 7), (6, 12), (8, 10), (9, 11)]),
+        };
+        let pairs = diagram.as_pairs_with_infinity();
+        assert_eq!(
+            pairs,
+            vec![
+                (0, None),
+                (1, Some(4)),
+                (2, Some(5)),
+                (3, Some(7)),
+                (6, Some(12)),
+                (8, Some(10)),
+                (9, Some(11)),
+                (13, None)
+            ]
+        );
+    }
+}
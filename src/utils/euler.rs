@@ -0,0 +1,136 @@
+use crate::{algorithms::Decomposition, columns::Column};
+
+/// Computes the Euler characteristic curve of a filtration, without any reduction.
+/// * `dims` - the dimension of each simplex (or cell).
+/// * `values` - the filtration value of each simplex, in the same order as `dims`.
+///
+/// Returns, for each distinct filtration value `t` in increasing order, the pair `(t, χ(t))`,
+/// where `χ(t)` is the alternating sum `∑(-1)^dim` over all simplices with filtration value at most `t`.
+///
+/// Panics if `dims` and `values` have different lengths.
+pub fn euler_characteristic_curve(dims: &[usize], values: &[f64]) -> Vec<(f64, i64)> {
+    assert_eq!(
+        dims.len(),
+        values.len(),
+        "Should provide a filtration value for every simplex"
+    );
+    let mut order: Vec<usize> = (0..dims.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut curve: Vec<(f64, i64)> = vec![];
+    let mut euler_char = 0;
+    for idx in order {
+        euler_char += alternating_sign(dims[idx]);
+        match curve.last_mut() {
+            // Same filtration value as the previous simplex, so update its entry
+            Some((value, chi)) if *value == values[idx] => *chi = euler_char,
+            _ => curve.push((values[idx], euler_char)),
+        }
+    }
+    curve
+}
+
+// Each simplex of even dimension contributes +1 and each of odd dimension -1
+fn alternating_sign(dimension: usize) -> i64 {
+    if dimension % 2 == 0 {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Computes the Euler characteristic `χ = ∑(-1)^d β_d` of the complex from the [`betti_numbers`](Decomposition::betti_numbers) of a decomposition.
+///
+/// In debug builds, asserts that this agrees with the alternating count of columns by dimension.
+/// The two agree whenever every pair joins columns of adjacent dimensions,
+/// so a mismatch indicates a gross error in constructing the boundary matrix (e.g. incorrect dimensions).
+/// This is a cheap sanity check, not a substitute for checking the matrix with [`is_boundary_matrix`](crate::utils::is_boundary_matrix).
+pub fn euler_characteristic_from_diagram<C: Column, D: Decomposition<C>>(decomposition: &D) -> i64 {
+    let euler_char = decomposition
+        .betti_numbers()
+        .into_iter()
+        .map(|(dimension, betti)| alternating_sign(dimension) * betti as i64)
+        .sum();
+    debug_assert_eq!(
+        euler_char,
+        (0..decomposition.n_cols())
+            .map(|idx| alternating_sign(decomposition.get_r_col(idx).dimension()))
+            .sum::<i64>(),
+        "Euler characteristic from Betti numbers should equal the alternating count of simplices"
+    );
+    euler_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{DecompositionAlgo, LockFreeAlgorithm};
+    use crate::columns::VecColumn;
+    use crate::utils::order_filtration;
+
+    // All faces of the given maximal simplices, each with filtration value its dimension
+    fn closure(maximal: &[Vec<usize>]) -> Vec<(f64, Vec<usize>)> {
+        let mut faces: Vec<Vec<usize>> = vec![];
+        for simplex in maximal {
+            for mask in 1..(1usize << simplex.len()) {
+                let mut face: Vec<usize> = (0..simplex.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| simplex[i])
+                    .collect();
+                face.sort_unstable();
+                if !faces.contains(&face) {
+                    faces.push(face);
+                }
+            }
+        }
+        faces
+            .into_iter()
+            .map(|face| ((face.len() - 1) as f64, face))
+            .collect()
+    }
+
+    fn euler_char_of(maximal: &[Vec<usize>]) -> i64 {
+        let (matrix, _) = order_filtration(closure(maximal));
+        let decomp = LockFreeAlgorithm::<VecColumn>::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose();
+        euler_characteristic_from_diagram(&decomp)
+    }
+
+    #[test]
+    fn sphere_and_torus_euler_characteristic() {
+        let tetrahedron_boundary: Vec<Vec<usize>> =
+            vec![vec![0, 1, 2], vec![0, 1, 3], vec![0, 2, 3], vec![1, 2, 3]];
+        assert_eq!(euler_char_of(&tetrahedron_boundary), 2);
+        // The 7 vertex triangulation of the torus
+        let torus: Vec<Vec<usize>> = (0..7)
+            .flat_map(|i| {
+                [
+                    vec![i, (i + 1) % 7, (i + 3) % 7],
+                    vec![i, (i + 2) % 7, (i + 3) % 7],
+                ]
+            })
+            .collect();
+        assert_eq!(closure(&torus).len(), 7 + 21 + 14);
+        assert_eq!(euler_char_of(&torus), 0);
+    }
+
+    #[test]
+    fn sphere_has_euler_characteristic_two() {
+        // Face vector (4, 6, 4) of the boundary of a tetrahedron, added one dimension at a time
+        let dims = [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2];
+        let values = [
+            0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0,
+        ];
+        let curve = euler_characteristic_curve(&dims, &values);
+        assert_eq!(curve, vec![(0.0, 4), (1.0, -2), (2.0, 2)]);
+    }
+
+    #[test]
+    fn values_need_not_be_sorted() {
+        let dims = [1, 0, 0, 2, 1, 0, 1];
+        let values = [0.5, 0.0, 0.0, 2.0, 1.0, 0.0, 0.5];
+        let curve = euler_characteristic_curve(&dims, &values);
+        assert_eq!(curve, vec![(0.0, 3), (0.5, 1), (1.0, 0), (2.0, 1)]);
+        assert!(euler_characteristic_curve(&[], &[]).is_empty());
+    }
+}
@@ -1,10 +1,13 @@
-use std::{cell::Cell, ops::Deref};
+use std::{cell::Cell, fmt::Display, marker::PhantomData, ops::Deref};
 
+use rayon::prelude::*;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
 use crate::{
     algorithms::{Decomposition, NoVMatrixError},
     columns::{Column, VecColumn},
+    error::LophatError,
+    utils::PersistenceDiagram,
 };
 
 #[macro_export]
@@ -80,21 +83,115 @@ macro_rules! impl_rvd_serialize {
 /// // Deseralize to file format
 /// let rvdff: DecompositionFileFormat = from_reader(bytes.as_slice()).ok().unwrap();
 /// ```
+///
+/// If the persistence diagram was also serialized (e.g. via [`WithDiagram`]) then it is stored in the file format
+/// and [`diagram`](Decomposition::diagram) returns it directly, without scanning R.
 #[derive(Deserialize, PartialEq, Debug)]
 pub struct DecompositionFileFormat {
     r: Vec<VecColumn>,
     v: Option<Vec<VecColumn>>,
+    // Older files do not have this field
+    #[serde(default)]
+    diagram: Option<SortedDiagram>,
 }
 
 impl DecompositionFileFormat {
     /// Construct the [`DecompositionFileFormat`] using the provided matrices.
     pub fn new(r: Vec<VecColumn>, v: Option<Vec<VecColumn>>) -> Self {
-        Self { r, v }
+        Self {
+            r,
+            v,
+            diagram: None,
+        }
+    }
+
+    /// Store the provided persistence diagram alongside the decomposition.
+    pub fn with_diagram(mut self, diagram: &PersistenceDiagram) -> Self {
+        self.diagram = Some(SortedDiagram::from(diagram));
+        self
+    }
+
+    /// Parses a [`DecompositionFileFormat`] from `reader`, using the provided deserialization function, and then validates it.
+    /// * `from_reader` - the deserialization function for your chosen format, e.g. `ciborium::de::from_reader`.
+    ///
+    /// Use this in place of deserializing directly when the input is untrusted.
+    /// Returns [`LophatError::InvalidFileFormat`] if the input cannot be parsed, if any column of R or V is not strictly increasing,
+    /// if V has a different number of columns to R or if any entry of V is out of range.
+    pub fn from_reader_validated<Rd, E: Display>(
+        reader: Rd,
+        from_reader: impl FnOnce(Rd) -> Result<Self, E>,
+    ) -> Result<Self, LophatError> {
+        let rvdff = from_reader(reader).map_err(|e| LophatError::InvalidFileFormat {
+            reason: e.to_string(),
+        })?;
+        rvdff.validate()?;
+        Ok(rvdff)
+    }
+
+    // Checks the invariants assumed of VecColumn, and that V is square and matches R
+    pub(crate) fn validate(&self) -> Result<(), LophatError> {
+        let invalid = |reason: String| Err(LophatError::InvalidFileFormat { reason });
+        let is_strictly_increasing =
+            |col: &VecColumn| col.entries().zip(col.entries().skip(1)).all(|(a, b)| a < b);
+        if let Some(idx) = self.r.iter().position(|col| !is_strictly_increasing(col)) {
+            return invalid(format!("Column {idx} of R is not strictly increasing"));
+        }
+        if let Some(v) = &self.v {
+            if v.len() != self.r.len() {
+                return invalid(format!(
+                    "R has {} columns but V has {}",
+                    self.r.len(),
+                    v.len()
+                ));
+            }
+            if let Some(idx) = v.iter().position(|col| !is_strictly_increasing(col)) {
+                return invalid(format!("Column {idx} of V is not strictly increasing"));
+            }
+            if let Some(idx) = v
+                .iter()
+                .position(|col| col.pivot().is_some_and(|piv| piv >= v.len()))
+            {
+                return invalid(format!("Column {idx} of V has an entry out of range"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether a persistence diagram is stored alongside the decomposition.
+    pub fn has_diagram(&self) -> bool {
+        self.diagram.is_some()
+    }
+}
+
+/// The format in which a [`PersistenceDiagram`] is serialized -- as vectors, sorted in increasing order.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SortedDiagram {
+    paired: Vec<(usize, usize)>,
+    unpaired: Vec<usize>,
+}
+
+impl From<&PersistenceDiagram> for SortedDiagram {
+    fn from(diagram: &PersistenceDiagram) -> Self {
+        let mut paired: Vec<_> = diagram.paired.iter().copied().collect();
+        let mut unpaired: Vec<_> = diagram.unpaired.iter().copied().collect();
+        paired.sort_unstable();
+        unpaired.sort_unstable();
+        Self { paired, unpaired }
+    }
+}
+
+impl From<&SortedDiagram> for PersistenceDiagram {
+    fn from(diagram: &SortedDiagram) -> Self {
+        PersistenceDiagram {
+            unpaired: diagram.unpaired.iter().copied().collect(),
+            paired: diagram.paired.iter().copied().collect(),
+        }
     }
 }
 
 impl Decomposition<VecColumn> for DecompositionFileFormat {
-    type RColRef<'a> = &'a VecColumn
+    type RColRef<'a>
+        = &'a VecColumn
     where
         Self: 'a;
 
@@ -102,7 +199,8 @@ impl Decomposition<VecColumn> for DecompositionFileFormat {
         &self.r[index]
     }
 
-    type VColRef<'a> = &'a VecColumn
+    type VColRef<'a>
+        = &'a VecColumn
     where
         Self: 'a;
 
@@ -113,11 +211,25 @@ impl Decomposition<VecColumn> for DecompositionFileFormat {
     fn n_cols(&self) -> usize {
         self.r.len()
     }
+
+    fn diagram(&self) -> PersistenceDiagram {
+        match &self.diagram {
+            Some(diagram) => PersistenceDiagram::from(diagram),
+            None => {
+                let r_col_iter = (0..self.n_cols()).map(|idx| self.get_r_col(idx));
+                crate::algorithms::diagram_from_r_cols(r_col_iter, self.n_cols())
+            }
+        }
+    }
 }
 
 /// Clones the column, converting it to [`VecColumn`] format.
-/// Under the hood, calls [`col.entries()`] to populate the output.
+/// If the column is already stored as a [`VecColumn`] (see [`as_vec_column`](Column::as_vec_column)) then it is cloned directly,
+/// otherwise calls [`col.entries()`] to populate the output.
 pub fn clone_to_veccolumn<C: Column>(col: &C) -> VecColumn {
+    if let Some(vec_col) = col.as_vec_column() {
+        return vec_col.clone();
+    }
     let mut output = VecColumn::new_with_dimension(col.dimension());
     output.add_entries(col.entries());
     output
@@ -125,6 +237,48 @@ pub fn clone_to_veccolumn<C: Column>(col: &C) -> VecColumn {
 
 /// After serializing your decomposition, you should deserialize to [`DecompositionFileFormat`].
 pub fn serialize_algo<C, Algo, S>(algo: &Algo, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    C: Column,
+    Algo: Decomposition<C>,
+{
+    serialize_algo_inner(algo, serializer, None)
+}
+
+/// As [`serialize_algo`], but first converts every column of R and V to [`VecColumn`] in parallel, using rayon.
+/// The converted matrices are then serialized sequentially.
+///
+/// This trades memory for speed: a full copy of the decomposition is held in memory while serializing (see [`clone_to_file_format_parallel`]).
+/// For large decompositions, where the conversion dominates, this can be considerably faster than [`serialize_algo`].
+pub fn serialize_algo_parallel<C, Algo, S>(algo: &Algo, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    C: Column,
+    Algo: Decomposition<C> + Sync,
+{
+    clone_to_file_format_parallel(algo).serialize(serializer)
+}
+
+/// As [`serialize_algo`], but additionally computes the persistence diagram and writes it alongside R and V.
+/// The diagram can then be read directly from the deserialized [`DecompositionFileFormat`].
+pub fn serialize_algo_with_diagram<C, Algo, S>(
+    algo: &Algo,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    C: Column,
+    Algo: Decomposition<C>,
+{
+    let diagram = SortedDiagram::from(&algo.diagram());
+    serialize_algo_inner(algo, serializer, Some(&diagram))
+}
+
+fn serialize_algo_inner<C, Algo, S>(
+    algo: &Algo,
+    serializer: S,
+    diagram: Option<&SortedDiagram>,
+) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
     C: Column,
@@ -155,7 +309,8 @@ where
     }
 
     // Set up struct
-    let mut rvdff = serializer.serialize_struct("DecompositionFileFormat", 2)?;
+    let n_fields = if diagram.is_some() { 3 } else { 2 };
+    let mut rvdff = serializer.serialize_struct("DecompositionFileFormat", n_fields)?;
 
     // Serialize R
     let r_col_iter = (0..algo.n_cols()).map(|idx| {
@@ -178,16 +333,66 @@ where
         None
     };
     rvdff.serialize_field("v", &v_col_iter_opt)?;
+
+    // Serialize diagram
+    if let Some(diagram) = diagram {
+        rvdff.serialize_field("diagram", diagram)?;
+    }
     rvdff.end()
 }
 
+/// A wrapper around a [`Decomposition`] which, when serialized, also writes the persistence diagram (see [`serialize_algo_with_diagram`]).
+///
+/// # Example
+/// ```
+/// use lophat::{
+///     algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm},
+///     columns::VecColumn,
+///     utils::{DecompositionFileFormat, WithDiagram},
+/// };
+/// use ciborium::{de::from_reader, ser::into_writer};
+///
+/// let matrix = vec![(0, vec![]), (0, vec![]), (1, vec![0, 1])]
+///     .into_iter()
+///     .map(VecColumn::from);
+/// let decomp = SerialAlgorithm::init(None).add_cols(matrix).decompose();
+/// let mut bytes: Vec<u8> = vec![];
+/// into_writer(&WithDiagram::new(&decomp), &mut bytes).ok();
+/// let rvdff: DecompositionFileFormat = from_reader(bytes.as_slice()).ok().unwrap();
+/// assert!(rvdff.has_diagram());
+/// assert_eq!(rvdff.diagram(), decomp.diagram());
+/// ```
+pub struct WithDiagram<'a, C, Algo> {
+    algo: &'a Algo,
+    phantom: PhantomData<C>,
+}
+
+impl<'a, C: Column, Algo: Decomposition<C>> WithDiagram<'a, C, Algo> {
+    /// Wrap the provided decomposition, so that the diagram is serialized alongside it.
+    pub fn new(algo: &'a Algo) -> Self {
+        Self {
+            algo,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, C: Column, Algo: Decomposition<C>> Serialize for WithDiagram<'a, C, Algo> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_algo_with_diagram(self.algo, serializer)
+    }
+}
+
 // We do not derive directly because we want all algorithms to use the same serialize function.
 impl Serialize for DecompositionFileFormat {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serialize_algo(self, serializer)
+        serialize_algo_inner(self, serializer, self.diagram.as_ref())
     }
 }
 
@@ -205,15 +410,36 @@ pub fn clone_to_file_format<C: Column, Algo: Decomposition<C>>(
             clone_to_veccolumn(col.deref())
         })
         .collect();
-    let v = algo.get_v_col(0).ok().and_then(|_| {
-        Some(
-            (0..algo.n_cols())
-                .map(|idx| {
-                    let col = algo.get_v_col(idx).unwrap();
-                    clone_to_veccolumn(col.deref())
-                })
-                .collect(),
-        )
+    let v = algo.get_v_col(0).ok().map(|_| {
+        (0..algo.n_cols())
+            .map(|idx| {
+                let col = algo.get_v_col(idx).unwrap();
+                clone_to_veccolumn(col.deref())
+            })
+            .collect()
+    });
+    DecompositionFileFormat::new(r, v)
+}
+
+/// As for [`clone_to_file_format`] but the columns are converted in parallel, using rayon.
+pub fn clone_to_file_format_parallel<C: Column, Algo: Decomposition<C> + Sync>(
+    algo: &Algo,
+) -> DecompositionFileFormat {
+    let r = (0..algo.n_cols())
+        .into_par_iter()
+        .map(|idx| {
+            let col = algo.get_r_col(idx);
+            clone_to_veccolumn(col.deref())
+        })
+        .collect();
+    let v = algo.get_v_col(0).ok().map(|_| {
+        (0..algo.n_cols())
+            .into_par_iter()
+            .map(|idx| {
+                let col = algo.get_v_col(idx).unwrap();
+                clone_to_veccolumn(col.deref())
+            })
+            .collect()
     });
     DecompositionFileFormat::new(r, v)
 }
@@ -221,13 +447,14 @@ pub fn clone_to_file_format<C: Column, Algo: Decomposition<C>>(
 #[cfg(test)]
 mod tests {
     use crate::{
-        algorithms::{DecompositionAlgo, LockFreeAlgorithm},
+        algorithms::{Decomposition, DecompositionAlgo, LockFreeAlgorithm},
         columns::VecColumn,
+        error::LophatError,
         options::LoPhatOptions,
     };
     use ciborium::{de::from_reader, ser::into_writer};
 
-    use super::DecompositionFileFormat;
+    use super::{serialize_algo_parallel, DecompositionFileFormat, WithDiagram};
 
     fn get_matrix() -> impl Iterator<Item = VecColumn> {
         vec![
@@ -294,10 +521,12 @@ mod tests {
         let matrix = get_matrix();
         let correct_rvdff = get_rvdff(true);
         // Decompose via LFA
-        let mut options = LoPhatOptions::default();
-        options.maintain_v = true;
-        options.clearing = false; // Just do normal left-to-right reduction in decreasing dimensions
-        options.num_threads = 1; // So we can predict the output
+        let options = LoPhatOptions {
+            maintain_v: true,
+            clearing: false, // Just do normal left-to-right reduction in decreasing dimensions
+            num_threads: 1,  // So we can predict the output
+            ..Default::default()
+        };
         let decomp = LockFreeAlgorithm::init(Some(options))
             .add_cols(matrix)
             .decompose();
@@ -314,10 +543,12 @@ mod tests {
     fn serialize_lfa_without_v() {
         let matrix = get_matrix();
         let correct_rvdff = get_rvdff(false); // Decompose via LFA
-        let mut options = LoPhatOptions::default();
-        options.maintain_v = false;
-        options.clearing = false; // Just do normal left-to-right reduction in decreasing dimensions
-        options.num_threads = 1; // So we can predict the output
+        let options = LoPhatOptions {
+            maintain_v: false,
+            clearing: false, // Just do normal left-to-right reduction in decreasing dimensions
+            num_threads: 1,  // So we can predict the output
+            ..Default::default()
+        };
         let decomp = LockFreeAlgorithm::init(Some(options))
             .add_cols(matrix)
             .decompose();
@@ -329,4 +560,137 @@ mod tests {
         // Check all columns are correct and V is none
         assert_eq!(rvdff, correct_rvdff)
     }
+
+    // Serializes the wrapped decomposition via serialize_algo_parallel
+    struct ParallelSerialize<'a, Algo>(&'a Algo);
+
+    impl<'a, Algo: Decomposition<VecColumn> + Sync> serde::Serialize for ParallelSerialize<'a, Algo> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize_algo_parallel(self.0, serializer)
+        }
+    }
+
+    #[test]
+    fn parallel_serialization_agrees() {
+        for maintain_v in [false, true] {
+            let options = LoPhatOptions {
+                maintain_v,
+                ..Default::default()
+            };
+            let decomp = LockFreeAlgorithm::init(Some(options))
+                .add_cols(get_matrix())
+                .decompose();
+            let mut streamed: Vec<u8> = vec![];
+            into_writer(&decomp, &mut streamed).ok();
+            let mut parallel: Vec<u8> = vec![];
+            into_writer(&ParallelSerialize(&decomp), &mut parallel).ok();
+            let streamed: DecompositionFileFormat = from_reader(streamed.as_slice()).ok().unwrap();
+            let parallel: DecompositionFileFormat = from_reader(parallel.as_slice()).ok().unwrap();
+            assert_eq!(streamed, parallel);
+        }
+    }
+
+    #[test]
+    fn serialize_lfa_with_diagram() {
+        let matrix = get_matrix();
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(matrix)
+            .decompose();
+        // Serialize with diagram into bytes
+        let mut bytes: Vec<u8> = vec![];
+        into_writer(&WithDiagram::new(&decomp), &mut bytes).ok();
+        // Deseralize to file format
+        let rvdff: DecompositionFileFormat = from_reader(bytes.as_slice()).ok().unwrap();
+        assert!(rvdff.has_diagram());
+        assert_eq!(rvdff.diagram(), decomp.diagram());
+        // Serialize the file format and back again, keeping the diagram
+        let mut bytes: Vec<u8> = vec![];
+        into_writer(&rvdff, &mut bytes).ok();
+        let rvdff_2: DecompositionFileFormat = from_reader(bytes.as_slice()).ok().unwrap();
+        assert_eq!(rvdff, rvdff_2);
+    }
+
+    #[test]
+    fn pivot_collisions_detect_unreduced_r() {
+        let reduced = get_rvdff(true);
+        assert!(reduced.is_reduced());
+        assert!(reduced.pivot_collisions().is_empty());
+        // Use D in place of R, in which columns 4 and 5 share pivot 2
+        let unreduced = DecompositionFileFormat::new(get_matrix().collect(), None);
+        assert!(!unreduced.is_reduced());
+        assert_eq!(unreduced.pivot_collisions(), vec![(4, 5)]);
+    }
+
+    #[test]
+    fn deserialize_without_diagram() {
+        // Files written without a diagram should still load and compute the diagram from R
+        let rvdff_1 = get_rvdff(true);
+        let mut bytes: Vec<u8> = vec![];
+        into_writer(&rvdff_1, &mut bytes).ok();
+        let rvdff_2: DecompositionFileFormat = from_reader(bytes.as_slice()).ok().unwrap();
+        assert!(!rvdff_2.has_diagram());
+        let with_diagram = get_rvdff(true).with_diagram(&rvdff_1.diagram());
+        assert_eq!(rvdff_2.diagram(), with_diagram.diagram());
+    }
+
+    // Serializes R and V exactly as provided, bypassing the checks made when serializing a decomposition
+    #[derive(serde::Serialize)]
+    struct RawFileFormat {
+        r: Vec<VecColumn>,
+        v: Option<Vec<VecColumn>>,
+    }
+
+    fn to_bytes(r: Vec<VecColumn>, v: Option<Vec<VecColumn>>) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        into_writer(&RawFileFormat { r, v }, &mut bytes).ok();
+        bytes
+    }
+
+    #[test]
+    fn validated_accepts_valid_payload() {
+        let mut bytes: Vec<u8> = vec![];
+        into_writer(&get_rvdff(true), &mut bytes).ok();
+        let rvdff =
+            DecompositionFileFormat::from_reader_validated(bytes.as_slice(), from_reader).unwrap();
+        assert_eq!(rvdff, get_rvdff(true));
+    }
+
+    #[test]
+    fn validated_rejects_unsorted_column() {
+        let mut r: Vec<VecColumn> = get_matrix().collect();
+        r[6] = VecColumn::from((2, vec![3, 5, 4]));
+        let bytes = to_bytes(r, None);
+        let result = DecompositionFileFormat::from_reader_validated(bytes.as_slice(), from_reader);
+        assert_eq!(
+            result,
+            Err(LophatError::InvalidFileFormat {
+                reason: "Column 6 of R is not strictly increasing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn validated_rejects_length_mismatch() {
+        let r: Vec<VecColumn> = get_matrix().collect();
+        let v: Vec<VecColumn> = get_matrix().take(3).collect();
+        let bytes = to_bytes(r, Some(v));
+        let result = DecompositionFileFormat::from_reader_validated(bytes.as_slice(), from_reader);
+        assert_eq!(
+            result,
+            Err(LophatError::InvalidFileFormat {
+                reason: "R has 7 columns but V has 3".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn validated_rejects_garbage() {
+        let result =
+            DecompositionFileFormat::from_reader_validated([0xffu8, 0x00].as_slice(), from_reader);
+        assert!(matches!(result, Err(LophatError::InvalidFileFormat { .. })));
+    }
 }
@@ -0,0 +1,51 @@
+use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+use crate::columns::Column;
+
+// Maximum number of columns to reduce when estimating fill
+const FILL_SAMPLE_SIZE: usize = 1000;
+
+/// Estimates the density of the columns of R, after reducing the provided square matrix.
+/// The result lies in `[0, 1]` and is the average number of non-zero entries in a reduced column, as a fraction of the column height.
+///
+/// To keep this cheap, at most 1000 evenly spaced columns are sampled and only the submatrix formed by these columns is reduced, using the [`SerialAlgorithm`].
+/// Hence, this is only an estimate of the fill-in, intended to help choose a column representation (e.g. [`VecColumn`](crate::columns::VecColumn) for low fill and [`BitSetColumn`](crate::columns::BitSetColumn) for high fill).
+pub fn estimate_fill<C: Column>(matrix: &[C]) -> f64 {
+    let height = matrix.len();
+    if height == 0 {
+        return 0.0;
+    }
+    let stride = height.div_ceil(FILL_SAMPLE_SIZE);
+    let sample = matrix.iter().step_by(stride).cloned();
+    let decomp = SerialAlgorithm::init(None).add_cols(sample).decompose();
+    let total_entries: usize = (0..decomp.n_cols())
+        .map(|idx| decomp.get_r_col(idx).entries().count())
+        .sum();
+    total_entries as f64 / (decomp.n_cols() * height) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::VecColumn;
+
+    #[test]
+    fn sparse_matrix_has_low_fill() {
+        // A path graph on 1000 vertices
+        let n = 1000;
+        let vertices = (0..n).map(|_| VecColumn::from((0, vec![])));
+        let edges = (1..n).map(|i| VecColumn::from((1, vec![i - 1, i])));
+        let path: Vec<VecColumn> = vertices.chain(edges).collect();
+        assert!(estimate_fill(&path) < 0.01);
+    }
+
+    #[test]
+    fn dense_matrix_has_high_fill() {
+        // Every column has every entry above the diagonal
+        let dense: Vec<VecColumn> = (0..1000)
+            .map(|j| VecColumn::from((0, (0..j).collect())))
+            .collect();
+        let fill = estimate_fill(&dense);
+        assert!(fill > 0.4 && fill <= 1.0);
+        assert_eq!(estimate_fill::<VecColumn>(&[]), 0.0);
+    }
+}
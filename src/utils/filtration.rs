@@ -0,0 +1,266 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+use hashbrown::HashMap;
+
+use crate::columns::VecColumn;
+use crate::error::LophatError;
+
+use super::SimplexIndex;
+
+/// Sorts the provided simplices into a valid filtration order and builds the corresponding boundary matrix.
+/// * `simplices` - a vector of `(value, vertices)` pairs, where `value` is the filtration value of the simplex and `vertices` are its vertices (in any order).
+///
+/// Ties in filtration value are resolved by placing lower dimensional simplices first, and then by lexicographic order on the (sorted) vertices.
+/// Hence every face appears before its cofaces, provided that the filtration values are monotone.
+///
+/// Returns the boundary matrix, in the sorted order, and the filtration value of each column.
+///
+/// Panics if the face of some simplex is not present, or appears with a larger filtration value than the simplex.
+pub fn order_filtration(simplices: Vec<(f64, Vec<usize>)>) -> (Vec<VecColumn>, Vec<f64>) {
+    let (matrix, values, _) = order_filtration_indexed(simplices);
+    (matrix, values)
+}
+
+/// As for [`order_filtration`] but additionally returns a [`SimplexIndex`], recording the simplex in each column of the boundary matrix.
+pub fn order_filtration_indexed(
+    simplices: Vec<(f64, Vec<usize>)>,
+) -> (Vec<VecColumn>, Vec<f64>, SimplexIndex) {
+    match try_order_filtration_indexed(simplices) {
+        Ok(output) => output,
+        Err(LophatError::MissingFace { .. }) => panic!("Faces should be present in the filtration"),
+        Err(_) => panic!("Faces should not appear after cofaces"),
+    }
+}
+
+/// As for [`order_filtration`] but returns an error, rather than panicking, if the simplices do not form a valid filtration.
+///
+/// Returns [`MissingFace`](LophatError::MissingFace) if the face of some simplex is not present,
+/// or [`InvalidFiltrationOrder`](LophatError::InvalidFiltrationOrder) if some face has a larger filtration value than its coface.
+/// In either case, positions refer to the sorted order.
+pub fn try_order_filtration(
+    simplices: Vec<(f64, Vec<usize>)>,
+) -> Result<(Vec<VecColumn>, Vec<f64>), LophatError> {
+    let (matrix, values, _) = try_order_filtration_indexed(simplices)?;
+    Ok((matrix, values))
+}
+
+fn try_order_filtration_indexed(
+    mut simplices: Vec<(f64, Vec<usize>)>,
+) -> Result<(Vec<VecColumn>, Vec<f64>, SimplexIndex), LophatError> {
+    for (_, vertices) in simplices.iter_mut() {
+        vertices.sort_unstable();
+    }
+    simplices.sort_by(|(value_a, vertices_a), (value_b, vertices_b)| {
+        value_a
+            .total_cmp(value_b)
+            .then(vertices_a.len().cmp(&vertices_b.len()))
+            .then_with(|| vertices_a.cmp(vertices_b))
+    });
+    let index_of: HashMap<&[usize], usize> = simplices
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, vertices))| (vertices.as_slice(), idx))
+        .collect();
+    let matrix = simplices
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, vertices))| {
+            let dimension = vertices.len().saturating_sub(1);
+            let mut boundary: Vec<usize> = if dimension == 0 {
+                vec![]
+            } else {
+                (0..vertices.len())
+                    .map(|removed| {
+                        let face: Vec<usize> = vertices
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(i, v)| (i != removed).then_some(*v))
+                            .collect();
+                        let face_idx = *index_of
+                            .get(face.as_slice())
+                            .ok_or(LophatError::MissingFace { coface: idx })?;
+                        if face_idx > idx {
+                            return Err(LophatError::InvalidFiltrationOrder {
+                                face: face_idx,
+                                coface: idx,
+                            });
+                        }
+                        Ok(face_idx)
+                    })
+                    .collect::<Result<_, _>>()?
+            };
+            boundary.sort_unstable();
+            Ok(VecColumn::from((dimension, boundary)))
+        })
+        .collect::<Result<_, LophatError>>()?;
+    drop(index_of);
+    let (values, simplices) = simplices.into_iter().unzip();
+    Ok((matrix, values, SimplexIndex { simplices }))
+}
+
+/// Reads a filtered simplicial complex in the plain text format exported from a gudhi simplex tree,
+/// in which each line has the form `value v0 v1 ...`, giving the filtration value and then the vertices of a simplex.
+/// Blank lines and lines starting with `#` are ignored.
+///
+/// The simplices are sorted into a valid filtration via [`order_filtration`], which is also responsible for the return value.
+/// Returns an error with kind [`InvalidData`](io::ErrorKind::InvalidData) if some line is malformed,
+/// or if the face of some simplex is not present or appears with a larger filtration value than the simplex.
+pub fn read_gudhi_simplices<R: Read>(reader: R) -> io::Result<(Vec<VecColumn>, Vec<f64>)> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let mut simplices = vec![];
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let simplex = parse_simplex(line)
+            .ok_or_else(|| invalid(format!("Could not parse simplex from line: {line}")))?;
+        simplices.push(simplex);
+    }
+    check_faces(&simplices)?;
+    Ok(order_filtration(simplices))
+}
+
+// Checks that every face of every simplex is present, with a filtration value no larger than that of the simplex
+fn check_faces(simplices: &[(f64, Vec<usize>)]) -> io::Result<()> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let value_of: HashMap<&[usize], f64> = simplices
+        .iter()
+        .map(|(value, vertices)| (vertices.as_slice(), *value))
+        .collect();
+    for (value, vertices) in simplices.iter() {
+        if vertices.len() < 2 {
+            continue;
+        }
+        for removed in 0..vertices.len() {
+            let mut face = vertices.clone();
+            face.remove(removed);
+            match value_of.get(face.as_slice()) {
+                None => {
+                    return Err(invalid(format!(
+                        "Simplex {vertices:?} has missing face {face:?}"
+                    )))
+                }
+                Some(face_value) if face_value > value => {
+                    return Err(invalid(format!(
+                        "Simplex {vertices:?} appears before its face {face:?}"
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+// Parses a line of the form `value v0 v1 ...`, returning the vertices sorted
+fn parse_simplex(line: &str) -> Option<(f64, Vec<usize>)> {
+    let mut tokens = line.split_whitespace();
+    let value = tokens.next()?.parse().ok()?;
+    let mut vertices = tokens
+        .map(|token| token.parse().ok())
+        .collect::<Option<Vec<usize>>>()?;
+    if vertices.is_empty() {
+        return None;
+    }
+    vertices.sort_unstable();
+    Some((value, vertices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::columns::Column;
+
+    #[test]
+    fn ties_put_faces_first() {
+        // A filled triangle, all appearing at the same time, apart from one vertex
+        let simplices = vec![
+            (0.0, vec![0, 1, 2]),
+            (0.0, vec![1, 2]),
+            (0.0, vec![2, 0]),
+            (0.0, vec![0, 1]),
+            (0.0, vec![2]),
+            (0.0, vec![1]),
+            (-1.0, vec![0]),
+        ];
+        let (matrix, values) = order_filtration(simplices);
+        assert_eq!(values, vec![-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let expected: Vec<VecColumn> = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![0, 2]),
+            (1, vec![1, 2]),
+            (2, vec![3, 4, 5]),
+        ]
+        .into_iter()
+        .map(VecColumn::from)
+        .collect();
+        assert_eq!(matrix, expected);
+        // Every face precedes its coface
+        for (idx, col) in matrix.iter().enumerate() {
+            assert!(col.entries().all(|face| face < idx));
+        }
+    }
+
+    // A filled triangle whose edges appear at 1, 2 and 3 and whose interior appears at 4
+    const TRIANGLE_FIXTURE: &str =
+        "# value vertices\n0 0\n0 1\n0 2\n1 0 1\n2 1 2\n3 2 0\n4 0 1 2\n";
+
+    #[test]
+    fn reads_gudhi_simplices() {
+        use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+        use crate::utils::PersistenceDiagram;
+        use hashbrown::HashSet;
+
+        let (matrix, values) = read_gudhi_simplices(TRIANGLE_FIXTURE.as_bytes()).unwrap();
+        assert_eq!(values, vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(matrix[5], VecColumn::from((1, vec![0, 2])));
+        assert_eq!(matrix[6], VecColumn::from((2, vec![3, 4, 5])));
+        let diagram = SerialAlgorithm::init(None)
+            .add_cols(matrix.into_iter())
+            .decompose()
+            .diagram();
+        let expected = PersistenceDiagram {
+            unpaired: HashSet::from_iter(vec![0]),
+            paired: HashSet::from_iter(vec![(1, 3), (2, 4), (5, 6)]),
+        };
+        assert_eq!(diagram, expected);
+    }
+
+    #[test]
+    fn gudhi_simplices_reject_missing_faces() {
+        let kind = |text: &str| read_gudhi_simplices(text.as_bytes()).unwrap_err().kind();
+        assert_eq!(kind("0 0\n1 0 1\n"), io::ErrorKind::InvalidData);
+        assert_eq!(kind("0 0\n2 1\n1 0 1\n"), io::ErrorKind::InvalidData);
+        assert_eq!(kind("0 0\nzero 1\n"), io::ErrorKind::InvalidData);
+        assert_eq!(kind("0\n"), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn try_order_filtration_reports_errors() {
+        assert_eq!(
+            try_order_filtration(vec![(0.0, vec![0]), (1.0, vec![0, 1])]),
+            Err(LophatError::MissingFace { coface: 1 })
+        );
+        // The edge sorts before its vertex 1, which has a larger value
+        assert_eq!(
+            try_order_filtration(vec![(0.0, vec![0]), (2.0, vec![1]), (1.0, vec![0, 1])]),
+            Err(LophatError::InvalidFiltrationOrder { face: 2, coface: 1 })
+        );
+        let simplices = vec![(0.0, vec![0]), (0.0, vec![1]), (1.0, vec![0, 1])];
+        assert_eq!(
+            try_order_filtration(simplices.clone()),
+            Ok(order_filtration(simplices))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_face_panics() {
+        order_filtration(vec![(0.0, vec![0]), (1.0, vec![0, 1])]);
+    }
+}
@@ -0,0 +1,27 @@
+//! Matrices shared between the tests of several modules.
+
+use crate::columns::VecColumn;
+
+/// The boundary matrix of a triangulation of the 2-sphere, as the boundary of a tetrahedron.
+/// The vertices are columns `0..4`, the edges `4..10` and the triangles `10..14`.
+pub(crate) fn build_sphere_triangulation() -> Vec<VecColumn> {
+    vec![
+        (0, vec![]),
+        (0, vec![]),
+        (0, vec![]),
+        (0, vec![]),
+        (1, vec![0, 1]),
+        (1, vec![0, 2]),
+        (1, vec![1, 2]),
+        (1, vec![0, 3]),
+        (1, vec![1, 3]),
+        (1, vec![2, 3]),
+        (2, vec![4, 7, 8]),
+        (2, vec![5, 7, 9]),
+        (2, vec![6, 8, 9]),
+        (2, vec![4, 5, 6]),
+    ]
+    .into_iter()
+    .map(VecColumn::from)
+    .collect()
+}
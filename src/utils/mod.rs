@@ -1,17 +1,59 @@
-//! Utility functions and structs, including persistence diagrams and matrix anti-transposition.
+//! Utility functions and structs, including persistence diagrams, barcodes, representatives, matrix anti-transposition, boundary matrix validation, Euler characteristic curves, reading/writing columns, conversion from other matrix formats and, with the `test-utils` feature, assertions for tests.
 
 mod anti_transpose;
+mod barcode;
+mod boundary_check;
+mod column_io;
+mod cubical;
+#[cfg(feature = "ndarray")]
+mod dense;
 mod diagram;
+mod euler;
 #[cfg(feature = "serde")]
 mod file_format;
+mod fill;
+mod filtration;
+#[cfg(test)]
+pub(crate) mod fixtures;
+#[cfg(feature = "arrow")]
+mod parquet_format;
+mod persistence_image;
+mod representatives;
+mod simplex_index;
+mod sparse;
+#[cfg(feature = "test-utils")]
+mod test_utils;
 
-pub use anti_transpose::anti_transpose;
-pub use diagram::PersistenceDiagram;
+pub use anti_transpose::{
+    anti_transpose, anti_transpose_dims, anti_transpose_parallel, map_cohomology_reps, transpose,
+    AntiTransposeView,
+};
+pub use barcode::{read_barcode_gudhi, write_barcode_gudhi, Bar, Barcode};
+pub use boundary_check::{is_boundary_matrix, is_upper_triangular};
+pub use column_io::{write_column, write_columns, write_r_cols, ColumnReader};
+pub use cubical::{CubicalComplexBuilder, CubicalConstruction};
+#[cfg(feature = "ndarray")]
+pub use dense::from_ndarray;
+pub use diagram::{diagram_to_pivots, PersistenceDiagram};
+pub use euler::{euler_characteristic_curve, euler_characteristic_from_diagram};
+pub use fill::estimate_fill;
+pub use filtration::{
+    order_filtration, order_filtration_indexed, read_gudhi_simplices, try_order_filtration,
+};
+pub use persistence_image::persistence_image;
+pub use representatives::RepresentativeSet;
+pub use simplex_index::SimplexIndex;
+pub use sparse::from_csr;
 
 #[cfg(feature = "serde")]
 pub use file_format::{
-    clone_to_file_format, clone_to_veccolumn, serialize_algo, DecompositionFileFormat,
+    clone_to_file_format, clone_to_file_format_parallel, clone_to_veccolumn, serialize_algo,
+    serialize_algo_parallel, serialize_algo_with_diagram, DecompositionFileFormat, WithDiagram,
 };
+#[cfg(feature = "arrow")]
+pub use parquet_format::{read_decomposition_parquet, write_decomposition_parquet};
+#[cfg(feature = "test-utils")]
+pub use test_utils::assert_diagram_eq;
 
 use crate::columns::{Column, ColumnMode};
 
@@ -22,3 +64,20 @@ pub(crate) fn set_mode_of_pair<C: Column>(column_pair: &mut (C, Option<C>), mode
         c.set_mode(mode);
     }
 }
+
+/// Helper function to set dimension of both columns
+pub(crate) fn set_dimension_of_pair<C: Column>(column_pair: &mut (C, Option<C>), dimension: usize) {
+    column_pair.0.set_dimension(dimension);
+    if let Some(c) = column_pair.1.as_mut() {
+        c.set_dimension(dimension);
+    }
+}
+
+/// Helper function to copy a pair of columns into another, re-using allocations where possible
+pub(crate) fn clone_pair_from<C: Column>(dest: &mut (C, Option<C>), source: &(C, Option<C>)) {
+    dest.0.clone_from_col(&source.0);
+    match (dest.1.as_mut(), source.1.as_ref()) {
+        (Some(dest_v), Some(source_v)) => dest_v.clone_from_col(source_v),
+        _ => dest.1 = source.1.clone(),
+    }
+}
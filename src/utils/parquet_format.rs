@@ -0,0 +1,249 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter};
+use parquet::file::reader::ChunkReader;
+
+use crate::{
+    algorithms::Decomposition,
+    columns::{Column, VecColumn},
+    utils::DecompositionFileFormat,
+};
+
+// The number of columns of R or V written in each record batch
+const COLS_PER_BATCH: usize = 1 << 16;
+
+fn schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("matrix", DataType::Utf8, false),
+        Field::new("col_index", DataType::UInt64, false),
+        Field::new("row_index", DataType::UInt64, true),
+        Field::new("dimension", DataType::UInt64, false),
+    ]))
+}
+
+// Builds the rows for columns `cols` of a single matrix, labelled by `name`
+fn build_batch<C: Column>(
+    name: &str,
+    cols: impl Iterator<Item = (usize, C)>,
+) -> Result<RecordBatch, arrow_schema::ArrowError> {
+    let mut col_index = vec![];
+    let mut row_index = vec![];
+    let mut dimension = vec![];
+    for (idx, col) in cols {
+        let mut entries: Vec<usize> = col.entries().collect();
+        entries.sort_unstable();
+        // An empty column is recorded by a single row with no row_index
+        let rows: Vec<Option<u64>> = if entries.is_empty() {
+            vec![None]
+        } else {
+            entries.into_iter().map(|row| Some(row as u64)).collect()
+        };
+        col_index.extend(std::iter::repeat_n(idx as u64, rows.len()));
+        dimension.extend(std::iter::repeat_n(col.dimension() as u64, rows.len()));
+        row_index.extend(rows);
+    }
+    let matrix = StringArray::from(vec![name; col_index.len()]);
+    let arrays: Vec<ArrayRef> = vec![
+        Arc::new(matrix),
+        Arc::new(UInt64Array::from(col_index)),
+        Arc::new(UInt64Array::from(row_index)),
+        Arc::new(UInt64Array::from(dimension)),
+    ];
+    RecordBatch::try_new(schema(), arrays)
+}
+
+/// Writes the R and V matrices of `decomposition` to `writer` as a Parquet file, e.g. for reading with pandas or polars.
+///
+/// The table has one row per non-zero entry, with columns
+/// * `matrix` - either `"R"` or `"V"`;
+/// * `col_index` - the index of the column within the matrix;
+/// * `row_index` - the row of the entry;
+/// * `dimension` - the dimension of the column.
+///
+/// An empty column is recorded by a single row in which `row_index` is null.
+/// Rows are sorted by `matrix`, then `col_index`, then `row_index`, so each row group covers a contiguous range of columns.
+/// The persistence diagram is not stored; read the file with [`read_decomposition_parquet`] and recompute it.
+///
+/// Requires the `arrow` feature.
+/// Returns an error if `writer` fails.
+pub fn write_decomposition_parquet<C, D, W>(decomposition: &D, writer: W) -> io::Result<()>
+where
+    C: Column,
+    D: Decomposition<C>,
+    W: Write + Send,
+{
+    let n_cols = decomposition.n_cols();
+    let mut writer = ArrowWriter::try_new(writer, schema(), None).map_err(io::Error::other)?;
+    let has_v = decomposition.has_v();
+    for chunk_start in (0..n_cols).step_by(COLS_PER_BATCH) {
+        let chunk = chunk_start..n_cols.min(chunk_start + COLS_PER_BATCH);
+        let cols = chunk
+            .clone()
+            .map(|idx| (idx, decomposition.get_r_col_owned(idx)));
+        let batch = build_batch("R", cols).map_err(io::Error::other)?;
+        writer.write(&batch).map_err(io::Error::other)?;
+    }
+    if has_v {
+        for chunk_start in (0..n_cols).step_by(COLS_PER_BATCH) {
+            let chunk = chunk_start..n_cols.min(chunk_start + COLS_PER_BATCH);
+            let cols = chunk.map(|idx| {
+                let v_col = decomposition
+                    .get_v_col_owned(idx)
+                    .expect("V should be maintained for every column");
+                (idx, v_col)
+            });
+            let batch = build_batch("V", cols).map_err(io::Error::other)?;
+            writer.write(&batch).map_err(io::Error::other)?;
+        }
+    }
+    writer.close().map_err(io::Error::other)?;
+    Ok(())
+}
+
+// The dimension and entries of each column read so far, or None if the column has not yet appeared
+type PartialMatrix = Vec<Option<(usize, Vec<usize>)>>;
+
+// Assembles the columns of a matrix, checking that every column appeared
+fn finish_matrix(name: &str, partial: PartialMatrix) -> io::Result<Vec<VecColumn>> {
+    partial
+        .into_iter()
+        .enumerate()
+        .map(|(idx, col)| {
+            let (dimension, mut entries) = col.ok_or_else(|| {
+                invalid_data(format!(
+                    "Column {idx} of {name} does not appear in the table"
+                ))
+            })?;
+            entries.sort_unstable();
+            Ok(VecColumn::from((dimension, entries)))
+        })
+        .collect()
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+// Finds the column with the given name and type in the batch, checking for nulls unless `nullable`
+fn column<'a, A: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+    nullable: bool,
+) -> io::Result<&'a A> {
+    let col = batch
+        .column_by_name(name)
+        .and_then(|col| col.as_any().downcast_ref::<A>())
+        .ok_or_else(|| invalid_data(format!("Table has no column {name} of the expected type")))?;
+    if !nullable && col.null_count() > 0 {
+        return Err(invalid_data(format!("Column {name} contains nulls")));
+    }
+    Ok(col)
+}
+
+/// Reads a decomposition in the format written by [`write_decomposition_parquet`].
+/// The rows may appear in any order.
+///
+/// Requires the `arrow` feature.
+/// Returns an error with kind [`InvalidData`](io::ErrorKind::InvalidData) if the input is not a valid Parquet file,
+/// if some column is missing or has the wrong type, if the rows of a column disagree on its dimension,
+/// or if the resulting decomposition is invalid (see [`from_reader_validated`](DecompositionFileFormat::from_reader_validated)).
+pub fn read_decomposition_parquet<R: ChunkReader + 'static>(
+    reader: R,
+) -> io::Result<DecompositionFileFormat> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(reader)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    // Every column of R and V has at least one row, which bounds the column indices
+    let n_rows = builder.metadata().file_metadata().num_rows() as usize;
+    let batches = builder
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut r: PartialMatrix = vec![];
+    let mut v: PartialMatrix = vec![];
+    let mut has_v = false;
+    for batch in batches {
+        let batch = batch.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let matrix = column::<StringArray>(&batch, "matrix", false)?;
+        let col_index = column::<UInt64Array>(&batch, "col_index", false)?;
+        let row_index = column::<UInt64Array>(&batch, "row_index", true)?;
+        let dimension = column::<UInt64Array>(&batch, "dimension", false)?;
+        for i in 0..batch.num_rows() {
+            let partial = match matrix.value(i) {
+                "R" => &mut r,
+                "V" => {
+                    has_v = true;
+                    &mut v
+                }
+                other => return Err(invalid_data(format!("Unknown matrix {other}"))),
+            };
+            let idx = col_index.value(i) as usize;
+            if idx >= n_rows {
+                return Err(invalid_data(format!(
+                    "Column index {idx} exceeds the number of rows"
+                )));
+            }
+            let dim = dimension.value(i) as usize;
+            if partial.len() <= idx {
+                partial.resize(idx + 1, None);
+            }
+            let (col_dim, entries) = partial[idx].get_or_insert_with(|| (dim, vec![]));
+            if *col_dim != dim {
+                return Err(invalid_data(format!(
+                    "Rows of column {idx} disagree on its dimension"
+                )));
+            }
+            if row_index.is_valid(i) {
+                entries.push(row_index.value(i) as usize);
+            }
+        }
+    }
+    let r = finish_matrix("R", r)?;
+    let v = if has_v {
+        Some(finish_matrix("V", v)?)
+    } else {
+        None
+    };
+    let rvdff = DecompositionFileFormat::new(r, v);
+    rvdff.validate().map_err(|e| invalid_data(e.to_string()))?;
+    Ok(rvdff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{DecompositionAlgo, LockFreeAlgorithm};
+    use crate::options::LoPhatOptions;
+    use crate::utils::clone_to_file_format;
+    use std::fs::File;
+
+    #[test]
+    fn parquet_round_trip() {
+        let matrix = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![0, 2]),
+            (1, vec![1, 2]),
+            (2, vec![3, 4, 5]),
+        ]
+        .into_iter()
+        .map(VecColumn::from);
+        let options = LoPhatOptions {
+            maintain_v: true,
+            ..Default::default()
+        };
+        let decomp = LockFreeAlgorithm::init(Some(options))
+            .add_cols(matrix)
+            .decompose();
+        let path = std::env::temp_dir().join(format!("lophat-{}.parquet", std::process::id()));
+        write_decomposition_parquet(&decomp, File::create(&path).unwrap()).unwrap();
+        let rvdff = read_decomposition_parquet(File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rvdff, clone_to_file_format(&decomp));
+        assert_eq!(rvdff.diagram(), decomp.diagram());
+        assert!(rvdff.has_v());
+    }
+}
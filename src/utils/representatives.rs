@@ -0,0 +1,14 @@
+use hashbrown::HashMap;
+
+/// Stores a representative for every feature in a persistence diagram.
+/// Each representative is a vector of column indices, sorted in increasing order.
+///
+/// Typically constructed via [`all_representatives`](crate::algorithms::Decomposition::all_representatives)
+/// or [`all_representatives_parallel`](crate::algorithms::Decomposition::all_representatives_parallel).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RepresentativeSet {
+    /// For each pairing `(birth, death)`, the entries of column `death` in R.
+    pub paired: HashMap<(usize, usize), Vec<usize>>,
+    /// For each unpaired column `birth`, the entries of column `birth` in V.
+    pub unpaired: HashMap<usize, Vec<usize>>,
+}
@@ -0,0 +1,96 @@
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::column_io::{read_u64, write_u64};
+
+/// Records the simplex corresponding to each column of a boundary matrix, as its sorted vertices.
+///
+/// Store alongside a persisted decomposition, so that column indices (e.g. of representatives) can later be translated back into simplices.
+/// Typically constructed by [`order_filtration_indexed`](crate::utils::order_filtration_indexed).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimplexIndex {
+    /// The vertices of the simplex in each column, sorted in increasing order.
+    pub simplices: Vec<Vec<usize>>,
+}
+
+impl SimplexIndex {
+    /// Returns the vertices of the simplex in column `idx`, or `None` if out of range.
+    pub fn simplex(&self, idx: usize) -> Option<&[usize]> {
+        self.simplices.get(idx).map(|vertices| vertices.as_slice())
+    }
+
+    /// Translates a chain of column indices (e.g. a representative) into the corresponding simplices.
+    ///
+    /// Panics if some index is out of range.
+    pub fn translate(&self, chain: &[usize]) -> Vec<&[usize]> {
+        chain
+            .iter()
+            .map(|&idx| {
+                self.simplex(idx)
+                    .expect("Column index should be within the simplex index")
+            })
+            .collect()
+    }
+
+    /// Writes the index in a compact binary format, read by [`read`](Self::read).
+    ///
+    /// Each simplex is stored as its number of vertices, followed by the vertices.
+    /// All integers are stored as little-endian `u64`, as for [`write_columns`](crate::utils::write_columns).
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for vertices in self.simplices.iter() {
+            write_u64(writer, vertices.len())?;
+            for &vertex in vertices.iter() {
+                write_u64(writer, vertex)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads an index in the format written by [`write`](Self::write), until the reader is exhausted.
+    /// Returns an error if the reader fails or ends part way through a simplex.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut simplices = vec![];
+        while let Some(n_vertices) = read_u64(&mut reader)? {
+            let vertices = (0..n_vertices)
+                .map(|_| read_u64(&mut reader)?.ok_or(io::ErrorKind::UnexpectedEof.into()))
+                .collect::<io::Result<_>>()?;
+            simplices.push(vertices);
+        }
+        Ok(Self { simplices })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::order_filtration_indexed;
+
+    #[test]
+    fn index_round_trip() {
+        let simplices = vec![
+            (1.0, vec![1, 0]),
+            (0.0, vec![0]),
+            (0.0, vec![1]),
+            (2.0, vec![2]),
+            (3.0, vec![2, 1]),
+        ];
+        let (matrix, _, index) = order_filtration_indexed(simplices);
+        assert_eq!(index.simplices.len(), matrix.len());
+        assert_eq!(index.simplex(2), Some(&[0, 1][..]));
+        assert_eq!(index.simplex(4), Some(&[1, 2][..]));
+        assert_eq!(index.simplex(5), None);
+        let mut bytes: Vec<u8> = vec![];
+        index.write(&mut bytes).unwrap();
+        let read = SimplexIndex::read(bytes.as_slice()).unwrap();
+        assert_eq!(read, index);
+        assert_eq!(read.translate(&[0, 4]), vec![&[0][..], &[1, 2][..]]);
+        bytes.truncate(bytes.len() - 4);
+        assert_eq!(
+            SimplexIndex::read(bytes.as_slice()).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
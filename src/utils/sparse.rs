@@ -0,0 +1,79 @@
+use crate::columns::VecColumn;
+
+/// Converts a boundary matrix in compressed sparse row (CSR) format into a vector of columns, suitable for passing to [`add_cols`](crate::algorithms::DecompositionAlgo::add_cols).
+/// * `indptr` - the non-zero entries of row `i` are at `indices[indptr[i]..indptr[i + 1]]`, so `indptr` has one more element than there are rows.
+/// * `indices` - the column index of each non-zero entry.
+/// * `n_cols` - the number of columns of the matrix.
+/// * `dims` - the dimension of each column.
+///
+/// Panics if `indptr` is not a valid index into `indices`, if the length of `dims` differs from `n_cols`, or if a column index is out of range.
+pub fn from_csr(
+    indptr: &[usize],
+    indices: &[usize],
+    n_cols: usize,
+    dims: &[usize],
+) -> Vec<VecColumn> {
+    assert_eq!(
+        dims.len(),
+        n_cols,
+        "Should provide a dimension for every column of the matrix"
+    );
+    assert_eq!(
+        indptr.last().copied().unwrap_or(0),
+        indices.len(),
+        "Final entry of indptr should be the number of non-zero entries"
+    );
+    let mut entries: Vec<Vec<usize>> = vec![vec![]; n_cols];
+    for (row, bounds) in indptr.windows(2).enumerate() {
+        for &col in &indices[bounds[0]..bounds[1]] {
+            assert!(col < n_cols, "Column index should be less than n_cols");
+            entries[col].push(row);
+        }
+    }
+    // Rows are visited in increasing order, so each column is already sorted
+    entries
+        .into_iter()
+        .zip(dims)
+        .map(|(col_entries, &dim)| VecColumn::from((dim, col_entries)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::{Decomposition, DecompositionAlgo, SerialAlgorithm};
+    use crate::columns::build_matrix;
+
+    #[test]
+    fn csr_agrees_with_columns() {
+        // Three vertices, three edges and a triangle
+        let spec = vec![
+            (0, vec![]),
+            (0, vec![]),
+            (0, vec![]),
+            (1, vec![0, 1]),
+            (1, vec![0, 2]),
+            (1, vec![1, 2]),
+            (2, vec![3, 4, 5]),
+        ];
+        let indptr = [0, 2, 4, 6, 7, 8, 9, 9];
+        let indices = [3, 4, 3, 5, 4, 5, 6, 6, 6];
+        let dims: Vec<usize> = spec.iter().map(|(dim, _)| *dim).collect();
+        let from_rows = from_csr(&indptr, &indices, 7, &dims);
+        let from_cols: Vec<VecColumn> = build_matrix(&spec);
+        assert_eq!(from_rows, from_cols);
+        let diagram_of = |matrix: Vec<VecColumn>| {
+            SerialAlgorithm::init(None)
+                .add_cols(matrix.into_iter())
+                .decompose()
+                .diagram()
+        };
+        assert_eq!(diagram_of(from_rows), diagram_of(from_cols));
+    }
+
+    #[test]
+    #[should_panic]
+    fn indptr_must_cover_indices() {
+        from_csr(&[0, 1], &[1, 0], 2, &[0, 0]);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt::Debug;
+
+use hashbrown::HashSet;
+
+use super::PersistenceDiagram;
+
+/// Asserts that two persistence diagrams are equal, ignoring the order of their sets.
+///
+/// On mismatch, panics with a message listing the pairs and unpaired columns which appear in only one of the diagrams, sorted, rather than the full diagrams.
+/// Intended for writing tests against a known barcode; requires the `test-utils` feature.
+#[track_caller]
+pub fn assert_diagram_eq(computed: &PersistenceDiagram, expected: &PersistenceDiagram) {
+    if let Some(message) = diagram_mismatch(computed, expected) {
+        panic!("{message}");
+    }
+}
+
+// Describes the differences between the diagrams, or returns None if they are equal
+fn diagram_mismatch(
+    computed: &PersistenceDiagram,
+    expected: &PersistenceDiagram,
+) -> Option<String> {
+    if computed == expected {
+        return None;
+    }
+    let mut lines = vec!["Persistence diagrams differ".to_string()];
+    push_differences(&mut lines, "pairs", &computed.paired, &expected.paired);
+    push_differences(
+        &mut lines,
+        "unpaired",
+        &computed.unpaired,
+        &expected.unpaired,
+    );
+    Some(lines.join("\n"))
+}
+
+// Appends a line for each side of the symmetric difference which is non-empty
+fn push_differences<T: Ord + Debug + Eq + std::hash::Hash>(
+    lines: &mut Vec<String>,
+    name: &str,
+    computed: &HashSet<T>,
+    expected: &HashSet<T>,
+) {
+    for (side, difference) in [
+        ("computed", computed.difference(expected)),
+        ("expected", expected.difference(computed)),
+    ] {
+        let mut difference: Vec<&T> = difference.collect();
+        if difference.is_empty() {
+            continue;
+        }
+        difference.sort_unstable();
+        lines.push(format!("  {name} only in {side}: {difference:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagram(unpaired: Vec<usize>, paired: Vec<(usize, usize)>) -> PersistenceDiagram {
+        PersistenceDiagram {
+            unpaired: HashSet::from_iter(unpaired),
+            paired: HashSet::from_iter(paired),
+        }
+    }
+
+    #[test]
+    fn mismatch_lists_symmetric_difference() {
+        let computed = diagram(vec![0, 13], vec![(1, 4), (2, 5), (9, 11)]);
+        let expected = diagram(vec![0], vec![(2, 5), (1, 4), (3, 7), (8, 10)]);
+        assert_diagram_eq(&computed, &computed.clone());
+        assert_eq!(
+            diagram_mismatch(&computed, &expected).unwrap(),
+            [
+                "Persistence diagrams differ",
+                "  pairs only in computed: [(9, 11)]",
+                "  pairs only in expected: [(3, 7), (8, 10)]",
+                "  unpaired only in computed: [13]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unpaired only in expected: [2]")]
+    fn assert_panics_on_mismatch() {
+        assert_diagram_eq(&diagram(vec![], vec![]), &diagram(vec![2], vec![]));
+    }
+}
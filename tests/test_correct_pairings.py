@@ -1,4 +1,4 @@
-from lophat import compute_pairings, compute_pairings_with_reps
+from lophat import betti_numbers, compute_pairings, compute_pairings_with_reps
 
 def test_2_simplex():
     matrix = [
@@ -17,4 +17,37 @@ def test_2_simplex():
     assert dgm.unpaired == correct_unpaired
     dgm_with_reps = compute_pairings_with_reps(matrix)
     assert set(dgm_with_reps.paired) == correct_paired
-    assert set(dgm_with_reps.unpaired) == correct_unpaired 
\ No newline at end of file
+    assert set(dgm_with_reps.unpaired) == correct_unpaired 
+
+def torus_boundary_matrix():
+    # Triangulate the torus as a 3x3 grid with opposite sides identified
+    n = 3
+    vertices = [(i, j) for i in range(n) for j in range(n)]
+    triangles = set()
+    for i in range(n):
+        for j in range(n):
+            a = (i, j)
+            b = ((i + 1) % n, j)
+            c = (i, (j + 1) % n)
+            d = ((i + 1) % n, (j + 1) % n)
+            triangles.add(frozenset([a, b, d]))
+            triangles.add(frozenset([a, c, d]))
+    edges = {frozenset(pair) for tri in triangles for pair in _pairs(tri)}
+    simplices = [frozenset([v]) for v in vertices] + list(edges) + list(triangles)
+    index = {simplex: idx for idx, simplex in enumerate(simplices)}
+    matrix = []
+    for simplex in simplices:
+        faces = [simplex - {v} for v in simplex] if len(simplex) > 1 else []
+        matrix.append((len(simplex) - 1, sorted(index[face] for face in faces)))
+    return matrix
+
+
+def _pairs(tri):
+    tri = list(tri)
+    return [(tri[0], tri[1]), (tri[0], tri[2]), (tri[1], tri[2])]
+
+
+def test_torus_betti_numbers():
+    matrix = torus_boundary_matrix()
+    assert betti_numbers(matrix) == {0: 1, 1: 2, 2: 1}
+    assert betti_numbers(col for col in matrix) == {0: 1, 1: 2, 2: 1}
@@ -0,0 +1,24 @@
+import os
+import pytest
+
+gudhi = pytest.importorskip("gudhi")
+
+FIXTURE = os.path.join(os.path.dirname(__file__), "fixtures", "triangle_gudhi.pers")
+
+
+# Regenerates the fixture read by the Rust barcode tests and checks that it is unchanged.
+# Pairs of equal length may be written in either order, so lines are compared as sets.
+def test_triangle_fixture_matches_gudhi(tmp_path):
+    st = gudhi.SimplexTree()
+    for vertex in range(3):
+        st.insert([vertex], filtration=0.0)
+    st.insert([0, 1], filtration=1.0)
+    st.insert([1, 2], filtration=2.0)
+    st.insert([0, 2], filtration=3.0)
+    st.insert([0, 1, 2], filtration=4.0)
+    st.compute_persistence(homology_coeff_field=2)
+    output = tmp_path / "triangle_gudhi.pers"
+    st.write_persistence_diagram(str(output))
+    with open(FIXTURE) as f:
+        expected = set(f.read().splitlines())
+    assert set(output.read_text().splitlines()) == expected